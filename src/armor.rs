@@ -0,0 +1,276 @@
+//! PEM-like armored blocks of hex.
+//!
+//! Keys and blobs are often shipped as hex wrapped in `-----BEGIN X-----` and
+//! `-----END X-----` lines, split into lines of fixed width, and optionally
+//! followed by a checksum line (`=` and the six-digit CRC-24 of the data, as
+//! in OpenPGP armor).  `Armor` produces and parses this framing:
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # use serde_hex::armor::Armor;
+//! # use serde_hex::StrictCap;
+//! # fn main() {
+//! let armor = Armor::<StrictCap>::new("SECRET KEY").width(8).checksum(true);
+//! let text = armor.encode(&[0xde, 0xad, 0xbe, 0xef, 0x01]);
+//! assert_eq!(
+//!     text,
+//!     "-----BEGIN SECRET KEY-----\nDEADBEEF\n01\n=91A6DB\n-----END SECRET KEY-----\n"
+//! );
+//! assert_eq!(armor.decode(&text).unwrap(), vec![0xde, 0xad, 0xbe, 0xef, 0x01]);
+//! # }
+//! ```
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use convert::encode;
+use std::marker::PhantomData;
+use std::{cmp, error, fmt};
+use types::ParseHexError;
+use utils;
+use StrictCap;
+
+/// Framing for armored hex blocks with a given label.
+///
+/// Digits are emitted in the case selected by `C` (the prefix and compact
+/// options don't apply), and either case is accepted when decoding.  Lines
+/// are 64 characters wide unless set with `width`.
+pub struct Armor<'a, C = Strict> {
+    label: &'a str,
+    width: usize,
+    checksum: bool,
+    conf: PhantomData<C>,
+}
+
+impl<'a, C: HexConf> Armor<'a, C> {
+    /// framing for blocks labeled `label` (e.g. `PRIVATE KEY`).
+    pub fn new(label: &'a str) -> Self {
+        Armor {
+            label,
+            width: 64,
+            checksum: false,
+            conf: PhantomData,
+        }
+    }
+
+    /// set the number of hex characters per line (zero is treated as one).
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = cmp::max(width, 1);
+        self
+    }
+
+    /// set whether a checksum line is emitted, and required when decoding.
+    /// a checksum line is always verified if present.
+    pub fn checksum(mut self, checksum: bool) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    /// wrap the hex encoding of `data` in an armored block.
+    pub fn encode(&self, data: &[u8]) -> String {
+        let hex = if C::withcap() {
+            encode::<StrictCap>(data)
+        } else {
+            encode::<Strict>(data)
+        };
+        let mut out = String::with_capacity(hex.len() + hex.len() / self.width + 64);
+        out.push_str(&format!("-----BEGIN {}-----\n", self.label));
+        let mut rest = hex.as_str();
+        while !rest.is_empty() {
+            // hex is ascii, so every index is a char boundary.
+            let (line, tail) = rest.split_at(cmp::min(self.width, rest.len()));
+            out.push_str(line);
+            out.push('\n');
+            rest = tail;
+        }
+        if self.checksum {
+            let sum = crc24(data);
+            if C::withcap() {
+                out.push_str(&format!("={:06X}\n", sum));
+            } else {
+                out.push_str(&format!("={:06x}\n", sum));
+            }
+        }
+        out.push_str(&format!("-----END {}-----\n", self.label));
+        out
+    }
+
+    /// parse an armored block, checking its label and (if present, or
+    /// required) its checksum.  blank lines, surrounding whitespace, and
+    /// anything after the `END` line are ignored, and lines may be of any
+    /// width.  the index of a `Char` error is relative to the body with
+    /// line breaks removed.
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, ArmorError> {
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        match lines.next().and_then(|line| boundary(line, "BEGIN")) {
+            Some(label) if label == self.label => {}
+            Some(label) => return Err(ArmorError::Label(label.into())),
+            None => return Err(ArmorError::MissingBegin),
+        }
+        let mut body = Vec::new();
+        let rslt = self.decode_body(&mut lines, &mut body);
+        utils::scrub(&mut body);
+        rslt
+    }
+
+    // collect the body lines into `body`, and decode them.
+    fn decode_body<'t, I>(&self, lines: &mut I, body: &mut Vec<u8>) -> Result<Vec<u8>, ArmorError>
+    where
+        I: Iterator<Item = &'t str>,
+    {
+        let mut sum = None;
+        loop {
+            let line = lines.next().ok_or(ArmorError::MissingEnd)?;
+            if let Some(label) = boundary(line, "END") {
+                if label != self.label {
+                    return Err(ArmorError::Label(label.into()));
+                }
+                break;
+            }
+            if sum.is_some() {
+                // the checksum must be the last line before `END`.
+                return Err(ArmorError::MissingEnd);
+            }
+            if let Some(digits) = line.strip_prefix('=') {
+                let mut buf = [0u8; 3];
+                utils::fromhex_with::<Strict>(&mut buf, digits.as_bytes())?;
+                sum = Some(u32::from_be_bytes([0, buf[0], buf[1], buf[2]]));
+            } else {
+                body.extend_from_slice(line.as_bytes());
+            }
+        }
+        let mut data = utils::fromhex_vec_with::<Strict>(body)?;
+        let rslt = match sum {
+            Some(expect) => {
+                let actual = crc24(&data);
+                if expect != actual {
+                    Err(ArmorError::Checksum { expect, actual })
+                } else {
+                    Ok(())
+                }
+            }
+            None if self.checksum => Err(ArmorError::MissingChecksum),
+            None => Ok(()),
+        };
+        if let Err(err) = rslt {
+            // the decoded body is dropped, so scrub it first.
+            utils::scrub(&mut data);
+            return Err(err);
+        }
+        Ok(data)
+    }
+}
+
+impl<'a, C> Clone for Armor<'a, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, C> Copy for Armor<'a, C> {}
+
+/// error raised when parsing an armored block.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ArmorError {
+    /// the block did not begin with a `-----BEGIN ...-----` line
+    MissingBegin,
+    /// the block did not end with a `-----END ...-----` line (or had lines
+    /// after the checksum)
+    MissingEnd,
+    /// a `BEGIN` or `END` line had an unexpected label
+    Label(String),
+    /// a checksum was required but not present
+    MissingChecksum,
+    /// the checksum did not match the data
+    Checksum {
+        /// checksum found in the block
+        expect: u32,
+        /// checksum of the decoded data
+        actual: u32,
+    },
+    /// the body (or checksum) was not valid hex
+    Parsing(ParseHexError),
+}
+
+impl fmt::Display for ArmorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArmorError::MissingBegin => f.write_str("missing `BEGIN` line"),
+            ArmorError::MissingEnd => f.write_str("missing `END` line"),
+            ArmorError::Label(ref label) => write!(f, "unexpected label `{}`", label),
+            ArmorError::MissingChecksum => f.write_str("missing checksum"),
+            ArmorError::Checksum { expect, actual } => write!(
+                f,
+                "checksum mismatch: expected `{:06x}`, got `{:06x}`",
+                expect, actual
+            ),
+            ArmorError::Parsing(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for ArmorError {}
+
+impl From<ParseHexError> for ArmorError {
+    fn from(err: ParseHexError) -> Self {
+        ArmorError::Parsing(err)
+    }
+}
+
+// label of a `-----{kind} {label}-----` line, if `line` is one.
+fn boundary<'t>(line: &'t str, kind: &str) -> Option<&'t str> {
+    line.strip_prefix("-----")?
+        .strip_suffix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')
+}
+
+// CRC-24 of `data`, as used by OpenPGP armor (RFC 4880, section 6.1).
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0x00b7_04ce;
+    for byte in data {
+        crc ^= u32::from(*byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= 0x0186_4cfb;
+            }
+        }
+    }
+    crc & 0x00ff_ffff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc24, Armor, ArmorError};
+    use config::Strict;
+
+    #[test]
+    fn armored() {
+        // check value from RFC 4880's reference implementation.
+        assert_eq!(crc24(b"123456789"), 0x0021_cf02);
+        let armor = Armor::<Strict>::new("BLOB").width(4);
+        let text = armor.encode(&[0xab, 0xcd, 0xef]);
+        assert_eq!(text, "-----BEGIN BLOB-----\nabcd\nef\n-----END BLOB-----\n");
+        let loose = "\n  -----BEGIN BLOB-----\r\nAB\nCDEF\n\n-----END BLOB-----\ntrailer";
+        assert_eq!(armor.decode(loose).unwrap(), vec![0xab, 0xcd, 0xef]);
+        match armor.checksum(true).decode(&text) {
+            Err(ArmorError::MissingChecksum) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let tampered = armor.checksum(true).encode(&[0xab]).replacen("ab", "ac", 1);
+        match armor.decode(&tampered) {
+            Err(ArmorError::Checksum { .. }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Armor::<Strict>::new("KEY").decode(&text) {
+            Err(ArmorError::Label(ref label)) if label == "BLOB" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match armor.decode("-----BEGIN BLOB-----\nab\n") {
+            Err(ArmorError::MissingEnd) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
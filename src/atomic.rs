@@ -0,0 +1,209 @@
+//! Atomic integers.
+//!
+//! The atomic integer types implement `SerHex` for all configs, writing the
+//! value they hold (loaded with `SeqCst`) exactly as the plain integer would
+//! be written, and constructing a fresh atomic when parsed (on targets which
+//! support atomics of that width).  The `Atomic` `with`-target does the same,
+//! but loads with the ordering named by its second parameter, for snapshots
+//! which don't need to synchronize:
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::atomic::{Atomic, Relaxed};
+//! # use serde_hex::{CompactPfx, SerHex, StrictPfx};
+//! # use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+//! #[derive(Serialize, Deserialize)]
+//! struct Stats {
+//!     #[serde(with = "SerHex::<StrictPfx>")]
+//!     flags: AtomicU32,
+//!     #[serde(with = "Atomic::<CompactPfx, Relaxed>")]
+//!     bytes: AtomicU64,
+//! }
+//!
+//! # fn main() {
+//! let stats = Stats { flags: AtomicU32::new(0x11), bytes: AtomicU64::new(0x2a) };
+//! stats.bytes.fetch_add(1, Ordering::Relaxed);
+//! let json = serde_json::to_string(&stats).unwrap();
+//! assert_eq!(json, r#"{"flags":"0x00000011","bytes":"0x2b"}"#);
+//! let stats: Stats = serde_json::from_str(&json).unwrap();
+//! assert_eq!(stats.bytes.load(Ordering::Relaxed), 0x2b);
+//! # }
+//! ```
+use config::{HexConf, Strict};
+use io;
+use serde::{de, Deserializer, Serializer};
+use std::marker::PhantomData;
+use std::sync::atomic;
+#[cfg(target_has_atomic = "16")]
+use std::sync::atomic::AtomicU16;
+#[cfg(target_has_atomic = "32")]
+use std::sync::atomic::AtomicU32;
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicU64;
+#[cfg(target_has_atomic = "8")]
+use std::sync::atomic::AtomicU8;
+#[cfg(target_has_atomic = "ptr")]
+use std::sync::atomic::AtomicUsize;
+use types::Error;
+use SerHex;
+
+/// A memory ordering for loads, named at the type level.
+pub trait LoadOrdering {
+    /// the ordering used to load the value of an atomic.
+    const ORDERING: atomic::Ordering;
+}
+
+macro_rules! orderings {
+    ($($(#[$meta: meta])* $name: ident,)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl LoadOrdering for $name {
+                const ORDERING: atomic::Ordering = atomic::Ordering::$name;
+            }
+        )*
+    };
+}
+
+orderings! {
+    /// Load with `Ordering::Relaxed`.
+    Relaxed,
+    /// Load with `Ordering::Acquire`.
+    Acquire,
+    /// Load with `Ordering::SeqCst`.
+    SeqCst,
+}
+
+/// An atomic integer, which is (de)serialized as the integer it holds.
+pub trait AtomicInt: Sized {
+    /// the integer held by the atomic.
+    type Int;
+
+    /// load the held integer with the given ordering.
+    fn load_int(&self, order: atomic::Ordering) -> Self::Int;
+
+    /// construct an atomic holding `val`.
+    fn from_int(val: Self::Int) -> Self;
+}
+
+macro_rules! impl_atomic {
+    ($(#[$meta: meta])* $atomic: ident($int: ty)) => {
+        $(#[$meta])*
+        impl AtomicInt for $atomic {
+            type Int = $int;
+
+            fn load_int(&self, order: atomic::Ordering) -> $int {
+                self.load(order)
+            }
+
+            fn from_int(val: $int) -> Self {
+                $atomic::new(val)
+            }
+        }
+
+        $(#[$meta])*
+        impl<C> SerHex<C> for $atomic
+        where
+            C: HexConf,
+        {
+            type Error = Error;
+
+            fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+            where
+                D: io::Write,
+            {
+                <$int as SerHex<C>>::into_hex_raw(&self.load(atomic::Ordering::SeqCst), dst)
+            }
+
+            fn encoded_len(&self) -> usize {
+                <$int as SerHex<C>>::encoded_len(&self.load(atomic::Ordering::SeqCst))
+            }
+
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: de::Error,
+            {
+                err.into_de_error(src)
+            }
+
+            fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                <$int as SerHex<C>>::from_hex_raw(src).map($atomic::new)
+            }
+        }
+    };
+}
+
+impl_atomic!(
+    #[cfg(target_has_atomic = "8")]
+    AtomicU8(u8)
+);
+impl_atomic!(
+    #[cfg(target_has_atomic = "16")]
+    AtomicU16(u16)
+);
+impl_atomic!(
+    #[cfg(target_has_atomic = "32")]
+    AtomicU32(u32)
+);
+impl_atomic!(
+    #[cfg(target_has_atomic = "64")]
+    AtomicU64(u64)
+);
+impl_atomic!(
+    #[cfg(target_has_atomic = "ptr")]
+    AtomicUsize(usize)
+);
+
+/// `with`-target which (de)serializes atomic integers as hex under config
+/// `C`, loading them with the ordering `O`.
+pub struct Atomic<C = Strict, O = SeqCst>(PhantomData<(C, O)>);
+
+impl<C: HexConf, O: LoadOrdering> Atomic<C, O> {
+    /// serialize the value currently held by `atomic`.
+    pub fn serialize<T, S>(atomic: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AtomicInt,
+        T::Int: SerHex<C>,
+        S: Serializer,
+    {
+        atomic.load_int(O::ORDERING).serialize(serializer)
+    }
+
+    /// deserialize a new atomic holding the parsed value.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: AtomicInt,
+        T::Int: SerHex<C>,
+        D: Deserializer<'de>,
+    {
+        <T::Int as SerHex<C>>::deserialize(deserializer).map(T::from_int)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AtomicInt;
+    use config::{Compact, StrictCap};
+    use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+    use SerHex;
+
+    #[test]
+    fn atomics() {
+        let atomic = AtomicU16::new(0xabc);
+        let hex = <AtomicU16 as SerHex<StrictCap>>::into_hex(&atomic).unwrap();
+        assert_eq!(hex, "0ABC");
+        let parsed = <AtomicU16 as SerHex<Compact>>::from_hex("abc").unwrap();
+        assert_eq!(parsed.load(Ordering::SeqCst), 0xabc);
+        let parsed = <AtomicUsize as SerHex<Compact>>::from_hex("1f").unwrap();
+        assert_eq!(parsed.load_int(Ordering::Relaxed), 0x1f);
+        assert!(<AtomicU16 as SerHex<Compact>>::from_hex("10000").is_err());
+    }
+}
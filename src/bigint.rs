@@ -0,0 +1,288 @@
+//! Arbitrary-precision unsigned integers.
+//!
+//! `HexUint` is a small big-number type for values which don't fit a
+//! primitive (256-bit balances, nonces and the like), without pulling in a
+//! full bignum crate.  It only supports comparison and conversion to and from
+//! the primitive integers, and implements `SerHex` for all configs: strict
+//! configs write whole bytes, and compact configs trim the leading zero digit.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::{CompactPfx, HexUint, SerHex};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Account {
+//!     #[serde(with = "SerHex::<CompactPfx>")]
+//!     balance: HexUint,
+//! }
+//!
+//! # fn main() {
+//! let json = r#"{"balance":"0x1000000000000000000000000000000000"}"#;
+//! let account: Account = serde_json::from_str(json).unwrap();
+//! assert_eq!(account.balance.bits(), 133);
+//! assert!(account.balance > HexUint::from(u128::MAX));
+//! assert_eq!(serde_json::to_string(&account).unwrap(), json);
+//! # }
+//! ```
+use alloc::vec::Vec;
+use config::{HexConf, IntConf};
+use io;
+use serde::de;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use types::{Error, ParseHexError};
+use utils;
+use SerHex;
+
+/// An arbitrary-precision unsigned integer.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct HexUint {
+    // least significant limb first, with no zero limbs at the end (so that
+    // zero has no limbs, and each value has a single representation).
+    limbs: Vec<u64>,
+}
+
+impl HexUint {
+    /// the value zero.
+    pub const fn zero() -> Self {
+        HexUint { limbs: Vec::new() }
+    }
+
+    /// construct a value from its big-endian bytes (of any length).
+    pub fn from_be_bytes(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| {
+                let mut buf = [0u8; 8];
+                buf[8 - chunk.len()..].copy_from_slice(chunk);
+                u64::from_be_bytes(buf)
+            })
+            .collect();
+        HexUint::from_limbs(limbs)
+    }
+
+    /// the big-endian bytes of this value, without leading zeroes (so zero
+    /// has no bytes).
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect();
+        let lead = bytes.iter().take_while(|b| **b == 0).count();
+        bytes.drain(..lead);
+        bytes
+    }
+
+    /// number of bits needed to represent this value.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 64 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// check if this value is zero.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        HexUint { limbs }
+    }
+
+    // the low 128 bits of this value.
+    fn low_u128(&self) -> u128 {
+        let limb = |idx| u128::from(self.limbs.get(idx).cloned().unwrap_or(0));
+        limb(0) | (limb(1) << 64)
+    }
+}
+
+impl Ord for HexUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (&self.limbs, &other.limbs);
+        a.len()
+            .cmp(&b.len())
+            .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+    }
+}
+
+impl PartialOrd for HexUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for HexUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HexUint({:#x})", self)
+    }
+}
+
+impl fmt::LowerHex for HexUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_limbs(&self.limbs, f, false)
+    }
+}
+
+impl fmt::UpperHex for HexUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_limbs(&self.limbs, f, true)
+    }
+}
+
+// write the limbs of a value as a single hex number.  honours the `#` flag,
+// but not padding.
+fn fmt_limbs(limbs: &[u64], f: &mut fmt::Formatter, cap: bool) -> fmt::Result {
+    if f.alternate() {
+        f.write_str(if cap { "0X" } else { "0x" })?;
+    }
+    let mut limbs = limbs.iter().rev();
+    match (limbs.next(), cap) {
+        (Some(top), false) => write!(f, "{:x}", top)?,
+        (Some(top), true) => write!(f, "{:X}", top)?,
+        (None, _) => return f.write_str("0"),
+    }
+    for limb in limbs {
+        if cap {
+            write!(f, "{:016X}", limb)?;
+        } else {
+            write!(f, "{:016x}", limb)?;
+        }
+    }
+    Ok(())
+}
+
+macro_rules! impl_conversions {
+    ($($int: ty),*) => {
+        $(
+            impl From<$int> for HexUint {
+                fn from(val: $int) -> Self {
+                    let val = val as u128;
+                    HexUint::from_limbs(alloc::vec![val as u64, (val >> 64) as u64])
+                }
+            }
+
+            impl<'a> TryFrom<&'a HexUint> for $int {
+                type Error = ParseHexError;
+
+                /// fails with `ParseHexError::Overflow` if the value doesn't
+                /// fit.
+                fn try_from(val: &'a HexUint) -> Result<Self, Self::Error> {
+                    let bits = <$int>::BITS as usize;
+                    if val.bits() > bits {
+                        let value = Some(val.low_u128()).filter(|_| val.bits() <= 128);
+                        return Err(ParseHexError::Overflow { bits, value });
+                    }
+                    Ok(val.low_u128() as $int)
+                }
+            }
+
+            impl TryFrom<HexUint> for $int {
+                type Error = ParseHexError;
+
+                fn try_from(val: HexUint) -> Result<Self, Self::Error> {
+                    <$int>::try_from(&val)
+                }
+            }
+        )*
+    };
+}
+
+impl_conversions!(u8, u16, u32, u64, u128, usize);
+
+impl<C> SerHex<C> for HexUint
+where
+    C: HexConf,
+{
+    type Error = Error;
+
+    fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+    where
+        D: io::Write,
+    {
+        let mut bytes = self.to_be_bytes();
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+        match C::width() {
+            Some(width) => utils::writehex_width::<IntConf<C>, _>(&bytes, width, dst),
+            None => utils::writehex_with::<IntConf<C>, _>(&bytes, dst),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        let digits = C::width().unwrap_or_else(|| self.bits().div_ceil(8).max(1) * 2);
+        if C::withpfx() {
+            digits + 2
+        } else {
+            digits
+        }
+    }
+
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        err.into_de_error(src)
+    }
+
+    fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        let src = src.as_ref();
+        // a value needs at least one digit, unless the config reads an
+        // empty compact value as zero.
+        let empty = utils::strippfx(src, C::acceptpfx()).is_empty();
+        if empty && !(C::compact() && C::emptyzero()) {
+            return Err(ParseHexError::EmptyInput.into());
+        }
+        let bytes = utils::fromhex_vec_with::<IntConf<C>>(src)?;
+        Ok(HexUint::from_be_bytes(&bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexUint;
+    use config::{Compact, StrictCapPfx, TrimEnd};
+    use std::convert::TryFrom;
+    use types::ParseHexError;
+    use SerHex;
+
+    #[test]
+    fn big_values() {
+        let hex = format!("1{}", "0".repeat(32));
+        let big = <HexUint as SerHex<Compact>>::from_hex(&hex).unwrap();
+        assert_eq!(big.bits(), 129);
+        assert_eq!(format!("{:x}", big), hex);
+        assert!(big > HexUint::from(u128::MAX));
+        assert_eq!(<HexUint as SerHex<Compact>>::into_hex(&big).unwrap(), hex);
+        let hex = <HexUint as SerHex<StrictCapPfx>>::into_hex(&big).unwrap();
+        assert_eq!(hex, format!("0x01{}", "0".repeat(32)));
+        match u128::try_from(big) {
+            Err(ParseHexError::Overflow {
+                bits: 128,
+                value: None,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let small = HexUint::from(0xabcu64);
+        assert_eq!(u16::try_from(&small).unwrap(), 0xabc);
+        assert_eq!(small.to_be_bytes(), vec![0x0a, 0xbc]);
+        assert!(HexUint::zero() < small);
+        let hex = <HexUint as SerHex<TrimEnd>>::into_hex(&HexUint::from(0x100u64)).unwrap();
+        assert_eq!(hex, "100");
+        let zero = <HexUint as SerHex<Compact>>::into_hex(&HexUint::zero()).unwrap();
+        assert_eq!(zero, "0");
+        assert!(<HexUint as SerHex<Compact>>::from_hex("").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! `with`-target for values whose width isn't a whole number of bytes.
+//!
+//! `#[serde(with = "HexBits::<BITS, C>")]` (de)serializes a big-endian
+//! `[u8; N]` holding a `BITS`-bit value (e.g. a 20-bit register field in a
+//! `[u8; 3]`).  Strict configs pad to the nibble width of the value rather
+//! than the byte width of the array (so 20 bits are always 5 digits), and
+//! parsing rejects values which don't fit in `BITS` bits.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::bits::HexBits;
+//! # use serde_hex::{CompactPfx, StrictPfx};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Register {
+//!     #[serde(with = "HexBits::<20, StrictPfx>")]
+//!     base: [u8; 3],
+//!     #[serde(with = "HexBits::<44, CompactPfx>")]
+//!     limit: [u8; 6],
+//! }
+//!
+//! # fn main() {
+//! let reg = Register {
+//!     base: [0x00, 0x12, 0x34],
+//!     limit: [0x00, 0x00, 0x00, 0x00, 0x0f, 0xff],
+//! };
+//! let json = serde_json::to_string(&reg).unwrap();
+//! assert_eq!(json, r#"{"base":"0x01234","limit":"0xfff"}"#);
+//! assert_eq!(serde_json::from_str::<Register>(&json).unwrap(), reg);
+//! let wide = r#"{"base":"0x101234","limit":"0xfff"}"#;
+//! assert!(serde_json::from_str::<Register>(wide).is_err());
+//! # }
+//! ```
+use config::{HexConf, Strict};
+use io;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use types::{Error, ParseHexError};
+use utils::{be_value, fromval, fromvalcaps, intoval, prefix, strippfx};
+
+/// `with`-target which (de)serializes a big-endian `[u8; N]` as a
+/// `BITS`-bit hex value under config `C`.
+///
+/// `BITS` must be non-zero and fit in the array (checked at compile time).
+pub struct HexBits<const BITS: usize, C = Strict>(PhantomData<C>);
+
+impl<const BITS: usize, C: HexConf> HexBits<BITS, C> {
+    // number of hex digits in the strict encoding.
+    const DIGITS: usize = BITS.div_ceil(4);
+
+    /// serialize `src` as hex, failing if it doesn't fit in `BITS` bits.
+    pub fn serialize<S, const N: usize>(src: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::Error;
+        Self::check(src).map_err(S::Error::custom)?;
+        serializer.collect_str(&BitsDisplay::<BITS, C, N>(src, PhantomData))
+    }
+
+    /// deserialize a hex value of at most `BITS` bits.
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let buff: &[u8] = Deserialize::deserialize(deserializer)?;
+        Self::from_hex(buff).map_err(|e| e.into_de_error(buff))
+    }
+
+    /// write the hex encoding of `src` to `dst`.  strict configs write
+    /// exactly `ceil(BITS / 4)` digits, and compact configs trim leading
+    /// zeroes.
+    pub fn into_hex_raw<D, const N: usize>(src: &[u8; N], mut dst: D) -> Result<(), Error>
+    where
+        D: io::Write,
+    {
+        const CHUNK: usize = 64;
+        Self::check(src)?;
+        if C::withpfx() {
+            dst.write_all(prefix::<C>())?;
+        }
+        let nibble = |idx: usize| (src[N - 1 - idx / 2] >> (4 * (idx % 2))) & 0x0f;
+        // nibbles are indexed from the least significant.
+        let mut top = Self::DIGITS;
+        if C::compact() {
+            while top > 1 && nibble(top - 1) == 0 {
+                top -= 1;
+            }
+        }
+        let mut buf = [0u8; CHUNK];
+        let mut len = 0;
+        for idx in (0..top).rev() {
+            buf[len] = if C::withcap() {
+                fromvalcaps(nibble(idx))
+            } else {
+                fromval(nibble(idx))
+            };
+            len += 1;
+            if len == CHUNK || idx == 0 {
+                dst.write_all(&buf[..len])?;
+                len = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// parse a hex value of at most `BITS` bits into a big-endian `[u8; N]`.
+    pub fn from_hex<S, const N: usize>(src: S) -> Result<[u8; N], ParseHexError>
+    where
+        S: AsRef<[u8]>,
+    {
+        let src = src.as_ref();
+        let hex = strippfx(src, C::acceptpfx());
+        let pfx = src.len() - hex.len();
+        let mut buf = [0u8; N];
+        if C::compact() {
+            if hex.is_empty() {
+                if C::emptyzero() {
+                    return Ok(buf);
+                }
+                return Err(ParseHexError::EmptyInput);
+            }
+            if hex.len() > Self::DIGITS {
+                let (min, max, got) = (1, Self::DIGITS, hex.len());
+                return Err(ParseHexError::Range { min, max, got });
+            }
+        } else if hex.len() != Self::DIGITS {
+            return Err(ParseHexError::size(Self::DIGITS, hex.len()));
+        }
+        for (pos, chr) in hex.iter().enumerate() {
+            let val = intoval(*chr).map_err(|e| e.shift(pfx + pos))?;
+            let idx = hex.len() - 1 - pos;
+            buf[N - 1 - idx / 2] |= val << (4 * (idx % 2));
+        }
+        Self::check(&buf)?;
+        Ok(buf)
+    }
+
+    // check that no bits above `BITS` are set.
+    fn check<const N: usize>(src: &[u8; N]) -> Result<(), ParseHexError> {
+        let () = Fits::<BITS, N>::OK;
+        let excess = N * 8 - BITS;
+        let whole = excess / 8;
+        let mask = !(0xffu8 >> (excess % 8));
+        let clear = src[..whole].iter().all(|byte| *byte == 0);
+        if clear && src[whole] & mask == 0 {
+            Ok(())
+        } else {
+            let value = be_value(src);
+            Err(ParseHexError::Overflow { bits: BITS, value })
+        }
+    }
+}
+
+// compile-time check that a `BITS`-bit value fits in `N` bytes.
+struct Fits<const BITS: usize, const N: usize>;
+
+impl<const BITS: usize, const N: usize> Fits<BITS, N> {
+    const OK: () = assert!(BITS > 0 && BITS <= N * 8, "`BITS` must be in `1..=N * 8`");
+}
+
+// formats a checked value straight into the serializer.
+struct BitsDisplay<'a, const BITS: usize, C, const N: usize>(&'a [u8; N], PhantomData<C>);
+
+impl<'a, const BITS: usize, C: HexConf, const N: usize> fmt::Display
+    for BitsDisplay<'a, BITS, C, N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        HexBits::<BITS, C>::into_hex_raw(self.0, io::FmtWriter(f)).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexBits;
+    use config::{Compact, StrictCap, StrictPfx};
+    use types::ParseHexError;
+
+    #[test]
+    fn widths() {
+        let mut out = Vec::new();
+        HexBits::<20, StrictCap>::into_hex_raw(&[0x0a, 0xbc, 0xde], &mut out).unwrap();
+        assert_eq!(out, b"ABCDE");
+        let mut out = Vec::new();
+        HexBits::<12, Compact>::into_hex_raw(&[0x00, 0x0f], &mut out).unwrap();
+        assert_eq!(out, b"f");
+        let base: [u8; 3] = HexBits::<20, StrictPfx>::from_hex("0xabcde").unwrap();
+        assert_eq!(base, [0x0a, 0xbc, 0xde]);
+        let limit: [u8; 2] = HexBits::<12, Compact>::from_hex("1").unwrap();
+        assert_eq!(limit, [0x00, 0x01]);
+        match HexBits::<20, StrictPfx>::from_hex::<_, 3>("0x0abcde") {
+            Err(ParseHexError::Size {
+                expect: 5,
+                actual: 6,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // 11 bits fit in 3 digits, but not every 3-digit value fits in 11 bits.
+        let max: [u8; 2] = HexBits::<11, Compact>::from_hex("7ff").unwrap();
+        assert_eq!(max, [0x07, 0xff]);
+        match HexBits::<11, Compact>::from_hex::<_, 2>("fff") {
+            Err(ParseHexError::Overflow { bits: 11, .. }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(HexBits::<11, Compact>::into_hex_raw(&[0x08, 0x00], Vec::new()).is_err());
+    }
+}
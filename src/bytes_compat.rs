@@ -0,0 +1,126 @@
+//! Interop with [`serde_bytes`](https://crates.io/crates/serde_bytes).
+//!
+//! Fields which use `#[serde(with = "serde_bytes")]` for compact binary
+//! formats can switch to `#[serde(with = "HexOrBytes::<C>")]`, which encodes
+//! them as hex (under config `C`) for human-readable formats, and passes them
+//! through to `serde_bytes` for everything else.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_bytes;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_bytes::ByteBuf;
+//! # use serde_hex::bytes_compat::HexOrBytes;
+//! # use serde_hex::StrictPfx;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Blob {
+//!     #[serde(with = "HexOrBytes::<StrictPfx>")]
+//!     data: ByteBuf,
+//!     #[serde(with = "HexOrBytes::<StrictPfx>")]
+//!     tag: [u8; 2],
+//! }
+//!
+//! # fn main() {
+//! let blob = Blob { data: ByteBuf::from(vec![0xab, 0xcd]), tag: [0, 1] };
+//! let json = serde_json::to_string(&blob).unwrap();
+//! assert_eq!(json, r#"{"data":"0xabcd","tag":"0x0001"}"#);
+//! assert_eq!(serde_json::from_str::<Blob>(&json).unwrap(), blob);
+//! # }
+//! ```
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use serde::{de, Deserializer, Serializer};
+use serde_bytes;
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+use types::ParseHexError;
+use utils;
+
+/// `with`-target which (de)serializes byte containers as hex under config
+/// `C` for human-readable formats, and via `serde_bytes` otherwise.
+///
+/// Serialization accepts anything which `serde_bytes` can serialize and
+/// which derefs to bytes (`Vec<u8>`, `[u8; N]`, `ByteBuf`, `Bytes`, ...).
+/// Deserialization additionally requires an owned container which can be
+/// built from a `Vec<u8>`, so borrowed types such as `&Bytes` are only
+/// supported for serialization.
+pub struct HexOrBytes<C = Strict>(PhantomData<C>);
+
+impl<C: HexConf> HexOrBytes<C> {
+    /// serialize `bytes` as hex, or as bytes for binary formats.
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + AsRef<[u8]> + serde_bytes::Serialize,
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return serde_bytes::serialize(bytes, serializer);
+        }
+        let src = bytes.as_ref();
+        let mut dst = Vec::with_capacity(utils::encoded_len::<C>(src.len()));
+        utils::writehex_with::<C, _>(src, &mut dst)
+            .map_err(<S::Error as ::serde::ser::Error>::custom)?;
+        let hex = ::std::str::from_utf8(&dst).map_err(<S::Error as ::serde::ser::Error>::custom)?;
+        serializer.serialize_str(hex)
+    }
+
+    /// deserialize a hex string, or bytes for binary formats.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<Vec<u8>> + serde_bytes::Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return serde_bytes::deserialize(deserializer);
+        }
+        deserializer.deserialize_str(HexVisitor::<C, T>(PhantomData))
+    }
+}
+
+struct HexVisitor<C, T>(PhantomData<(C, T)>);
+
+impl<'de, C, T> de::Visitor<'de> for HexVisitor<C, T>
+where
+    C: HexConf,
+    T: TryFrom<Vec<u8>>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, src: &str) -> Result<T, E> {
+        let src = src.as_bytes();
+        let bytes = utils::fromhex_vec_with::<C>(src).map_err(|e| e.into_de_error(src))?;
+        T::try_from(bytes).map_err(|_| {
+            let len = utils::strippfx(src, C::acceptpfx()).len();
+            ParseHexError::Length { len }.into_de_error(src)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexOrBytes;
+    use config::CompactPfx;
+    use serde::de::value::{Error, StrDeserializer};
+    use serde::de::IntoDeserializer;
+    use serde_bytes::ByteBuf;
+
+    #[test]
+    fn human_readable() {
+        let de: StrDeserializer<Error> = "0xabc".into_deserializer();
+        let buf: ByteBuf = HexOrBytes::<CompactPfx>::deserialize(de).unwrap();
+        assert_eq!(buf.as_ref(), [0x0a, 0xbc]);
+        let de: StrDeserializer<Error> = "0xabc".into_deserializer();
+        let err = HexOrBytes::<CompactPfx>::deserialize::<[u8; 4], _>(de).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 3, expected a hex string of valid length"
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! Serde-free core of the hex encoding and decoding.
+//!
+//! The functions here take their options as a `ConfDescriptor` value, rather
+//! than a `HexConf` type parameter, and operate purely on slices and writers.
+//! They are the same code paths used by the `SerHex` impls (the
+//! config-generic helpers in `utils` are shims over them), so crates which
+//! want exactly these parsing rules (prefix and compact handling, `padodd`,
+//! `width`, etc.) can use them without serde or the macros.  Options are
+//! usually taken from a built-in config and then adjusted:
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # use serde_hex::codec;
+//! # use serde_hex::{HexConf, StrictPfx};
+//! # fn main() {
+//! let mut opts = StrictPfx::describe();
+//! let mut buf = [0u8; 10];
+//! let len = codec::encode_into(&opts, &mut buf, &[0x00, 0x0a, 0xbc, 0xde]).unwrap();
+//! assert_eq!(&buf[..len], b"0x000abcde");
+//! opts.compact = true;
+//! let len = codec::encode_into(&opts, &mut buf, &[0x00, 0x0a, 0xbc, 0xde]).unwrap();
+//! assert_eq!(&buf[..len], b"0xabcde");
+//! let mut out = [0u8; 4];
+//! codec::decode_into(&opts, &mut out, b"0xabcde").unwrap();
+//! assert_eq!(out, [0x00, 0x0a, 0xbc, 0xde]);
+//! # }
+//! ```
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use config::{CompactZero, ConfDescriptor};
+use io::{self, UninitWriter};
+use std::mem::MaybeUninit;
+use std::{cmp, slice};
+use types::{Error, ParseHexError};
+use utils::{
+    be_value, fromhex, fromhex_conf, fromhex_conf_body, fromval, fromvalcaps, hex_value, intohex,
+    intohex_conf, intohexcaps, intoval, scrub, strippfx,
+};
+#[cfg(feature = "constant-time")]
+use utils::{fromhex_ct, intohex_ct};
+
+/// Number of bytes in the hex encoding of a fixed-size value of `len` bytes
+/// (as by `encode_into`), including any prefix.  Exact for strict options
+/// and options with a `width`; compact output trims leading zeroes, so for
+/// compact options this is the maximum.
+pub fn encoded_len(opts: &ConfDescriptor, len: usize) -> usize {
+    let pfx = if opts.withpfx { 2 } else { 0 };
+    match opts.width {
+        Some(width) if !consttime(opts) => pfx + width,
+        _ => pfx + len * 2,
+    }
+}
+
+/// Number of bytes which the hex string `src` decodes to (as by
+/// `decode_vec`): half the digits after any prefix, with an odd digit
+/// counting as a whole byte.  Doesn't check that `src` is valid.
+pub fn decoded_len(opts: &ConfDescriptor, src: &[u8]) -> usize {
+    strippfx(src, opts.acceptpfx).len().div_ceil(2)
+}
+
+/// Encode the fixed-size value `src` into the front of `buf`, returning the
+/// number of bytes written.  Fails with `ParseHexError::Buffer` if `buf` is
+/// shorter than `encoded_len`, and with `ParseHexError::Overflow` if the
+/// value needs more digits than a fixed `width`.
+pub fn encode_into(opts: &ConfDescriptor, buf: &mut [u8], src: &[u8]) -> Result<usize, Error> {
+    let expect = encoded_len(opts, src.len());
+    if buf.len() < expect {
+        let actual = buf.len();
+        return Err(ParseHexError::Buffer { expect, actual }.into());
+    }
+    if let Some(width) = opts.width {
+        if !consttime(opts) {
+            let mut dst = &mut buf[..expect];
+            write_width(opts, src, width, &mut dst)?;
+            return Ok(expect);
+        }
+    }
+    Ok(encode_unchecked(opts, buf, src))
+}
+
+// body of `encode_into` for options without a `width`, which doesn't check
+// the size of `buf`.
+#[inline]
+pub(crate) fn encode_unchecked(opts: &ConfDescriptor, buf: &mut [u8], src: &[u8]) -> usize {
+    #[cfg(feature = "constant-time")]
+    {
+        if opts.consttime {
+            let pfx = opts.prefix().len();
+            buf[..pfx].copy_from_slice(opts.prefix().as_bytes());
+            let end = pfx + src.len() * 2;
+            intohex_ct(&mut buf[pfx..end], src, opts.withcap);
+            return end;
+        }
+    }
+    let mut compact = opts.compact;
+    let mut src = src;
+    if compact && opts.trimend {
+        // trailing zero bytes are trimmed, and the rest written as strict hex.
+        compact = false;
+        src = &src[..trimmed_len(opts, src)];
+    } else if compact && opts.compactzero != CompactZero::Minimal && src.iter().all(|v| *v == 0) {
+        // zero is written as strict hex, of either one byte or the full width.
+        compact = false;
+        if opts.compactzero == CompactZero::Byte && !src.is_empty() {
+            src = &src[src.len() - 1..];
+        }
+    }
+    let len = intohex_conf(buf, src, compact, opts.withpfx, opts.withcap);
+    if opts.withpfx && opts.pfxcap {
+        buf[1] = b'X';
+    }
+    len
+}
+
+/// Write the hex encoding of the variable-length `src` to `dst`.  Encoding
+/// is done in fixed-size chunks, so no buffer proportional to the length of
+/// `src` is needed.  Compact options trim leading zeroes, as for fixed-size
+/// values, and `width` is ignored.
+pub fn write<D>(opts: &ConfDescriptor, src: &[u8], mut dst: D) -> Result<(), Error>
+where
+    D: io::Write,
+{
+    const CHUNK: usize = 64;
+    let consttime = consttime(opts);
+    let cap = opts.withcap;
+    if opts.withpfx {
+        dst.write_all(opts.prefix().as_bytes())?;
+    }
+    let mut body = src;
+    if opts.compact && opts.trimend && !consttime {
+        body = &src[..trimmed_len(opts, src)];
+    } else if opts.compact && !consttime {
+        // find index and value of first non-zero byte.
+        match src.iter().position(|v| *v > 0u8) {
+            Some(idx) if src[idx] < 0x10 => {
+                let chr = if cap {
+                    fromvalcaps(src[idx])
+                } else {
+                    fromval(src[idx])
+                };
+                dst.write_all(&[chr])?;
+                body = &src[(idx + 1)..];
+            }
+            Some(idx) => body = &src[idx..],
+            None => match opts.compactzero {
+                CompactZero::Full => {}
+                CompactZero::Byte if !src.is_empty() => body = &src[src.len() - 1..],
+                _ => {
+                    dst.write_all(b"0")?;
+                    return Ok(());
+                }
+            },
+        }
+    }
+    let mut buf = [0u8; CHUNK * 2];
+    let mut encode = || -> Result<(), Error> {
+        for chunk in body.chunks(CHUNK) {
+            let hex = &mut buf[..chunk.len() * 2];
+            match () {
+                #[cfg(feature = "constant-time")]
+                () if consttime => intohex_ct(hex, chunk, cap),
+                () if cap => intohexcaps(hex, chunk),
+                () => intohex(hex, chunk),
+            }
+            dst.write_all(hex)?;
+        }
+        Ok(())
+    };
+    let rslt = encode();
+    scrub(&mut buf);
+    rslt
+}
+
+/// Write the hex encoding of the fixed-size `src` to `dst` as exactly
+/// `width` digits (plus any prefix).  Fails with `ParseHexError::Overflow`
+/// if the value needs more than `width` digits.  Constant-time options
+/// ignore `width`, as for `write`.
+pub fn write_width<D>(
+    opts: &ConfDescriptor,
+    src: &[u8],
+    width: usize,
+    mut dst: D,
+) -> Result<(), Error>
+where
+    D: io::Write,
+{
+    const CHUNK: usize = 64;
+    if consttime(opts) {
+        return write(opts, src, dst);
+    }
+    let full = src.len() * 2;
+    let nibble = |idx: usize| {
+        let byte = src[idx / 2];
+        if idx % 2 == 1 {
+            byte & 0x0f
+        } else {
+            byte >> 4
+        }
+    };
+    // nibbles are indexed from the most significant, and the value needs
+    // every digit from the first non-zero one onwards.
+    let start = full.saturating_sub(width);
+    if (0..start).any(|idx| nibble(idx) != 0) {
+        let value = be_value(src);
+        return Err(ParseHexError::Overflow {
+            bits: width * 4,
+            value,
+        }
+        .into());
+    }
+    if opts.withpfx {
+        dst.write_all(opts.prefix().as_bytes())?;
+    }
+    let cap = opts.withcap;
+    let pad = width.saturating_sub(full);
+    let mut buf = [0u8; CHUNK];
+    let mut len = 0;
+    let digits = (0..pad).map(|_| 0).chain((start..full).map(nibble));
+    let encode = || -> Result<(), Error> {
+        for val in digits {
+            buf[len] = if cap { fromvalcaps(val) } else { fromval(val) };
+            len += 1;
+            if len == CHUNK {
+                dst.write_all(&buf)?;
+                len = 0;
+            }
+        }
+        dst.write_all(&buf[..len])?;
+        Ok(())
+    };
+    let rslt = encode();
+    scrub(&mut buf);
+    rslt
+}
+
+/// Decode `src` into the fixed-size `buf`.  Strict options expect exactly
+/// `buf.len() * 2` digits (or `width`, if set), and compact options accept
+/// up to that many, left-padding with zeroes.
+pub fn decode_into(opts: &ConfDescriptor, buf: &mut [u8], src: &[u8]) -> Result<(), ParseHexError> {
+    #[cfg(feature = "constant-time")]
+    {
+        if opts.consttime {
+            return fromhex_ct(buf, strippfx(src, opts.acceptpfx));
+        }
+    }
+    let compact = opts.compact;
+    let pfx = opts.acceptpfx;
+    let hex = strippfx(src, pfx);
+    if compact && opts.emptyzero && hex.is_empty() {
+        for byte in buf.iter_mut() {
+            *byte = 0;
+        }
+        return Ok(());
+    }
+    if let Some(width) = opts.width {
+        let skip = src.len() - hex.len();
+        return decode_width(buf, hex, width, compact).map_err(|e| e.shift(skip));
+    }
+    if compact && opts.trimend {
+        let skip = src.len() - hex.len();
+        return decode_trimmed(buf, hex).map_err(|e| e.shift(skip));
+    }
+    if !compact && opts.padodd && hex.len() + 1 == buf.len() * 2 {
+        // compact decoding of a full-width value pads exactly one nibble.
+        return fromhex_conf(buf, src, true, pfx);
+    }
+    fromhex_conf(buf, src, compact, pfx)
+}
+
+// number of leading bytes of `src` which are kept when trailing zero bytes
+// are trimmed.  zero keeps a single byte, or every byte if the options ask
+// for a full-width zero.
+fn trimmed_len(opts: &ConfDescriptor, src: &[u8]) -> usize {
+    match src.iter().rposition(|v| *v > 0u8) {
+        Some(idx) => idx + 1,
+        None if opts.compactzero == CompactZero::Full => src.len(),
+        None => cmp::min(src.len(), 1),
+    }
+}
+
+// body of `decode_into` for options which trim trailing zero bytes,
+// operating on input with the prefix stripped.  the digits fill the front
+// of `buf`, and the rest is zeroed.
+fn decode_trimmed(buf: &mut [u8], hex: &[u8]) -> Result<(), ParseHexError> {
+    let (max, got) = (buf.len() * 2, hex.len());
+    if got == 0 {
+        return Err(ParseHexError::EmptyInput);
+    }
+    if got > max {
+        return Err(ParseHexError::Range { min: 2, max, got });
+    }
+    if got % 2 == 1 {
+        return Err(ParseHexError::OddLength { len: got });
+    }
+    let (head, tail) = buf.split_at_mut(got / 2);
+    tail.iter_mut().for_each(|byte| *byte = 0);
+    fromhex(head, hex)
+}
+
+// body of `decode_into` for options with a fixed `width`, operating on
+// input with the prefix stripped.  digits beyond the width of `buf` must
+// be zero.
+fn decode_width(
+    buf: &mut [u8],
+    hex: &[u8],
+    width: usize,
+    compact: bool,
+) -> Result<(), ParseHexError> {
+    let full = buf.len() * 2;
+    if compact {
+        let max = cmp::max(width, full);
+        let got = hex.len();
+        if got > max {
+            return Err(ParseHexError::Range { min: 1, max, got });
+        }
+    } else if hex.len() != width {
+        return Err(ParseHexError::size(width, hex.len()));
+    }
+    let excess = hex.len().saturating_sub(full);
+    for (idx, chr) in hex[..excess].iter().enumerate() {
+        if intoval(*chr).map_err(|e| e.shift(idx))? != 0 {
+            return Err(ParseHexError::Overflow {
+                bits: buf.len() * 8,
+                value: hex_value(hex),
+            });
+        }
+    }
+    fromhex_conf_body(buf, &hex[excess..], true).map_err(|e| e.shift(excess))
+}
+
+/// Decode the variable-length hex string `src`.  Strict options accept any
+/// even number of digits (including none), and compact options any non-zero
+/// number (or none, with `emptyzero`), left-padding odd-length input by one
+/// nibble.
+#[cfg(feature = "alloc")]
+pub fn decode_vec(opts: &ConfDescriptor, src: &[u8]) -> Result<Vec<u8>, ParseHexError> {
+    // decoded straight into the spare capacity, so it is never zeroed first.
+    let mut buf = Vec::with_capacity(decoded_len(opts, src));
+    let len = decode_uninit(opts, buf.spare_capacity_mut(), src)?.len();
+    // SAFETY: `decode_uninit` initialized the first `len` bytes.
+    unsafe { buf.set_len(len) };
+    Ok(buf)
+}
+
+/// Encode the fixed-size value `src` into the front of the uninitialized
+/// `buf` (as by `encode_into`), returning the written bytes.  Useful for
+/// large scratch buffers, which then needn't be zeroed first.
+pub fn encode_uninit<'a>(
+    opts: &ConfDescriptor,
+    buf: &'a mut [MaybeUninit<u8>],
+    src: &[u8],
+) -> Result<&'a mut [u8], Error> {
+    let expect = encoded_len(opts, src.len());
+    if buf.len() < expect {
+        let actual = buf.len();
+        return Err(ParseHexError::Buffer { expect, actual }.into());
+    }
+    let mut dst = UninitWriter { buf, len: 0 };
+    match opts.width {
+        Some(width) if !consttime(opts) => write_width(opts, src, width, &mut dst)?,
+        _ => write(opts, src, &mut dst)?,
+    }
+    let UninitWriter { buf, len } = dst;
+    // SAFETY: the writer initialized the first `len` bytes.
+    Ok(unsafe { assume_init(buf, len) })
+}
+
+/// Decode the variable-length hex string `src` (as by `decode_vec`) into
+/// the front of the uninitialized `buf`, returning the decoded bytes.  Fails
+/// with `ParseHexError::Buffer` if `buf` is shorter than `decoded_len`.  On
+/// failure, any bytes already decoded are zeroed.
+pub fn decode_uninit<'a>(
+    opts: &ConfDescriptor,
+    buf: &'a mut [MaybeUninit<u8>],
+    src: &[u8],
+) -> Result<&'a mut [u8], ParseHexError> {
+    let hex = checked_digits(opts, src)?;
+    let skip = src.len() - hex.len();
+    if hex.is_empty() {
+        return Ok(&mut []);
+    }
+    let expect = hex.len().div_ceil(2);
+    if buf.len() < expect {
+        let actual = buf.len();
+        return Err(ParseHexError::Buffer { expect, actual });
+    }
+    let buf = &mut buf[..expect];
+    let rslt = match () {
+        #[cfg(feature = "constant-time")]
+        () if consttime(opts) => decode_uninit_ct(buf, hex),
+        // `buf` is sized to the input, so this pads at most one nibble.
+        () => fromhex_conf_body(buf, hex, true).map_err(|e| e.shift(skip)),
+    };
+    if let Err(err) = rslt {
+        for slot in buf.iter_mut() {
+            slot.write(0);
+        }
+        return Err(err);
+    }
+    // SAFETY: every byte of `buf` was written by the decoding above.
+    Ok(unsafe { assume_init(buf, expect) })
+}
+
+// the digits of the variable-length hex string `src` (as for `decode_vec`),
+// with any prefix stripped, if their number is acceptable to `opts`.  empty
+// input yields no digits, unless the options reject it.  the digits
+// themselves are left to be checked while decoding.
+pub(crate) fn checked_digits<'a>(
+    opts: &ConfDescriptor,
+    src: &'a [u8],
+) -> Result<&'a [u8], ParseHexError> {
+    let compact = opts.compact;
+    let hex = strippfx(src, opts.acceptpfx);
+    if hex.is_empty() {
+        return if !compact || opts.emptyzero {
+            Ok(hex)
+        } else {
+            Err(ParseHexError::EmptyInput)
+        };
+    }
+    let pad = (compact || opts.padodd) && !consttime(opts);
+    if hex.len() % 2 == 1 && !pad {
+        return Err(ParseHexError::OddLength { len: hex.len() });
+    }
+    Ok(hex)
+}
+
+// constant-time body of `decode_uninit`, which decodes through a small stack
+// buffer.  every chunk is decoded even after an error, so that the running
+// time depends only on the length of the input.
+#[cfg(feature = "constant-time")]
+fn decode_uninit_ct(buf: &mut [MaybeUninit<u8>], hex: &[u8]) -> Result<(), ParseHexError> {
+    const CHUNK: usize = 32;
+    let mut chunk = [0u8; CHUNK];
+    let mut rslt = Ok(());
+    for (slots, digits) in buf.chunks_mut(CHUNK).zip(hex.chunks(CHUNK * 2)) {
+        let bytes = &mut chunk[..slots.len()];
+        rslt = rslt.and(fromhex_ct(bytes, digits));
+        for (slot, byte) in slots.iter_mut().zip(bytes.iter()) {
+            slot.write(*byte);
+        }
+    }
+    scrub(&mut chunk);
+    rslt
+}
+
+// view the first `len` bytes of `buf` as initialized.
+//
+// SAFETY: the caller must have initialized the first `len` bytes of `buf`.
+unsafe fn assume_init(buf: &mut [MaybeUninit<u8>], len: usize) -> &mut [u8] {
+    debug_assert!(len <= buf.len());
+    slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, len)
+}
+
+// whether `opts` selects constant-time coding (never, without the feature).
+#[inline]
+pub(crate) fn consttime(opts: &ConfDescriptor) -> bool {
+    #[cfg(feature = "constant-time")]
+    let consttime = opts.consttime;
+    #[cfg(not(feature = "constant-time"))]
+    let consttime = {
+        let _ = opts;
+        false
+    };
+    consttime
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_into, decode_uninit, decode_vec, encode_into, encode_uninit, write};
+    use config::{Compact, CompactPfx, HexConf, StrictCapPfx, Width};
+    use std::mem::MaybeUninit;
+    use types::{Error, ParseHexError};
+
+    #[test]
+    fn explicit_options() {
+        let mut opts = StrictCapPfx::describe();
+        let mut out = Vec::new();
+        write(&opts, &[0x0a, 0xbc], &mut out).unwrap();
+        assert_eq!(out, b"0x0ABC");
+        opts.withcap = false;
+        opts.padodd = true;
+        let mut buf = [0u8; 2];
+        decode_into(&opts, &mut buf, b"0xabc").unwrap();
+        assert_eq!(buf, [0x0a, 0xbc]);
+        match encode_into(&opts, &mut [0u8; 5], &[0x0a, 0xbc]) {
+            Err(Error::Parsing(ParseHexError::Buffer {
+                expect: 6,
+                actual: 5,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let opts = Width::<6, Compact>::describe();
+        let mut buf = [0u8; 8];
+        assert_eq!(encode_into(&opts, &mut buf, &[0x0a, 0xbc]).unwrap(), 6);
+        assert_eq!(&buf[..6], b"000abc");
+        assert_eq!(
+            decode_vec(&Compact::describe(), b"abc").unwrap(),
+            [0x0a, 0xbc]
+        );
+    }
+    #[test]
+    fn uninit_buffers() {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 16];
+        let opts = StrictCapPfx::describe();
+        let hex = encode_uninit(&opts, &mut buf, &[0x00, 0x0a, 0xbc]).unwrap();
+        assert_eq!(hex, b"0x000ABC");
+        let hex = encode_uninit(&CompactPfx::describe(), &mut buf, &[0x00, 0x0a]).unwrap();
+        assert_eq!(hex, b"0xa");
+        let hex = encode_uninit(&Width::<6, Compact>::describe(), &mut buf, &[0xab]).unwrap();
+        assert_eq!(hex, b"0000ab");
+        assert!(encode_uninit(&opts, &mut buf[..7], &[0x00, 0x0a, 0xbc]).is_err());
+        let bytes = decode_uninit(&CompactPfx::describe(), &mut buf, b"0xabcde").unwrap();
+        assert_eq!(bytes, [0x0a, 0xbc, 0xde]);
+        let bytes = decode_uninit(&opts, &mut buf, b"0x").unwrap();
+        assert!(bytes.is_empty());
+        match decode_uninit(&opts, &mut buf, b"0x00zz") {
+            Err(ParseHexError::Char { val: 'z', index: 4 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match decode_uninit(&opts, &mut buf[..1], b"0x0000") {
+            Err(ParseHexError::Buffer {
+                expect: 2,
+                actual: 1,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "constant-time")]
+    #[test]
+    fn uninit_consttime() {
+        use config::StrictCt;
+        let opts = StrictCt::describe();
+        let val: Vec<u8> = (0..100).collect();
+        let hex: String = val.iter().map(|b| format!("{:02x}", b)).collect();
+        let mut buf = [MaybeUninit::<u8>::uninit(); 128];
+        assert_eq!(
+            decode_uninit(&opts, &mut buf, hex.as_bytes()).unwrap(),
+            &val[..]
+        );
+        // a bad digit within the second 64-digit chunk.
+        let mut hex = hex.into_bytes();
+        hex[70] = b'g';
+        match decode_uninit(&opts, &mut buf, &hex) {
+            Err(ParseHexError::Invalid) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // SAFETY: the failed decoding zeroed the first 100 bytes.
+        assert!(buf[..100].iter().all(|b| unsafe { b.assume_init() } == 0));
+    }
+}
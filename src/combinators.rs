@@ -0,0 +1,440 @@
+//! Composable adapters for nested containers of hex values.
+//!
+//! `SerHexOpt` and `SerHexList` cover a single level of nesting.  For deeper
+//! shapes, `As<A>` takes an adapter type `A` which mirrors the shape of the
+//! field, with `HexAs<C>` (or `HexBytes<C>`) in place of each hex-encoded
+//! value and `Same` in place of anything left to its own `Serialize` and
+//! `Deserialize` impls (in the style of
+//! [`serde_with`](https://crates.io/crates/serde_with)).
+//!
+//! | field type                          | adapter                                            |
+//! |-------------------------------------|----------------------------------------------------|
+//! | `Option<Vec<[u8; 32]>>`             | `As::<Option<Vec<HexAs<C>>>>`                      |
+//! | `Vec<Option<[u8; 20]>>`             | `As::<Vec<Option<HexAs<C>>>>`                      |
+//! | `HashMap<String, Option<Vec<u8>>>`  | `As::<HashMap<Same, Option<HexBytes<C>>>>`         |
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::combinators::{As, HexAs, HexBytes, Same};
+//! # use serde_hex::{CompactPfx, StrictPfx};
+//! # use std::collections::HashMap;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Peer {
+//!     #[serde(with = "As::<Option<Vec<HexAs<StrictPfx>>>>")]
+//!     hashes: Option<Vec<[u8; 2]>>,
+//!     #[serde(with = "As::<Vec<Option<HexAs<CompactPfx>>>>")]
+//!     nonces: Vec<Option<u64>>,
+//!     #[serde(with = "As::<HashMap<Same, Option<HexBytes<StrictPfx>>>>")]
+//!     blobs: HashMap<String, Option<Vec<u8>>>,
+//! }
+//!
+//! # fn main() {
+//! let mut blobs = HashMap::new();
+//! blobs.insert("code".to_string(), Some(vec![0x60, 0x80]));
+//! let peer = Peer {
+//!     hashes: Some(vec![[0xab, 0xcd]]),
+//!     nonces: vec![Some(0xff), None],
+//!     blobs,
+//! };
+//! let json = serde_json::to_string(&peer).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"hashes":["0xabcd"],"nonces":["0xff",null],"blobs":{"code":"0x6080"}}"#
+//! );
+//! assert_eq!(serde_json::from_str::<Peer>(&json).unwrap(), peer);
+//! # }
+//! ```
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+use config::{HexConf, Strict};
+#[cfg(all(feature = "indexmap", feature = "std"))]
+use indexmap::{IndexMap, IndexSet};
+#[cfg(feature = "alloc")]
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "alloc")]
+use std::fmt;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hash};
+#[cfg(feature = "alloc")]
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+#[cfg(feature = "alloc")]
+use utils;
+use SerHex;
+
+/// Adapter describing how to (de)serialize values of type `T`.
+///
+/// Implemented by the leaf adapters (`HexAs`, `HexBytes`, `Same`), and by
+/// `Option`, `Vec`, `BTreeMap` and `HashMap` (and, with the `indexmap`
+/// feature, `IndexMap` and `IndexSet`) of adapters for the corresponding
+/// containers.
+pub trait HexAdapter<T> {
+    /// serialize `src` as described by this adapter.
+    fn serialize_as<S>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+
+    /// deserialize a `T` as described by this adapter.
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// `with`-target which (de)serializes a field as described by adapter `A`.
+pub struct As<A>(PhantomData<A>);
+
+impl<A> As<A> {
+    /// serialize `src` as described by `A`.
+    pub fn serialize<T, S>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        A: HexAdapter<T>,
+        S: Serializer,
+    {
+        A::serialize_as(src, serializer)
+    }
+
+    /// deserialize a `T` as described by `A`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        A: HexAdapter<T>,
+        D: Deserializer<'de>,
+    {
+        A::deserialize_as(deserializer)
+    }
+}
+
+/// Leaf adapter which (de)serializes a value via `SerHex<C>`.
+pub struct HexAs<C = Strict>(PhantomData<C>);
+
+impl<C, T> HexAdapter<T> for HexAs<C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn serialize_as<S>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        <T as SerHex<C>>::serialize(src, serializer)
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <T as SerHex<C>>::deserialize(deserializer)
+    }
+}
+
+/// Leaf adapter which (de)serializes a variable-length byte container (e.g.
+/// `Vec<u8>` or `Box<[u8]>`) as a single hex string under config `C`.
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub struct HexBytes<C = Strict>(PhantomData<C>);
+
+#[cfg(feature = "alloc")]
+impl<C, T> HexAdapter<T> for HexBytes<C>
+where
+    C: HexConf,
+    T: AsRef<[u8]> + From<Vec<u8>>,
+{
+    fn serialize_as<S>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let src = src.as_ref();
+        let mut dst = Vec::with_capacity(utils::encoded_len::<C>(src.len()));
+        utils::writehex_with::<C, _>(src, &mut dst)
+            .map_err(<S::Error as ::serde::ser::Error>::custom)?;
+        let rslt = match ::std::str::from_utf8(&dst) {
+            Ok(hex) => serializer.serialize_str(hex),
+            Err(err) => Err(<S::Error as ::serde::ser::Error>::custom(err)),
+        };
+        utils::scrub(&mut dst);
+        rslt
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let src: &[u8] = Deserialize::deserialize(deserializer)?;
+        let bytes = utils::fromhex_vec_with::<C>(src).map_err(|e| e.into_de_error(src))?;
+        Ok(bytes.into())
+    }
+}
+
+/// Leaf adapter which defers to the value's own `Serialize` and
+/// `Deserialize` impls (e.g. for map keys).
+pub struct Same;
+
+impl<T> HexAdapter<T> for Same
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+{
+    fn serialize_as<S>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        src.serialize(serializer)
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+impl<A, T> HexAdapter<Option<T>> for Option<A>
+where
+    A: HexAdapter<T>,
+{
+    fn serialize_as<S>(src: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *src {
+            Some(ref inner) => serializer.serialize_some(&SerAs::<A, T>::new(inner)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let inner: Option<DeAs<A, T>> = Deserialize::deserialize(deserializer)?;
+        Ok(inner.map(|inner| inner.0))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, T> HexAdapter<Vec<T>> for Vec<A>
+where
+    A: HexAdapter<T>,
+{
+    fn serialize_as<S>(src: &Vec<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(src.iter().map(SerAs::<A, T>::new))
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let items: Vec<DeAs<A, T>> = Deserialize::deserialize(deserializer)?;
+        Ok(items.into_iter().map(|item| item.0).collect())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<KA, VA, K, V> HexAdapter<BTreeMap<K, V>> for BTreeMap<KA, VA>
+where
+    KA: HexAdapter<K>,
+    VA: HexAdapter<V>,
+    K: Ord,
+{
+    fn serialize_as<S>(src: &BTreeMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let entries = src
+            .iter()
+            .map(|(key, value)| (SerAs::<KA, K>::new(key), SerAs::<VA, V>::new(value)));
+        serializer.collect_map(entries)
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor::<KA, VA, K, V, BTreeMap<K, V>>(PhantomData))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<KA, VA, K, V, H> HexAdapter<HashMap<K, V, H>> for HashMap<KA, VA>
+where
+    KA: HexAdapter<K>,
+    VA: HexAdapter<V>,
+    K: Eq + Hash,
+    H: BuildHasher + Default,
+{
+    fn serialize_as<S>(src: &HashMap<K, V, H>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let entries = src
+            .iter()
+            .map(|(key, value)| (SerAs::<KA, K>::new(key), SerAs::<VA, V>::new(value)));
+        serializer.collect_map(entries)
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<HashMap<K, V, H>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor::<KA, VA, K, V, HashMap<K, V, H>>(PhantomData))
+    }
+}
+
+// entries of an `IndexMap` are written (and collected) in insertion order, so
+// that hex-keyed maps round-trip exactly.
+#[cfg(all(feature = "indexmap", feature = "std"))]
+impl<KA, VA, K, V, H> HexAdapter<IndexMap<K, V, H>> for IndexMap<KA, VA>
+where
+    KA: HexAdapter<K>,
+    VA: HexAdapter<V>,
+    K: Eq + Hash,
+    H: BuildHasher + Default,
+{
+    fn serialize_as<S>(src: &IndexMap<K, V, H>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let entries = src
+            .iter()
+            .map(|(key, value)| (SerAs::<KA, K>::new(key), SerAs::<VA, V>::new(value)));
+        serializer.collect_map(entries)
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<IndexMap<K, V, H>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor::<KA, VA, K, V, IndexMap<K, V, H>>(PhantomData))
+    }
+}
+
+#[cfg(all(feature = "indexmap", feature = "std"))]
+impl<A, T, H> HexAdapter<IndexSet<T, H>> for IndexSet<A>
+where
+    A: HexAdapter<T>,
+    T: Eq + Hash,
+    H: BuildHasher + Default,
+{
+    fn serialize_as<S>(src: &IndexSet<T, H>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(src.iter().map(SerAs::<A, T>::new))
+    }
+
+    fn deserialize_as<'de, D>(deserializer: D) -> Result<IndexSet<T, H>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(IndexSetVisitor::<A, T, H>(PhantomData))
+    }
+}
+
+// serializes a borrowed value as described by adapter `A`.
+struct SerAs<'a, A, T: 'a>(&'a T, PhantomData<A>);
+
+impl<'a, A, T> SerAs<'a, A, T> {
+    fn new(src: &'a T) -> Self {
+        SerAs(src, PhantomData)
+    }
+}
+
+impl<'a, A, T> Serialize for SerAs<'a, A, T>
+where
+    A: HexAdapter<T>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        A::serialize_as(self.0, serializer)
+    }
+}
+
+// deserializes a value as described by adapter `A`.
+struct DeAs<A, T>(T, PhantomData<A>);
+
+impl<'de, A, T> Deserialize<'de> for DeAs<A, T>
+where
+    A: HexAdapter<T>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        A::deserialize_as(deserializer).map(|inner| DeAs(inner, PhantomData))
+    }
+}
+
+// collects a map into any `FromIterator` container, as described by the
+// key and value adapters.
+#[cfg(feature = "alloc")]
+struct MapVisitor<KA, VA, K, V, M>(PhantomData<(KA, VA, K, V, M)>);
+
+#[cfg(feature = "alloc")]
+impl<'de, KA, VA, K, V, M> de::Visitor<'de> for MapVisitor<KA, VA, K, V, M>
+where
+    KA: HexAdapter<K>,
+    VA: HexAdapter<V>,
+    M: FromIterator<(K, V)>,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        utils::collect_until_err(|| {
+            map.next_entry::<DeAs<KA, K>, DeAs<VA, V>>()
+                .map(|entry| entry.map(|(key, value)| (key.0, value.0)))
+        })
+    }
+}
+
+// collects a sequence into an `IndexSet` in order, rejecting duplicates
+// (which would otherwise be dropped, shifting the later elements).
+#[cfg(all(feature = "indexmap", feature = "std"))]
+struct IndexSetVisitor<A, T, H>(PhantomData<(A, T, H)>);
+
+#[cfg(all(feature = "indexmap", feature = "std"))]
+impl<'de, A, T, H> de::Visitor<'de> for IndexSetVisitor<A, T, H>
+where
+    A: HexAdapter<T>,
+    T: Eq + Hash,
+    H: BuildHasher + Default,
+{
+    type Value = IndexSet<T, H>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of unique values")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: de::SeqAccess<'de>,
+    {
+        let mut set = IndexSet::with_hasher(H::default());
+        while let Some(item) = seq.next_element::<DeAs<A, T>>()? {
+            let index = set.len();
+            if !set.insert(item.0) {
+                return Err(de::Error::custom(format_args!(
+                    "duplicate value at index {}",
+                    index
+                )));
+            }
+        }
+        Ok(set)
+    }
+}
@@ -6,6 +6,8 @@
 //! functions that are marked with `#[inline]`.  This ensures that
 //! the compiler will (usually) optimize away all configuration
 //! checks.
+use std::fmt;
+use std::marker::PhantomData;
 
 /// Trait for supplying configuration to `SerHex`.
 /// This trait takes no `self` parameters, as it is
@@ -28,6 +30,285 @@ pub trait HexConf {
     fn withcap() -> bool {
         false
     }
+    /// function indicating whether parsing accepts (but doesn't require) a
+    /// `0x` prefix.  defaults to `withpfx`, so that configs parse what they
+    /// emit.  overriding it allows e.g. a config which emits bare hex to
+    /// tolerate prefixed input.
+    #[inline]
+    fn acceptpfx() -> bool {
+        Self::withpfx()
+    }
+    /// function indicating whether the prefix is emitted as `0X` rather
+    /// than `0x`, independently of `withcap`.  parsing accepts either form
+    /// of the prefix regardless.  has no effect on unprefixed configs.
+    #[inline]
+    fn pfxcap() -> bool {
+        false
+    }
+    /// function indicating whether compact parsing treats empty input
+    /// (e.g. a bare `0x`) as zero, rather than rejecting it with
+    /// `ParseHexError::EmptyInput`.  has no effect on strict configs.
+    #[inline]
+    fn emptyzero() -> bool {
+        false
+    }
+    /// function indicating whether strict parsing accepts input which is a
+    /// single nibble short (e.g. `0xabc` for a two-byte value), treating it
+    /// as if it were left-padded with a zero.  has no effect on compact
+    /// configs, and only applies to bytearray-style types and integers.
+    #[inline]
+    fn padodd() -> bool {
+        false
+    }
+    /// function indicating a fixed number of digits (not including any
+    /// prefix) for fixed-size types such as integers.  output is left-padded
+    /// with zeroes to exactly this many digits whether or not the config is
+    /// compact, and strict parsing requires exactly this many.  `None` (the
+    /// default) uses the width of the type.  has no effect on constant-time
+    /// configs.
+    #[inline]
+    fn width() -> Option<usize> {
+        None
+    }
+    /// function indicating whether compact configs trim trailing (rather
+    /// than leading) zero bytes, for byte-arrays which hold little-endian
+    /// values.  output keeps whole bytes, and parsing requires them, filling
+    /// in any missing trailing bytes with zero.  has no effect on strict
+    /// configs, on configs with a fixed `width`, or on integers (which are
+    /// big-endian values, so always trim leading zeroes).
+    #[inline]
+    fn trimend() -> bool {
+        false
+    }
+    /// function indicating how compact configs encode a value which is zero.
+    /// defaults to `CompactZero::Minimal` (a single `0`).  has no effect on
+    /// strict configs, or on configs with a fixed `width`.
+    #[inline]
+    fn compactzero() -> CompactZero {
+        CompactZero::Minimal
+    }
+    /// function indicating how integers handle a parsed value which doesn't
+    /// fit (a `ParseHexError::Overflow`).  defaults to `OverflowMode::Error`.
+    #[inline]
+    fn overflow() -> OverflowMode {
+        OverflowMode::Error
+    }
+    /// function indicating whether `SerHex::serialize` always formats hex
+    /// straight into the serializer via `collect_str`, rather than encoding
+    /// values of up to 256 characters into a stack buffer first.  serializers
+    /// which stream strings to their output (e.g. `serde_json` writing to an
+    /// `io::Write`) then never buffer the hex at all.  doesn't change the
+    /// output.
+    #[inline]
+    fn collectstr() -> bool {
+        false
+    }
+    /// function indicating whether to use constant-time (branchless)
+    /// encoding and decoding.  implies strict representation.
+    #[cfg(feature = "constant-time")]
+    #[inline]
+    fn consttime() -> bool {
+        false
+    }
+    /// the options of this config, as a value.  useful for logging which
+    /// encoding is in use, or for tooling which is generic over configs.
+    #[inline]
+    fn describe() -> ConfDescriptor {
+        ConfDescriptor {
+            compact: Self::compact(),
+            withpfx: Self::withpfx(),
+            acceptpfx: Self::acceptpfx(),
+            withcap: Self::withcap(),
+            pfxcap: Self::pfxcap(),
+            emptyzero: Self::emptyzero(),
+            padodd: Self::padodd(),
+            width: Self::width(),
+            trimend: Self::trimend(),
+            compactzero: Self::compactzero(),
+            overflow: Self::overflow(),
+            collectstr: Self::collectstr(),
+            #[cfg(feature = "constant-time")]
+            consttime: Self::consttime(),
+        }
+    }
+}
+
+/// How compact configs encode a value which is zero, as returned by
+/// `HexConf::compactzero`.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactZero, HexConf, SerHex};
+/// struct Quantity;
+/// impl HexConf for Quantity {
+///     fn compact() -> bool { true }
+///     fn withpfx() -> bool { true }
+///     fn compactzero() -> CompactZero { CompactZero::Byte }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(<u32 as SerHex<Quantity>>::into_hex(&0).unwrap(), "0x00");
+/// assert_eq!(<u32 as SerHex<Quantity>>::into_hex(&0xabc).unwrap(), "0xabc");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompactZero {
+    /// a single digit (`0`).
+    Minimal,
+    /// a single byte (`00`).
+    Byte,
+    /// the full width of the value, as under a strict config.
+    Full,
+}
+
+/// How integers handle a parsed value which doesn't fit, as returned by
+/// `HexConf::overflow`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowMode {
+    /// fail with `ParseHexError::Overflow`.
+    Error,
+    /// keep the low bits of the value (i.e. the trailing digits).
+    Wrap,
+    /// clamp to the largest value of the type, or for signed types to the
+    /// smallest if the leading digit of the input has its top bit set.
+    /// signed input which is only sign-extended (e.g. 34 `f` digits for an
+    /// `i128`) is in range, and is truncated as for `Wrap`.
+    Saturate,
+}
+
+/// The options of a `HexConf`, as returned by `HexConf::describe`.
+///
+/// Displays as the name of the matching built-in config, followed by any
+/// other options (e.g. `CompactPfx (emptyzero, width 8)`).
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{AcceptPfx, Compact, CompactPfx, HexConf, StrictCapPfx, Width};
+/// # fn main() {
+/// let desc = StrictCapPfx::describe();
+/// assert!(desc.withcap && !desc.compact);
+/// assert_eq!(desc.to_string(), "StrictCapPfx");
+/// assert_eq!(Width::<8, CompactPfx>::describe().to_string(), "CompactPfx (width 8)");
+/// assert_eq!(AcceptPfx::<Compact>::describe().to_string(), "Compact (acceptpfx)");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ConfDescriptor {
+    /// see `HexConf::compact`.
+    pub compact: bool,
+    /// see `HexConf::withpfx`.
+    pub withpfx: bool,
+    /// see `HexConf::acceptpfx`.
+    pub acceptpfx: bool,
+    /// see `HexConf::withcap`.
+    pub withcap: bool,
+    /// see `HexConf::pfxcap`.
+    pub pfxcap: bool,
+    /// see `HexConf::emptyzero`.
+    pub emptyzero: bool,
+    /// see `HexConf::padodd`.
+    pub padodd: bool,
+    /// see `HexConf::width`.
+    pub width: Option<usize>,
+    /// see `HexConf::trimend`.
+    pub trimend: bool,
+    /// see `HexConf::compactzero`.
+    pub compactzero: CompactZero,
+    /// see `HexConf::overflow`.
+    pub overflow: OverflowMode,
+    /// see `HexConf::collectstr`.
+    pub collectstr: bool,
+    /// see `HexConf::consttime`.
+    #[cfg(feature = "constant-time")]
+    pub consttime: bool,
+}
+
+impl ConfDescriptor {
+    /// the prefix emitted under these options: `0x`, `0X` (with `pfxcap`),
+    /// or nothing for unprefixed options.
+    pub fn prefix(&self) -> &'static str {
+        match (self.withpfx, self.pfxcap) {
+            (false, _) => "",
+            (true, false) => "0x",
+            (true, true) => "0X",
+        }
+    }
+}
+
+impl fmt::Display for ConfDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "constant-time")]
+        let consttime = self.consttime;
+        #[cfg(not(feature = "constant-time"))]
+        let consttime = false;
+        f.write_str(if self.compact { "Compact" } else { "Strict" })?;
+        if consttime {
+            f.write_str("Ct")?;
+        }
+        if self.withcap {
+            f.write_str("Cap")?;
+        }
+        if self.withpfx {
+            f.write_str("Pfx")?;
+        }
+        let mut sep = " (";
+        if self.acceptpfx != self.withpfx {
+            let neg = if self.acceptpfx { "" } else { "no " };
+            write!(f, "{}{}acceptpfx", sep, neg)?;
+            sep = ", ";
+        }
+        if self.pfxcap && self.withpfx {
+            write!(f, "{}pfxcap", sep)?;
+            sep = ", ";
+        }
+        if self.emptyzero {
+            write!(f, "{}emptyzero", sep)?;
+            sep = ", ";
+        }
+        if self.padodd {
+            write!(f, "{}padodd", sep)?;
+            sep = ", ";
+        }
+        if let Some(width) = self.width {
+            write!(f, "{}width {}", sep, width)?;
+            sep = ", ";
+        }
+        if self.trimend {
+            write!(f, "{}trimend", sep)?;
+            sep = ", ";
+        }
+        match self.compactzero {
+            CompactZero::Minimal => {}
+            CompactZero::Byte => {
+                write!(f, "{}zero byte", sep)?;
+                sep = ", ";
+            }
+            CompactZero::Full => {
+                write!(f, "{}zero full", sep)?;
+                sep = ", ";
+            }
+        }
+        match self.overflow {
+            OverflowMode::Error => {}
+            OverflowMode::Wrap => {
+                write!(f, "{}wrap", sep)?;
+                sep = ", ";
+            }
+            OverflowMode::Saturate => {
+                write!(f, "{}saturate", sep)?;
+                sep = ", ";
+            }
+        }
+        if self.collectstr {
+            write!(f, "{}collectstr", sep)?;
+            sep = ", ";
+        }
+        if sep == ", " {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
 }
 
 /// Config indicating a strict representation
@@ -124,3 +405,268 @@ impl HexConf for CompactCapPfx {
         true
     }
 }
+
+/// Config indicating a strict representation with no
+/// capitalization and no prefixing, which is encoded and
+/// decoded in constant time.  Intended for secret material.
+#[cfg(feature = "constant-time")]
+pub struct StrictCt;
+#[cfg(feature = "constant-time")]
+impl HexConf for StrictCt {
+    #[inline]
+    fn consttime() -> bool {
+        true
+    }
+}
+
+/// Config indicating a strict representation with
+/// prefixing but no capitalization, which is encoded and
+/// decoded in constant time.  Intended for secret material.
+#[cfg(feature = "constant-time")]
+pub struct StrictCtPfx;
+#[cfg(feature = "constant-time")]
+impl HexConf for StrictCtPfx {
+    #[inline]
+    fn consttime() -> bool {
+        true
+    }
+    #[inline]
+    fn withpfx() -> bool {
+        true
+    }
+}
+
+// implement every `HexConf` function of a wrapper config except `$skip`
+// by deferring to the wrapped config `$c`, so that each wrapper only spells
+// out the option it changes.
+macro_rules! forward_hexconf {
+    ($c:ident, except $skip:ident) => {
+        forward_hexconf!(@one $c, $skip, compact, bool);
+        forward_hexconf!(@one $c, $skip, withpfx, bool);
+        forward_hexconf!(@one $c, $skip, acceptpfx, bool);
+        forward_hexconf!(@one $c, $skip, withcap, bool);
+        forward_hexconf!(@one $c, $skip, pfxcap, bool);
+        forward_hexconf!(@one $c, $skip, emptyzero, bool);
+        forward_hexconf!(@one $c, $skip, padodd, bool);
+        forward_hexconf!(@one $c, $skip, width, Option<usize>);
+        forward_hexconf!(@one $c, $skip, trimend, bool);
+        forward_hexconf!(@one $c, $skip, compactzero, CompactZero);
+        forward_hexconf!(@one $c, $skip, overflow, OverflowMode);
+        forward_hexconf!(@one $c, $skip, collectstr, bool);
+        #[cfg(feature = "constant-time")]
+        #[inline]
+        fn consttime() -> bool {
+            $c::consttime()
+        }
+    };
+    (@one $c:ident, compact, compact, $t:ty) => {};
+    (@one $c:ident, withpfx, withpfx, $t:ty) => {};
+    (@one $c:ident, acceptpfx, acceptpfx, $t:ty) => {};
+    (@one $c:ident, withcap, withcap, $t:ty) => {};
+    (@one $c:ident, pfxcap, pfxcap, $t:ty) => {};
+    (@one $c:ident, emptyzero, emptyzero, $t:ty) => {};
+    (@one $c:ident, padodd, padodd, $t:ty) => {};
+    (@one $c:ident, width, width, $t:ty) => {};
+    (@one $c:ident, trimend, trimend, $t:ty) => {};
+    (@one $c:ident, compactzero, compactzero, $t:ty) => {};
+    (@one $c:ident, overflow, overflow, $t:ty) => {};
+    (@one $c:ident, collectstr, collectstr, $t:ty) => {};
+    (@one $c:ident, $skip:ident, $name:ident, $t:ty) => {
+        #[inline]
+        fn $name() -> $t {
+            $c::$name()
+        }
+    };
+}
+
+/// Config which behaves like `C`, but emits exactly `W` digits for
+/// fixed-size types (e.g. integers), so that values line up in columns.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactPfx, SerHex, StrictCap, Width};
+/// # fn main() {
+/// assert_eq!(<u64 as SerHex<Width<6, CompactPfx>>>::into_hex(&0xabc).unwrap(), "0x000abc");
+/// assert_eq!(<u16 as SerHex<Width<6, StrictCap>>>::into_hex(&0xabc).unwrap(), "000ABC");
+/// assert!(<u64 as SerHex<Width<2, CompactPfx>>>::into_hex(&0xabc).is_err());
+/// assert_eq!(<u64 as SerHex<Width<6, CompactPfx>>>::from_hex("0xabc").unwrap(), 0xabc);
+/// assert!(<u64 as SerHex<Width<6, StrictCap>>>::from_hex("0ABC").is_err());
+/// # }
+/// ```
+pub struct Width<const W: usize, C = Strict>(PhantomData<C>);
+impl<const W: usize, C: HexConf> HexConf for Width<W, C> {
+    forward_hexconf!(C, except width);
+    #[inline]
+    fn width() -> Option<usize> {
+        Some(W)
+    }
+}
+
+/// Config which behaves like `C`, but emits the prefix as `0X` (whatever
+/// the case of the digits), for systems which require it.  Parsing under
+/// any prefixed config accepts both `0x` and `0X`.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{PfxCap, SerHex, StrictCapPfx, StrictPfx};
+/// # fn main() {
+/// assert_eq!(<u32 as SerHex<PfxCap<StrictCapPfx>>>::into_hex(&0xdeadbeef).unwrap(), "0XDEADBEEF");
+/// assert_eq!(<u32 as SerHex<PfxCap<StrictPfx>>>::into_hex(&0xdeadbeef).unwrap(), "0Xdeadbeef");
+/// assert_eq!(<u32 as SerHex<StrictPfx>>::from_hex("0XDEADBEEF").unwrap(), 0xdeadbeef);
+/// # }
+/// ```
+pub struct PfxCap<C = StrictPfx>(PhantomData<C>);
+impl<C: HexConf> HexConf for PfxCap<C> {
+    forward_hexconf!(C, except pfxcap);
+    #[inline]
+    fn pfxcap() -> bool {
+        true
+    }
+}
+
+/// Config which behaves like `C`, but accepts a `0x` (or `0X`) prefix when
+/// parsing even if `C` doesn't emit one.  Useful for emitting bare hex
+/// while tolerating prefixed input.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{AcceptPfx, Compact, SerHex, Strict};
+/// # fn main() {
+/// assert_eq!(<u16 as SerHex<AcceptPfx<Strict>>>::into_hex(&0xabc).unwrap(), "0abc");
+/// assert_eq!(<u16 as SerHex<AcceptPfx<Strict>>>::from_hex("0x0abc").unwrap(), 0xabc);
+/// assert_eq!(<u16 as SerHex<AcceptPfx<Compact>>>::from_hex("abc").unwrap(), 0xabc);
+/// assert!(<u16 as SerHex<Strict>>::from_hex("0x0abc").is_err());
+/// # }
+/// ```
+pub struct AcceptPfx<C = Strict>(PhantomData<C>);
+impl<C: HexConf> HexConf for AcceptPfx<C> {
+    forward_hexconf!(C, except acceptpfx);
+    #[inline]
+    fn acceptpfx() -> bool {
+        true
+    }
+}
+
+/// Config which behaves like `C`, but integers keep the low bits of a parsed
+/// value which doesn't fit (as hardware registers do), instead of failing.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{Compact, SerHex, StrictPfx, Width, Wrapping};
+/// # fn main() {
+/// assert_eq!(<u16 as SerHex<Wrapping<Compact>>>::from_hex("1abcd").unwrap(), 0xabcd);
+/// assert_eq!(<u8 as SerHex<Wrapping<Width<4, StrictPfx>>>>::from_hex("0x01ff").unwrap(), 0xff);
+/// assert!(<u16 as SerHex<Compact>>::from_hex("1abcd").is_err());
+/// # }
+/// ```
+pub struct Wrapping<C = Compact>(PhantomData<C>);
+impl<C: HexConf> HexConf for Wrapping<C> {
+    forward_hexconf!(C, except overflow);
+    #[inline]
+    fn overflow() -> OverflowMode {
+        OverflowMode::Wrap
+    }
+}
+
+/// Config which behaves like `C`, but integers clamp a parsed value which
+/// doesn't fit to their largest value, instead of failing.  Signed integers
+/// clamp to their smallest value instead if the leading digit has its top
+/// bit set (i.e. the input reads as a wider negative bit pattern), unless
+/// the input is only a sign-extension of an in-range value, which is kept.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactPfx, Saturating, SerHex};
+/// # fn main() {
+/// assert_eq!(<u16 as SerHex<Saturating<CompactPfx>>>::from_hex("0x1abcd").unwrap(), 0xffff);
+/// assert_eq!(<u16 as SerHex<Saturating<CompactPfx>>>::from_hex("0xabc").unwrap(), 0xabc);
+/// let wide = format!("0x8{}", "0".repeat(32));
+/// assert_eq!(<i128 as SerHex<Saturating<CompactPfx>>>::from_hex(&wide).unwrap(), i128::MIN);
+/// let extended = format!("0x{}", "f".repeat(34));
+/// assert_eq!(<i128 as SerHex<Saturating<CompactPfx>>>::from_hex(&extended).unwrap(), -1);
+/// # }
+/// ```
+pub struct Saturating<C = Compact>(PhantomData<C>);
+impl<C: HexConf> HexConf for Saturating<C> {
+    forward_hexconf!(C, except overflow);
+    #[inline]
+    fn overflow() -> OverflowMode {
+        OverflowMode::Saturate
+    }
+}
+
+/// Config which behaves like the compact config `C`, but trims trailing
+/// rather than leading zero bytes, for byte-arrays which hold little-endian
+/// values (e.g. register dumps).  See `HexConf::trimend`.  Only applies to
+/// byte-arrays and slices: integers ignore it, and are always compacted by
+/// trimming their leading zeroes.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactPfx, SerHex, TrimEnd};
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Reg([u8; 4]);
+/// impl_newtype_bytearray!(Reg, 4);
+/// impl_serhex_bytearray!(Reg, 4);
+///
+/// # fn main() {
+/// let reg = Reg([0x34, 0x12, 0x00, 0x00]);
+/// let hex = <Reg as SerHex<TrimEnd<CompactPfx>>>::into_hex(&reg).unwrap();
+/// assert_eq!(hex, "0x3412");
+/// assert_eq!(<Reg as SerHex<TrimEnd<CompactPfx>>>::from_hex(&hex).unwrap(), reg);
+/// # }
+/// ```
+pub struct TrimEnd<C = Compact>(PhantomData<C>);
+impl<C: HexConf> HexConf for TrimEnd<C> {
+    forward_hexconf!(C, except trimend);
+    #[inline]
+    fn trimend() -> bool {
+        true
+    }
+}
+
+/// Config which behaves like `C` with `trimend` cleared.  Used by the integer
+/// impls, whose big-endian bytes keep their trailing zeroes under any config
+/// (so `TrimEnd` only applies to byte-arrays and slices).
+#[doc(hidden)]
+pub struct IntConf<C>(PhantomData<C>);
+impl<C: HexConf> HexConf for IntConf<C> {
+    forward_hexconf!(C, except trimend);
+    #[inline]
+    fn trimend() -> bool {
+        false
+    }
+}
+
+/// Config which behaves like `C`, but always serializes via `collect_str`
+/// (see `HexConf::collectstr`), for large or numerous values written by a
+/// serializer which streams strings.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// # use serde_hex::{CollectStr, CompactPfx, HexConf, SerHex};
+/// #[derive(Serialize)]
+/// struct Block {
+///     #[serde(with = "SerHex::<CollectStr<CompactPfx>>")]
+///     nonce: u64,
+/// }
+///
+/// # fn main() {
+/// let mut out = Vec::new();
+/// serde_json::to_writer(&mut out, &Block { nonce: 0xabc }).unwrap();
+/// assert_eq!(out, br#"{"nonce":"0xabc"}"#);
+/// assert_eq!(CollectStr::<CompactPfx>::describe().to_string(), "CompactPfx (collectstr)");
+/// # }
+/// ```
+pub struct CollectStr<C = Strict>(PhantomData<C>);
+impl<C: HexConf> HexConf for CollectStr<C> {
+    forward_hexconf!(C, except collectstr);
+    #[inline]
+    fn collectstr() -> bool {
+        true
+    }
+}
@@ -0,0 +1,67 @@
+//! One-off conversions between bytes and hex, outside of serde.
+//!
+//! `encode` and `decode` treat bytes as a variable-length value, applying
+//! the same rules as the serde impls of variable-length byte types (see
+//! `impl_serhex_bytevec`) for the same config, so values round-trip between
+//! the two.  Unlike the fixed-size impls they ignore any `width`.  Both are
+//! re-exported at the crate root.
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # use serde_hex::{CompactPfx, StrictPfx};
+//! # fn main() {
+//! let hex = serde_hex::encode::<StrictPfx>(&[0x00, 0xab]);
+//! assert_eq!(hex, "0x00ab");
+//! assert_eq!(serde_hex::encode::<CompactPfx>(&[0x00, 0xab]), "0xab");
+//! assert_eq!(serde_hex::decode::<StrictPfx>(&hex).unwrap(), vec![0x00, 0xab]);
+//! assert!(serde_hex::decode::<StrictPfx>("0xabc").is_err());
+//! # }
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+use config::HexConf;
+use io;
+use types::ParseHexError;
+use utils;
+
+/// Encode `src` as hex under config `C`.
+///
+/// `src` is treated as a variable-length value (as by
+/// `utils::writehex_with`), so compact configs trim leading zeroes and any
+/// `width` is ignored.
+pub fn encode<C: HexConf>(src: impl AsRef<[u8]>) -> String {
+    let src = src.as_ref();
+    let mut dst = String::with_capacity(utils::encoded_len::<C>(src.len()));
+    // writes to a `String` only fail on invalid utf-8, and hex is ascii.
+    let _ = utils::writehex_with::<C, _>(src, io::StringWriter(&mut dst));
+    dst
+}
+
+/// Decode the hex string `src` under config `C`.
+///
+/// Strict configs accept any even number of digits, and compact configs
+/// any non-zero number (as by `utils::fromhex_vec_with`), and any `width`
+/// is ignored.
+pub fn decode<C: HexConf>(src: impl AsRef<[u8]>) -> Result<Vec<u8>, ParseHexError> {
+    utils::fromhex_vec_with::<C>(src.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+    use config::{Compact, Strict, StrictCap, Width};
+    use types::ParseHexError;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(encode::<StrictCap>(b"\x0a\xbc"), "0ABC");
+        assert_eq!(encode::<Compact>([0u8; 3]), "0");
+        assert_eq!(decode::<Compact>("abc").unwrap(), vec![0x0a, 0xbc]);
+        assert_eq!(encode::<Width<8, Strict>>([0xab]), "ab");
+        assert_eq!(decode::<Width<8, Strict>>("ab").unwrap(), vec![0xab]);
+        match decode::<StrictCap>("0ABG") {
+            Err(ParseHexError::Char { val: 'G', index: 3 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! `SerHex` impls for the public keys and signatures of common crypto crates.
+//!
+//! Each crate is supported behind a feature of the same name:
+//!
+//! - `ed25519-dalek`: `VerifyingKey` (32 bytes) and `Signature` (64 bytes).
+//! - `k256`: `PublicKey` and `ecdsa::VerifyingKey` (33 or 65 bytes), and
+//!   `ecdsa::Signature` (64 bytes).
+//! - `secp256k1`: `PublicKey` (33 or 65 bytes), `XOnlyPublicKey` (32 bytes),
+//!   `ecdsa::Signature` and `schnorr::Signature` (64 bytes), and
+//!   `ecdsa::RecoverableSignature` (65 bytes, with the recovery id last).
+//!
+//! Only the strict configs are implemented, since leading zeroes are
+//! significant.  Public keys are encoded in compressed form, and wrapping
+//! them in `Uncompressed` selects the 65-byte form instead; either form is
+//! accepted when decoding.  Well-formed hex which does not decode to a valid
+//! key or signature is rejected with `ParseHexError::Rejected`.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # #[cfg(feature = "secp256k1")]
+//! # extern crate secp256k1;
+//! # #[cfg(feature = "secp256k1")]
+//! # fn main() {
+//! # use serde_hex::{SerHex, StrictPfx};
+//! use secp256k1::PublicKey;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Account {
+//!     #[serde(with = "SerHex::<StrictPfx>")]
+//!     key: PublicKey,
+//! }
+//!
+//! let json = r#"{"key":"0x0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"}"#;
+//! let account: Account = serde_json::from_str(json).unwrap();
+//! assert_eq!(serde_json::to_string(&account).unwrap(), json);
+//! # }
+//! # #[cfg(not(feature = "secp256k1"))]
+//! # fn main() {}
+//! ```
+use config::HexConf;
+use std::ops::{Deref, DerefMut};
+use types::{Error, ParseHexError};
+use utils;
+
+// size of the largest supported encoding, in bytes.
+const MAX_LEN: usize = 65;
+
+/// Adapter which encodes a public key in its uncompressed (65-byte) form,
+/// rather than the default compressed (33-byte) form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uncompressed<T>(pub T);
+
+impl<T> Uncompressed<T> {
+    /// unwrap the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Uncompressed<T> {
+    fn from(inner: T) -> Self {
+        Uncompressed(inner)
+    }
+}
+
+impl<T> Deref for Uncompressed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Uncompressed<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// decode `src` (which must encode one of `lens` bytes) under config `C`,
+// and hand the bytes to `decode` for validation.  size errors report the
+// first (preferred) length.
+fn from_hex_key<C, T, F>(src: &[u8], lens: &[usize], decode: F) -> Result<T, Error>
+where
+    C: HexConf,
+    F: FnOnce(&[u8]) -> Option<T>,
+{
+    let hex = utils::strippfx(src, <C as HexConf>::acceptpfx());
+    let len = match lens.iter().find(|len| **len * 2 == hex.len()) {
+        Some(len) => *len,
+        None => return Err(ParseHexError::size(lens[0] * 2, hex.len()).into()),
+    };
+    let mut buf = [0u8; MAX_LEN];
+    let rslt = match utils::fromhex_with::<C>(&mut buf[..len], src) {
+        Ok(()) => decode(&buf[..len]).ok_or_else(|| ParseHexError::Rejected.into()),
+        Err(err) => Err(err.into()),
+    };
+    utils::scrub(&mut buf);
+    rslt
+}
+
+macro_rules! impl_serhex_key {
+    ($type: ty, $lens: expr, $encode: expr, $decode: expr) => {
+        impl_serhex_key!(
+            @conf $type, $lens, $encode, $decode,
+            Strict, StrictPfx, StrictCap, StrictCapPfx
+        );
+    };
+    (@conf $type: ty, $lens: expr, $encode: expr, $decode: expr, $($conf: ty),*) => {
+        $(
+            impl SerHex<$conf> for $type {
+                type Error = Error;
+
+                fn into_hex_raw<D>(&self, dst: D) -> Result<(), Error>
+                where
+                    D: io::Write,
+                {
+                    let bytes = ($encode)(self);
+                    utils::writehex_with::<$conf, D>(bytes.as_ref(), dst)
+                }
+
+                fn from_hex_raw<S>(src: S) -> Result<Self, Error>
+                where
+                    S: AsRef<[u8]>,
+                {
+                    from_hex_key::<$conf, Self, _>(src.as_ref(), &$lens, $decode)
+                }
+
+                fn encoded_len(&self) -> usize {
+                    $lens[0] * 2 + 2
+                }
+
+                fn de_error<E>(err: Error, src: &[u8]) -> E
+                where
+                    E: ::serde::de::Error,
+                {
+                    err.into_de_error(src)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "ed25519-dalek")]
+mod ed25519 {
+    use super::from_hex_key;
+    use config::{Strict, StrictCap, StrictCapPfx, StrictPfx};
+    use ed25519_dalek::{Signature, VerifyingKey};
+    use io;
+    use std::convert::TryInto;
+    use types::Error;
+    use utils;
+    use SerHex;
+
+    impl_serhex_key!(
+        VerifyingKey,
+        [32],
+        |key: &VerifyingKey| key.to_bytes(),
+        |bytes: &[u8]| VerifyingKey::from_bytes(bytes.try_into().ok()?).ok()
+    );
+
+    impl_serhex_key!(
+        Signature,
+        [64],
+        |sig: &Signature| sig.to_bytes(),
+        |bytes: &[u8]| Signature::from_slice(bytes).ok()
+    );
+}
+
+#[cfg(feature = "k256")]
+mod k256_impls {
+    use super::{from_hex_key, Uncompressed};
+    use config::{Strict, StrictCap, StrictCapPfx, StrictPfx};
+    use io;
+    use k256::ecdsa::{Signature, VerifyingKey};
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use k256::PublicKey;
+    use types::Error;
+    use utils;
+    use SerHex;
+
+    impl_serhex_key!(
+        PublicKey,
+        [33, 65],
+        |key: &PublicKey| key.to_encoded_point(true),
+        |bytes: &[u8]| PublicKey::from_sec1_bytes(bytes).ok()
+    );
+
+    impl_serhex_key!(
+        Uncompressed<PublicKey>,
+        [65, 33],
+        |key: &Uncompressed<PublicKey>| key.to_encoded_point(false),
+        |bytes: &[u8]| PublicKey::from_sec1_bytes(bytes).ok().map(Uncompressed)
+    );
+
+    impl_serhex_key!(
+        VerifyingKey,
+        [33, 65],
+        |key: &VerifyingKey| key.to_encoded_point(true),
+        |bytes: &[u8]| VerifyingKey::from_sec1_bytes(bytes).ok()
+    );
+
+    impl_serhex_key!(
+        Uncompressed<VerifyingKey>,
+        [65, 33],
+        |key: &Uncompressed<VerifyingKey>| key.to_encoded_point(false),
+        |bytes: &[u8]| VerifyingKey::from_sec1_bytes(bytes).ok().map(Uncompressed)
+    );
+
+    impl_serhex_key!(
+        Signature,
+        [64],
+        |sig: &Signature| sig.to_bytes(),
+        |bytes: &[u8]| Signature::from_slice(bytes).ok()
+    );
+}
+
+#[cfg(feature = "secp256k1")]
+mod secp256k1_impls {
+    use super::{from_hex_key, Uncompressed};
+    use config::{Strict, StrictCap, StrictCapPfx, StrictPfx};
+    use io;
+    use secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature};
+    use secp256k1::{schnorr, PublicKey, XOnlyPublicKey};
+    use types::Error;
+    use utils;
+    use SerHex;
+
+    impl_serhex_key!(
+        PublicKey,
+        [33, 65],
+        |key: &PublicKey| key.serialize(),
+        |bytes: &[u8]| PublicKey::from_slice(bytes).ok()
+    );
+
+    impl_serhex_key!(
+        Uncompressed<PublicKey>,
+        [65, 33],
+        |key: &Uncompressed<PublicKey>| key.serialize_uncompressed(),
+        |bytes: &[u8]| PublicKey::from_slice(bytes).ok().map(Uncompressed)
+    );
+
+    impl_serhex_key!(
+        XOnlyPublicKey,
+        [32],
+        |key: &XOnlyPublicKey| key.serialize(),
+        |bytes: &[u8]| XOnlyPublicKey::from_slice(bytes).ok()
+    );
+
+    impl_serhex_key!(
+        Signature,
+        [64],
+        |sig: &Signature| sig.serialize_compact(),
+        |bytes: &[u8]| Signature::from_compact(bytes).ok()
+    );
+
+    impl_serhex_key!(
+        schnorr::Signature,
+        [64],
+        |sig: &schnorr::Signature| sig.serialize(),
+        |bytes: &[u8]| schnorr::Signature::from_slice(bytes).ok()
+    );
+
+    impl_serhex_key!(
+        RecoverableSignature,
+        [65],
+        |sig: &RecoverableSignature| {
+            let (recid, compact) = sig.serialize_compact();
+            let mut bytes = [0u8; 65];
+            bytes[..64].copy_from_slice(&compact);
+            bytes[64] = recid.to_i32() as u8;
+            bytes
+        },
+        |bytes: &[u8]| {
+            let recid = RecoveryId::from_i32(bytes[64] as i32).ok()?;
+            RecoverableSignature::from_compact(&bytes[..64], recid).ok()
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use config::Strict;
+    use SerHex;
+
+    // generator point of secp256k1, in compressed and uncompressed form.
+    #[cfg(any(feature = "k256", feature = "secp256k1"))]
+    const COMPRESSED: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    #[cfg(any(feature = "k256", feature = "secp256k1"))]
+    const UNCOMPRESSED: &str = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                                483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    #[cfg(feature = "ed25519-dalek")]
+    #[test]
+    fn ed25519() {
+        use config::StrictPfx;
+        use ed25519_dalek::{Signature, VerifyingKey};
+        let hex = "0x3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+        let key = <VerifyingKey as SerHex<StrictPfx>>::from_hex(hex).unwrap();
+        assert_eq!(
+            <VerifyingKey as SerHex<StrictPfx>>::into_hex(&key).unwrap(),
+            hex
+        );
+        let sig = <Signature as SerHex<Strict>>::from_hex("ab".repeat(64)).unwrap();
+        assert_eq!(
+            <Signature as SerHex<Strict>>::into_hex(&sig).unwrap(),
+            "ab".repeat(64)
+        );
+    }
+
+    #[cfg(feature = "k256")]
+    #[test]
+    fn k256() {
+        use super::Uncompressed;
+        use k256::PublicKey;
+        use types::ParseHexError;
+        let key = <PublicKey as SerHex<Strict>>::from_hex(UNCOMPRESSED).unwrap();
+        assert_eq!(
+            <PublicKey as SerHex<Strict>>::into_hex(&key).unwrap(),
+            COMPRESSED
+        );
+        let key = Uncompressed(key);
+        assert_eq!(
+            <Uncompressed<PublicKey> as SerHex<Strict>>::into_hex(&key).unwrap(),
+            UNCOMPRESSED
+        );
+        // not a point on the curve.
+        let err =
+            <PublicKey as SerHex<Strict>>::from_hex(format!("02{}", "00".repeat(32))).unwrap_err();
+        match err.parse_error() {
+            Some(&ParseHexError::Rejected) => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn secp256k1() {
+        use super::Uncompressed;
+        use config::StrictCapPfx;
+        use secp256k1::ecdsa::RecoverableSignature;
+        use secp256k1::PublicKey;
+        use types::ParseHexError;
+        let key = <PublicKey as SerHex<Strict>>::from_hex(COMPRESSED).unwrap();
+        let upper = <PublicKey as SerHex<StrictCapPfx>>::into_hex(&key).unwrap();
+        assert_eq!(upper, format!("0x{}", COMPRESSED.to_uppercase()));
+        let hex = <Uncompressed<PublicKey> as SerHex<Strict>>::into_hex(&Uncompressed(key));
+        assert_eq!(hex.unwrap(), UNCOMPRESSED);
+        let hex = format!("{}{}01", "11".repeat(32), "22".repeat(32));
+        let sig = <RecoverableSignature as SerHex<Strict>>::from_hex(&hex).unwrap();
+        assert_eq!(
+            <RecoverableSignature as SerHex<Strict>>::into_hex(&sig).unwrap(),
+            hex
+        );
+        let err = <PublicKey as SerHex<Strict>>::from_hex("02abcd").unwrap_err();
+        match err.parse_error() {
+            Some(&ParseHexError::Size {
+                expect: 66,
+                actual: 6,
+            }) => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Fixed-size digests tagged with their algorithm.
+//!
+//! `Digest<N, A>` holds an `N`-byte digest produced by algorithm `A` (a
+//! zero-sized tag such as `Sha256`).  It implements `SerHex` for all configs
+//! (as plain hex), and `Display`/`FromStr` in the tagged `algo:hexdigest`
+//! form used by artifact registries and container tooling.  The `Tagged`
+//! `with`-target (de)serializes the tagged form, with the digest itself
+//! written under a given config:
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::digest::{Sha256Digest, Tagged};
+//! # use serde_hex::{SerHex, Strict, StrictPfx};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Layer {
+//!     #[serde(with = "Tagged::<Strict>")]
+//!     digest: Sha256Digest,
+//!     #[serde(with = "SerHex::<StrictPfx>")]
+//!     plain: Sha256Digest,
+//! }
+//!
+//! # fn main() {
+//! let digest = Sha256Digest::new([0xab; 32]);
+//! let layer = Layer { digest, plain: digest };
+//! let json = serde_json::to_string(&layer).unwrap();
+//! assert!(json.starts_with(r#"{"digest":"sha256:abab"#));
+//! assert_eq!(serde_json::from_str::<Layer>(&json).unwrap(), layer);
+//! assert!("sha512:abab".parse::<Sha256Digest>().is_err());
+//! # }
+//! ```
+use config::{HexConf, Strict};
+use io;
+use serde::{de, Deserializer, Serializer};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::{error, fmt, str};
+use types::{Error, ParseHexError};
+use utils;
+use SerHex;
+
+/// A digest algorithm, identified by the tag written before its digests.
+pub trait Algorithm {
+    /// tag of the algorithm (e.g. `sha256`).
+    const NAME: &'static str;
+}
+
+macro_rules! algorithms {
+    ($($(#[$meta: meta])* $name: ident => $tag: expr,)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name;
+
+            impl Algorithm for $name {
+                const NAME: &'static str = $tag;
+            }
+        )*
+    };
+}
+
+algorithms! {
+    /// SHA-1, tagged `sha1`.
+    Sha1 => "sha1",
+    /// SHA-256, tagged `sha256`.
+    Sha256 => "sha256",
+    /// SHA-384, tagged `sha384`.
+    Sha384 => "sha384",
+    /// SHA-512, tagged `sha512`.
+    Sha512 => "sha512",
+    /// BLAKE3, tagged `blake3`.
+    Blake3 => "blake3",
+}
+
+/// A SHA-1 digest.
+pub type Sha1Digest = Digest<20, Sha1>;
+
+/// A SHA-256 digest.
+pub type Sha256Digest = Digest<32, Sha256>;
+
+/// A SHA-384 digest.
+pub type Sha384Digest = Digest<48, Sha384>;
+
+/// A SHA-512 digest.
+pub type Sha512Digest = Digest<64, Sha512>;
+
+/// A BLAKE3 digest (of the default length).
+pub type Blake3Digest = Digest<32, Blake3>;
+
+/// An `N`-byte digest produced by algorithm `A`.
+pub struct Digest<const N: usize, A> {
+    bytes: [u8; N],
+    algo: PhantomData<A>,
+}
+
+impl<const N: usize, A: Algorithm> Digest<N, A> {
+    /// wrap the raw bytes of a digest.
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Digest {
+            bytes,
+            algo: PhantomData,
+        }
+    }
+
+    /// the raw bytes of this digest.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.bytes
+    }
+
+    /// unwrap the raw bytes of this digest.
+    pub fn into_bytes(self) -> [u8; N] {
+        self.bytes
+    }
+
+    /// tag of the algorithm which produced this digest.
+    pub fn algorithm(&self) -> &'static str {
+        A::NAME
+    }
+}
+
+impl<const N: usize, A> Clone for Digest<N, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const N: usize, A> Copy for Digest<N, A> {}
+
+impl<const N: usize, A> PartialEq for Digest<N, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<const N: usize, A> Eq for Digest<N, A> {}
+
+impl<const N: usize, A> PartialOrd for Digest<N, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize, A> Ord for Digest<N, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+impl<const N: usize, A> Hash for Digest<N, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state)
+    }
+}
+
+impl<const N: usize, A> AsRef<[u8]> for Digest<N, A> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize, A: Algorithm> From<[u8; N]> for Digest<N, A> {
+    fn from(bytes: [u8; N]) -> Self {
+        Digest::new(bytes)
+    }
+}
+
+impl<const N: usize, A: Algorithm> fmt::Display for Digest<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Tagged::<Strict>::into_hex_raw(self, io::FmtWriter(f)).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize, A: Algorithm> fmt::Debug for Digest<N, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Digest({})", self)
+    }
+}
+
+impl<const N: usize, A: Algorithm> str::FromStr for Digest<N, A> {
+    type Err = DigestError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Tagged::<Strict>::from_hex(src)
+    }
+}
+
+impl<const N: usize, A, C> SerHex<C> for Digest<N, A>
+where
+    A: Algorithm,
+    C: HexConf,
+{
+    type Error = Error;
+
+    fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+    where
+        D: io::Write,
+    {
+        match C::width() {
+            Some(width) => utils::writehex_width::<C, _>(&self.bytes, width, dst),
+            None => utils::writehex_with::<C, _>(&self.bytes, dst),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        utils::encoded_len::<C>(N)
+    }
+
+    fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        let mut bytes = [0u8; N];
+        utils::fromhex_with::<C>(&mut bytes, src.as_ref())?;
+        Ok(Digest::new(bytes))
+    }
+
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        err.into_de_error(src)
+    }
+}
+
+/// `with`-target which (de)serializes a `Digest` in the tagged
+/// `algo:hexdigest` form, with the digest written under config `C`.
+pub struct Tagged<C = Strict>(PhantomData<C>);
+
+impl<C: HexConf> Tagged<C> {
+    /// serialize `digest` in tagged form.
+    pub fn serialize<const N: usize, A, S>(
+        digest: &Digest<N, A>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        A: Algorithm,
+        S: Serializer,
+    {
+        serializer.collect_str(&TaggedDisplay::<N, A, C>(digest, PhantomData))
+    }
+
+    /// deserialize a digest in tagged form, failing if the tag is not that
+    /// of `A`.
+    pub fn deserialize<'de, const N: usize, A, D>(deserializer: D) -> Result<Digest<N, A>, D::Error>
+    where
+        A: Algorithm,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(TaggedVisitor::<N, A, C>(PhantomData))
+    }
+
+    /// write the tag of `A`, a colon, and the hex of `digest` to `dst`.
+    pub fn into_hex_raw<const N: usize, A, D>(
+        digest: &Digest<N, A>,
+        mut dst: D,
+    ) -> Result<(), Error>
+    where
+        A: Algorithm,
+        D: io::Write,
+    {
+        dst.write_all(A::NAME.as_bytes())?;
+        dst.write_all(b":")?;
+        <Digest<N, A> as SerHex<C>>::into_hex_raw(digest, dst)
+    }
+
+    /// parse a digest in tagged form.  the index of a `Char` error is
+    /// relative to the whole input.
+    pub fn from_hex<const N: usize, A, S>(src: S) -> Result<Digest<N, A>, DigestError>
+    where
+        A: Algorithm,
+        S: AsRef<[u8]>,
+    {
+        let src = src.as_ref();
+        let tag = A::NAME.as_bytes();
+        if src.len() <= tag.len() || &src[..tag.len()] != tag || src[tag.len()] != b':' {
+            return Err(DigestError::Tag);
+        }
+        let skip = tag.len() + 1;
+        let mut bytes = [0u8; N];
+        utils::fromhex_with::<C>(&mut bytes, &src[skip..]).map_err(|e| e.shift(skip))?;
+        Ok(Digest::new(bytes))
+    }
+}
+
+// formats a tagged digest straight into the serializer.
+struct TaggedDisplay<'a, const N: usize, A, C>(&'a Digest<N, A>, PhantomData<C>);
+
+impl<'a, const N: usize, A: Algorithm, C: HexConf> fmt::Display for TaggedDisplay<'a, N, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Tagged::<C>::into_hex_raw(self.0, io::FmtWriter(f)).map_err(|_| fmt::Error)
+    }
+}
+
+struct TaggedVisitor<const N: usize, A, C>(PhantomData<(A, C)>);
+
+impl<'de, const N: usize, A: Algorithm, C: HexConf> de::Visitor<'de> for TaggedVisitor<N, A, C> {
+    type Value = Digest<N, A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a `{}:`-tagged hex digest", A::NAME)
+    }
+
+    fn visit_str<E: de::Error>(self, src: &str) -> Result<Self::Value, E> {
+        match Tagged::<C>::from_hex(src) {
+            Ok(digest) => Ok(digest),
+            Err(DigestError::Tag) => Err(E::invalid_value(de::Unexpected::Str(src), &self)),
+            Err(DigestError::Parsing(err)) => Err(err.into_de_error(src.as_bytes())),
+        }
+    }
+}
+
+/// error raised when parsing a tagged digest.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DigestError {
+    /// the input did not begin with the expected algorithm tag and a colon
+    Tag,
+    /// the digest was not valid hex of the expected size
+    Parsing(ParseHexError),
+}
+
+impl fmt::Display for DigestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DigestError::Tag => f.write_str("missing or unexpected algorithm tag"),
+            DigestError::Parsing(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for DigestError {}
+
+impl From<ParseHexError> for DigestError {
+    fn from(err: ParseHexError) -> Self {
+        DigestError::Parsing(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DigestError, Sha1Digest, Sha256Digest, Tagged};
+    use config::{CompactPfx, StrictCap};
+    use types::ParseHexError;
+    use SerHex;
+
+    #[test]
+    fn tagged() {
+        let digest = Sha1Digest::new([0x0a; 20]);
+        assert_eq!(digest.algorithm(), "sha1");
+        assert_eq!(digest.to_string(), format!("sha1:{}", "0a".repeat(20)));
+        assert_eq!(digest.to_string().parse::<Sha1Digest>().unwrap(), digest);
+        let mut out = Vec::new();
+        Tagged::<StrictCap>::into_hex_raw(&Sha256Digest::new([0xab; 32]), &mut out).unwrap();
+        assert_eq!(&out[..11], b"sha256:ABAB");
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let hex = <Sha256Digest as SerHex<CompactPfx>>::into_hex(&Sha256Digest::new(one));
+        assert_eq!(hex.unwrap(), "0x1");
+        match "sha1:0a".parse::<Sha256Digest>() {
+            Err(DigestError::Tag) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "sha256".parse::<Sha256Digest>() {
+            Err(DigestError::Tag) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "sha256:0g".parse::<Sha256Digest>() {
+            Err(DigestError::Parsing(ParseHexError::Size { .. })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let bad = format!("sha256:{}g", "0".repeat(63));
+        match bad.parse::<Sha256Digest>() {
+            Err(DigestError::Parsing(ParseHexError::Char {
+                val: 'g',
+                index: 70,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
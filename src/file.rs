@@ -0,0 +1,379 @@
+//! Streaming hex conversion of whole files.
+//!
+//! `encode_file` writes the hex encoding of a file to another file under
+//! config `C`, and `decode_file` reverses it.  Both stream through the
+//! incremental encoder and decoder in fixed-size chunks, so files of any
+//! size are converted in bounded memory.  `HexFile` runs the same conversion
+//! between any reader and writer (e.g. already opened `File`s), and can wrap
+//! the hex into lines and report progress.
+//!
+//! Output is written as it is produced, so a conversion which fails partway
+//! (e.g. on a bad digit, or an i/o error) leaves `dst` holding whatever was
+//! written before the failure.  Compact configs trim the leading zeroes of
+//! the whole file when encoding, which can leave an odd number of digits
+//! that `decode_file` rejects, so use strict configs for files which are to
+//! be decoded again:
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # use serde_hex::file::HexFile;
+//! # use serde_hex::StrictCap;
+//! # fn main() {
+//! let data: Vec<u8> = (0..12).collect();
+//! let mut seen = 0;
+//! let mut progress = |done| seen = done;
+//! let mut hex = Vec::new();
+//! HexFile::<StrictCap>::new()
+//!     .wrap(16)
+//!     .progress(&mut progress)
+//!     .encode(&data[..], &mut hex)
+//!     .unwrap();
+//! assert_eq!(hex, b"0001020304050607\n08090A0B\n");
+//! assert_eq!(seen, 12);
+//!
+//! let mut out = Vec::new();
+//! HexFile::<StrictCap>::new().decode(&hex[..], &mut out).unwrap();
+//! assert_eq!(out, data);
+//! # }
+//! ```
+use config::{HexConf, Strict};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::{cmp, fmt};
+use stream::{HexDecoder, HexEncoder};
+use types::Error;
+use utils::scrub;
+
+/// Size of the stack buffer which input is read into.
+const CHUNK: usize = 4096;
+
+/// Write the hex encoding of the file at `src` to the file at `dst` (which
+/// is created or truncated), without line breaks.  Returns the number of
+/// bytes written.  Under compact configs, the output may not decode back to
+/// the original file (see the module docs).
+pub fn encode_file<C, P, Q>(src: P, dst: Q) -> Result<u64, Error>
+where
+    C: HexConf,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    HexFile::<C>::new().encode_path(src, dst)
+}
+
+/// Decode the hex file at `src` into the file at `dst` (which is created or
+/// truncated).  Line breaks in the input are skipped.  Returns the number of
+/// bytes written.  On failure, `dst` is left holding the bytes decoded so
+/// far.
+pub fn decode_file<C, P, Q>(src: P, dst: Q) -> Result<u64, Error>
+where
+    C: HexConf,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    HexFile::<C>::new().decode_path(src, dst)
+}
+
+/// Options for streaming hex conversion under config `C`.
+///
+/// Hex is written on a single line unless set with `wrap`.  When decoding,
+/// line breaks (`\n` and `\r`) are skipped wherever they occur, and errors
+/// for non-hexadecimal characters report their offset within the input.
+/// Decoding reads whole bytes only, so it can't reverse the odd-length
+/// output of compact configs.
+pub struct HexFile<'a, C = Strict> {
+    wrap: Option<usize>,
+    progress: Option<&'a mut dyn FnMut(u64)>,
+    conf: PhantomData<C>,
+}
+
+impl<'a, C: HexConf> HexFile<'a, C> {
+    /// options with no wrapping and no progress callback.
+    pub fn new() -> Self {
+        HexFile {
+            wrap: None,
+            progress: None,
+            conf: PhantomData,
+        }
+    }
+
+    /// break encoded output into lines of `width` characters (zero is
+    /// treated as one), each ending with `\n`.
+    pub fn wrap(mut self, width: usize) -> Self {
+        self.wrap = Some(cmp::max(width, 1));
+        self
+    }
+
+    /// call `progress` with the total number of input bytes consumed, after
+    /// each chunk of input.
+    pub fn progress(mut self, progress: &'a mut dyn FnMut(u64)) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// encode the file at `src` into the file at `dst`.
+    pub fn encode_path<P, Q>(&mut self, src: P, dst: Q) -> Result<u64, Error>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let src = File::open(src)?;
+        let mut dst = BufWriter::new(File::create(dst)?);
+        let len = self.encode(src, &mut dst)?;
+        dst.flush()?;
+        Ok(len)
+    }
+
+    /// decode the hex file at `src` into the file at `dst`.
+    pub fn decode_path<P, Q>(&mut self, src: P, dst: Q) -> Result<u64, Error>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let src = File::open(src)?;
+        let mut dst = BufWriter::new(File::create(dst)?);
+        let len = self.decode(src, &mut dst)?;
+        dst.flush()?;
+        Ok(len)
+    }
+
+    /// read `src` to the end, writing its hex encoding to `dst`.  Returns
+    /// the number of bytes written (including line breaks).
+    pub fn encode<R, W>(&mut self, src: R, dst: W) -> Result<u64, Error>
+    where
+        R: Read,
+        W: Write,
+    {
+        let mut buf = [0u8; CHUNK];
+        let mut hex = [0u8; CHUNK * 2 + 2];
+        let rslt = self.encode_with(&mut buf, &mut hex, src, dst);
+        scrub(&mut buf);
+        scrub(&mut hex);
+        rslt
+    }
+
+    /// read hex from `src` to the end, writing the decoded bytes to `dst`.
+    /// Returns the number of bytes written.
+    pub fn decode<R, W>(&mut self, src: R, dst: W) -> Result<u64, Error>
+    where
+        R: Read,
+        W: Write,
+    {
+        let mut hex = [0u8; CHUNK];
+        let mut buf = [0u8; CHUNK / 2 + 1];
+        let rslt = self.decode_with(&mut hex, &mut buf, src, dst);
+        scrub(&mut hex);
+        scrub(&mut buf);
+        rslt
+    }
+
+    // body of `encode`, using `buf` and `hex` as scratch space.
+    fn encode_with<R, W>(
+        &mut self,
+        buf: &mut [u8; CHUNK],
+        hex: &mut [u8; CHUNK * 2 + 2],
+        mut src: R,
+        dst: W,
+    ) -> Result<u64, Error>
+    where
+        R: Read,
+        W: Write,
+    {
+        let mut encoder = HexEncoder::<C>::new();
+        let mut out = Lines {
+            inner: dst,
+            width: self.wrap,
+            col: 0,
+            written: 0,
+        };
+        let mut done = 0;
+        loop {
+            let got = read_some(&mut src, buf)?;
+            if got == 0 {
+                break;
+            }
+            let len = encoder.feed(&buf[..got], hex)?;
+            out.write_hex(&hex[..len])?;
+            done += got as u64;
+            self.report(done);
+        }
+        let len = encoder.finish(hex)?;
+        out.write_hex(&hex[..len])?;
+        out.finish()?;
+        Ok(out.written)
+    }
+
+    // body of `decode`, using `hex` and `buf` as scratch space.
+    fn decode_with<R, W>(
+        &mut self,
+        hex: &mut [u8; CHUNK],
+        buf: &mut [u8; CHUNK / 2 + 1],
+        mut src: R,
+        mut dst: W,
+    ) -> Result<u64, Error>
+    where
+        R: Read,
+        W: Write,
+    {
+        let mut decoder = HexDecoder::<C>::new();
+        // the decoder never sees line breaks, so its error offsets are
+        // shifted by the number skipped so far.
+        let mut skipped = 0;
+        let (mut done, mut written) = (0, 0);
+        loop {
+            let got = read_some(&mut src, hex)?;
+            if got == 0 {
+                break;
+            }
+            let lines = hex[..got].split(|c| *c == b'\n' || *c == b'\r');
+            for (idx, line) in lines.enumerate() {
+                if idx > 0 {
+                    skipped += 1;
+                }
+                let len = decoder.feed(line, buf).map_err(|e| e.shift(skipped))?;
+                dst.write_all(&buf[..len])?;
+                written += len as u64;
+            }
+            done += got as u64;
+            self.report(done);
+        }
+        decoder.finish()?;
+        dst.flush()?;
+        Ok(written)
+    }
+
+    fn report(&mut self, done: u64) {
+        if let Some(ref mut progress) = self.progress {
+            progress(done);
+        }
+    }
+}
+
+impl<'a, C: HexConf> Default for HexFile<'a, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, C> fmt::Debug for HexFile<'a, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HexFile")
+            .field("wrap", &self.wrap)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+// writer which breaks hex into lines of `width` characters.
+struct Lines<W> {
+    inner: W,
+    width: Option<usize>,
+    col: usize,
+    written: u64,
+}
+
+impl<W: Write> Lines<W> {
+    fn write_hex(&mut self, mut hex: &[u8]) -> io::Result<()> {
+        let width = match self.width {
+            Some(width) => width,
+            None => return self.put(hex),
+        };
+        while !hex.is_empty() {
+            if self.col == width {
+                self.put(b"\n")?;
+                self.col = 0;
+            }
+            let take = cmp::min(width - self.col, hex.len());
+            self.put(&hex[..take])?;
+            self.col += take;
+            hex = &hex[take..];
+        }
+        Ok(())
+    }
+
+    // terminate the last line (if any), and flush.
+    fn finish(&mut self) -> io::Result<()> {
+        if self.col > 0 {
+            self.put(b"\n")?;
+        }
+        self.inner.flush()
+    }
+
+    fn put(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner.write_all(buf)?;
+        self.written += buf.len() as u64;
+        Ok(())
+    }
+}
+
+// read into `buf`, retrying if interrupted.
+fn read_some<R: Read>(src: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match src.read(buf) {
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            rslt => return rslt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_file, encode_file, HexFile};
+    use config::{Compact, Strict, StrictPfx};
+    use std::{env, fs, process};
+    use types::{Error, ParseHexError};
+
+    #[test]
+    fn files() {
+        let dir = env::temp_dir();
+        let name = |ext| dir.join(format!("serde-hex-{}.{}", process::id(), ext));
+        let (bin, hex, out) = (name("bin"), name("hex"), name("out"));
+        let data: Vec<u8> = (0..10000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&bin, &data).unwrap();
+        assert_eq!(encode_file::<StrictPfx, _, _>(&bin, &hex).unwrap(), 20002);
+        assert!(fs::read(&hex).unwrap().starts_with(b"0x00010203"));
+        assert_eq!(decode_file::<StrictPfx, _, _>(&hex, &out).unwrap(), 10000);
+        assert_eq!(fs::read(&out).unwrap(), data);
+        let mut calls = Vec::new();
+        let mut progress = |done| calls.push(done);
+        let len = HexFile::<Strict>::new()
+            .wrap(64)
+            .progress(&mut progress)
+            .encode_path(&bin, &hex)
+            .unwrap();
+        assert_eq!(len, 20000 + 313);
+        assert_eq!(calls.last(), Some(&10000));
+        assert_eq!(decode_file::<Strict, _, _>(&hex, &out).unwrap(), 10000);
+        assert_eq!(fs::read(&out).unwrap(), data);
+        for path in [bin, hex, out].iter() {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn line_breaks() {
+        let mut out = Vec::new();
+        let rslt = HexFile::<Strict>::new().decode(&b"00\r\n11\n2x"[..], &mut out);
+        match rslt {
+            Err(Error::Parsing(ParseHexError::Char { val: 'x', index: 8 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let mut out = Vec::new();
+        let rslt = HexFile::<Strict>::new().decode(&b"0\n01\n"[..], &mut out);
+        assert!(rslt.is_err());
+    }
+
+    #[test]
+    fn compact_limits() {
+        let mut hex = Vec::new();
+        HexFile::<Compact>::new()
+            .encode(&[0x00, 0x0a, 0xbc][..], &mut hex)
+            .unwrap();
+        assert_eq!(hex, b"abc");
+        let mut out = Vec::new();
+        let rslt = HexFile::<Compact>::new().decode(&hex[..], &mut out);
+        assert!(rslt.is_err());
+        assert_eq!(out, [0xab]);
+    }
+}
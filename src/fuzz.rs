@@ -0,0 +1,152 @@
+//! Fuzzing support via [`arbitrary`](https://crates.io/crates/arbitrary).
+//!
+//! `Hex<T, C>` and `HexString<C>` implement `Arbitrary` (as do byte-array
+//! newtypes declared with this crate's macros).  For fuzzing parsers which
+//! take hex text directly, `valid_hex` and `corrupt_hex` generate inputs
+//! which are well-formed, or broken in a known way, under a given config.
+//!
+//! ```rust
+//! # extern crate arbitrary;
+//! # extern crate serde_hex;
+//! # use arbitrary::Unstructured;
+//! # use serde_hex::fuzz::{corrupt_hex, valid_hex};
+//! # use serde_hex::{SerHex, StrictPfx};
+//! # fn main() {
+//! let data = [0xab, 0xcd, 0xef, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09];
+//! let mut u = Unstructured::new(&data);
+//! let good = valid_hex::<StrictPfx>(&mut u, 4).unwrap();
+//! assert!(<[u8; 4] as SerHex<StrictPfx>>::from_hex(&good).is_ok());
+//! let bad = corrupt_hex::<StrictPfx>(&mut u, 4).unwrap();
+//! assert!(<[u8; 4] as SerHex<StrictPfx>>::from_hex(&bad).is_err());
+//! # }
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+use config::HexConf;
+use utils;
+use wrapper::{Hex, HexString};
+
+/// Generate the hex encoding of `bytes` arbitrary bytes, as emitted under
+/// config `C` (so compact configs trim leading zeroes).
+pub fn valid_hex<C: HexConf>(u: &mut Unstructured, bytes: usize) -> Result<String> {
+    let src = u.bytes(bytes)?;
+    let mut dst = Vec::with_capacity(utils::encoded_len::<C>(bytes));
+    utils::writehex_with::<C, _>(src, &mut dst).map_err(|_| Error::IncorrectFormat)?;
+    String::from_utf8(dst).map_err(|_| Error::IncorrectFormat)
+}
+
+/// Generate hex which is invalid under config `C` for a fixed-width value
+/// of `bytes` bytes.  Starts from `valid_hex`, then applies one of:
+///
+/// - replacing a digit with a non-hex character (invalid under any config).
+/// - repeating the `0x` prefix (or, if there is none, adding it twice).
+/// - for strict configs, dropping a digit (unless `padodd` is set) or
+///   appending a byte.
+pub fn corrupt_hex<C: HexConf>(u: &mut Unstructured, bytes: usize) -> Result<String> {
+    let mut hex = valid_hex::<C>(u, bytes)?;
+    let pfx = if C::withpfx() { 2 } else { 0 };
+    let mut kinds: Vec<Corruption> = vec![bad_char, double_prefix];
+    if !C::compact() {
+        if !C::padodd() && hex.len() > pfx {
+            kinds.push(drop_digit);
+        }
+        kinds.push(extra_byte);
+    }
+    let kind = u.choose(&kinds)?;
+    kind(&mut hex, pfx, u)?;
+    Ok(hex)
+}
+
+/// Mutates valid hex (with a prefix of the given length) into invalid hex.
+type Corruption = fn(&mut String, usize, &mut Unstructured) -> Result<()>;
+
+fn bad_char(hex: &mut String, pfx: usize, u: &mut Unstructured) -> Result<()> {
+    let c = *u.choose(&['g', 'x', 'z', 'G', ' ', '-', '_', '\u{e9}'])?;
+    if hex.len() > pfx {
+        let index = u.int_in_range(pfx..=hex.len() - 1)?;
+        hex.replace_range(index..index + 1, c.encode_utf8(&mut [0; 4]));
+    } else {
+        hex.push(c);
+    }
+    Ok(())
+}
+
+fn double_prefix(hex: &mut String, pfx: usize, _: &mut Unstructured) -> Result<()> {
+    // a single prefix may be accepted, even if none is emitted.
+    hex.insert_str(0, if pfx > 0 { "0x" } else { "0x0x" });
+    Ok(())
+}
+
+fn drop_digit(hex: &mut String, _: usize, _: &mut Unstructured) -> Result<()> {
+    hex.pop();
+    Ok(())
+}
+
+fn extra_byte(hex: &mut String, _: usize, u: &mut Unstructured) -> Result<()> {
+    let byte = u8::arbitrary(u)?;
+    hex.push_str(&format!("{:02x}", byte));
+    Ok(())
+}
+
+impl<'a, T, C> Arbitrary<'a> for Hex<T, C>
+where
+    T: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        T::arbitrary(u).map(Hex::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        T::size_hint(depth)
+    }
+}
+
+impl<'a, C> Arbitrary<'a> for HexString<C>
+where
+    C: HexConf,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bytes = u.arbitrary_len::<u8>()?;
+        // compact configs may reject empty input.
+        let bytes = if C::compact() { bytes.max(1) } else { bytes };
+        let hex = valid_hex::<C>(u, bytes)?;
+        HexString::new(hex).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{corrupt_hex, valid_hex};
+    use arbitrary::{Arbitrary, Unstructured};
+    use config::{Compact, CompactPfx, Strict, StrictPfx};
+    use wrapper::{Hex, HexString};
+    use SerHex;
+
+    #[test]
+    fn generated_inputs() {
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..64 {
+            let good = valid_hex::<StrictPfx>(&mut u, 8).unwrap();
+            assert!(<[u8; 8] as SerHex<StrictPfx>>::from_hex(&good).is_ok());
+            let bad = corrupt_hex::<StrictPfx>(&mut u, 8).unwrap();
+            assert!(<[u8; 8] as SerHex<StrictPfx>>::from_hex(&bad).is_err());
+            let bad = corrupt_hex::<Strict>(&mut u, 8).unwrap();
+            assert!(<[u8; 8] as SerHex<Strict>>::from_hex(&bad).is_err());
+            let bad = corrupt_hex::<Compact>(&mut u, 8).unwrap();
+            assert!(<u64 as SerHex<Compact>>::from_hex(&bad).is_err());
+        }
+    }
+
+    #[test]
+    fn wrappers() {
+        let mut u = Unstructured::new(&[1, 2, 3, 4]);
+        let hex = Hex::<u16, StrictPfx>::arbitrary(&mut u).unwrap();
+        assert_eq!(*hex, 0x0201);
+        let data = [3, 0xab, 0xcd, 0xef, 0x12];
+        let hex = HexString::<CompactPfx>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!(hex.as_str().starts_with("0x"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Git object ids, and their abbreviations.
+//!
+//! `Sha1Oid` and `Sha256Oid` are byte-array newtypes (as declared by
+//! `define_hex_array`) which read and write lowercase hex without a prefix,
+//! as git does.  Abbreviations are written with `abbrev` (or
+//! `unique_abbrev`, which lengthens the abbreviation until no other known id
+//! shares it), and parsed as an `OidPrefix`, which finds the single id it
+//! names among a set of candidates:
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # use serde_hex::git::{ResolveError, Sha1Oid, Sha1Prefix};
+//! # fn main() {
+//! let head: Sha1Oid = "8a1f2b3c4d5e6f708192a3b4c5d6e7f801234567".parse().unwrap();
+//! let base: Sha1Oid = "8a1f2b3d00000000000000000000000000000000".parse().unwrap();
+//! assert_eq!(head.abbrev(7).to_string(), "8a1f2b3");
+//! assert_eq!(head.unique_abbrev(&[base], 7).to_string(), "8a1f2b3c");
+//! let prefix: Sha1Prefix = "8a1f2b3".parse().unwrap();
+//! assert_eq!(prefix.resolve(&[head, base]), Err(ResolveError::Ambiguous));
+//! let prefix: Sha1Prefix = "8a1f2b3c".parse().unwrap();
+//! assert_eq!(prefix.resolve(&[head, base]), Ok(&head));
+//! # }
+//! ```
+use config::Strict;
+use std::borrow::Borrow;
+use std::{error, fmt, str};
+use types::ParseHexError;
+use utils;
+
+define_hex_array!(
+    /// A SHA-1 git object id.
+    pub struct Sha1Oid(20),
+    config = Strict
+);
+
+define_hex_array!(
+    /// A SHA-256 git object id.
+    pub struct Sha256Oid(32),
+    config = Strict
+);
+
+/// Prefix of a `Sha1Oid`.
+pub type Sha1Prefix = OidPrefix<20>;
+
+/// Prefix of a `Sha256Oid`.
+pub type Sha256Prefix = OidPrefix<32>;
+
+/// The shortest abbreviation which is accepted or written, as in git.
+pub const MIN_ABBREV: usize = 4;
+
+macro_rules! impl_oid {
+    ($name: ident, $len: expr) => {
+        impl $name {
+            /// the first `digits` hex digits of this id, clamped to
+            /// `MIN_ABBREV` and the full width.
+            pub fn abbrev(&self, digits: usize) -> Abbrev<'_> {
+                Abbrev(&self.0, digits.clamp(MIN_ABBREV, $len * 2))
+            }
+
+            /// the shortest abbreviation of at least `min` digits which is
+            /// not shared with any of `others` (ids equal to this one are
+            /// ignored).
+            pub fn unique_abbrev<'a, I>(&self, others: I, min: usize) -> Abbrev<'_>
+            where
+                I: IntoIterator<Item = &'a $name>,
+            {
+                let digits = others
+                    .into_iter()
+                    .filter(|other| *other != self)
+                    .map(|other| common_digits(&self.0, &other.0) + 1)
+                    .fold(min, usize::max);
+                self.abbrev(digits)
+            }
+        }
+    };
+}
+
+impl_oid!(Sha1Oid, 20);
+impl_oid!(Sha256Oid, 32);
+
+/// Display adapter which writes the first digits of an object id.
+#[derive(Debug, Clone, Copy)]
+pub struct Abbrev<'a>(&'a [u8], usize);
+
+impl<'a> fmt::Display for Abbrev<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0u8; 64];
+        for (idx, digit) in buf[..self.1].iter_mut().enumerate() {
+            *digit = utils::fromval(nibble(self.0, idx));
+        }
+        f.pad(str::from_utf8(&buf[..self.1]).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// A parsed abbreviation of an `N`-byte object id.
+///
+/// Prefixes are between `MIN_ABBREV` and `N * 2` digits, in either case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OidPrefix<const N: usize> {
+    bytes: [u8; N],
+    digits: usize,
+}
+
+impl<const N: usize> OidPrefix<N> {
+    /// number of digits in this prefix.
+    pub fn digits(&self) -> usize {
+        self.digits
+    }
+
+    /// check if `oid` starts with this prefix.
+    pub fn matches<T: Borrow<[u8; N]>>(&self, oid: &T) -> bool {
+        common_digits(&self.bytes, oid.borrow()) >= self.digits
+    }
+
+    /// find the single candidate which starts with this prefix (candidates
+    /// are assumed to be distinct).
+    pub fn resolve<'a, T, I>(&self, candidates: I) -> Result<&'a T, ResolveError>
+    where
+        T: Borrow<[u8; N]> + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let mut found = candidates.into_iter().filter(|oid| self.matches(*oid));
+        match (found.next(), found.next()) {
+            (Some(oid), None) => Ok(oid),
+            (None, _) => Err(ResolveError::Missing),
+            (Some(_), Some(_)) => Err(ResolveError::Ambiguous),
+        }
+    }
+}
+
+impl<const N: usize> str::FromStr for OidPrefix<N> {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.as_bytes();
+        if src.len() < MIN_ABBREV || src.len() > N * 2 {
+            let (min, max, got) = (MIN_ABBREV, N * 2, src.len());
+            return Err(ParseHexError::Range { min, max, got });
+        }
+        let mut bytes = [0u8; N];
+        for (idx, chr) in src.iter().enumerate() {
+            let val = utils::intoval(*chr).map_err(|e| e.shift(idx))?;
+            bytes[idx / 2] |= val << (4 * (1 - idx % 2));
+        }
+        Ok(OidPrefix {
+            bytes,
+            digits: src.len(),
+        })
+    }
+}
+
+/// error raised when resolving an `OidPrefix` against a set of ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// no candidate starts with the prefix
+    Missing,
+    /// more than one candidate starts with the prefix
+    Ambiguous,
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveError::Missing => f.write_str("no object matches the prefix"),
+            ResolveError::Ambiguous => f.write_str("prefix is ambiguous"),
+        }
+    }
+}
+
+impl error::Error for ResolveError {}
+
+// the `idx`th hex digit of `bytes`.
+fn nibble(bytes: &[u8], idx: usize) -> u8 {
+    (bytes[idx / 2] >> (4 * (1 - idx % 2))) & 0x0f
+}
+
+// number of leading hex digits which `a` and `b` share.
+fn common_digits(a: &[u8], b: &[u8]) -> usize {
+    (0..a.len() * 2)
+        .position(|idx| nibble(a, idx) != nibble(b, idx))
+        .unwrap_or(a.len() * 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResolveError, Sha1Oid, Sha1Prefix, Sha256Oid};
+    use types::ParseHexError;
+
+    #[test]
+    fn object_ids() {
+        let oid: Sha256Oid = "ab".repeat(32).parse().unwrap();
+        assert_eq!(oid.abbrev(12).to_string(), "abababababab");
+        assert_eq!(oid.abbrev(1).to_string(), "abab");
+        assert_eq!(oid.abbrev(100).to_string().len(), 64);
+        let a = Sha1Oid([0x12; 20]);
+        let b = Sha1Oid([0x13; 20]);
+        assert_eq!(a.unique_abbrev(&[a, b], 4).to_string(), "1212");
+        assert_eq!(a.unique_abbrev(&[a], 4).to_string(), "1212");
+        let prefix: Sha1Prefix = "12121".parse().unwrap();
+        assert!(prefix.matches(&a));
+        assert_eq!(prefix.digits(), 5);
+        assert_eq!(prefix.resolve(&[a, b]), Ok(&a));
+        assert_eq!(prefix.resolve(&[b]), Err(ResolveError::Missing));
+        match "121".parse::<Sha1Prefix>() {
+            Err(ParseHexError::Range {
+                min: 4,
+                max: 40,
+                got: 3,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "12g4".parse::<Sha1Prefix>() {
+            Err(ParseHexError::Char { val: 'g', index: 2 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! `with`-target for hex split into delimited groups.
+//!
+//! `#[serde(with = "Grouped::<C, SIZE, DELIM>")]` (de)serializes byte
+//! containers as hex under config `C`, split into groups of `SIZE` bytes
+//! separated by `DELIM` (e.g. `dead beef`).  By default a prefixed config
+//! writes a single prefix before the whole string; setting `EACH` instead
+//! prefixes every group (e.g. `0xde 0xad 0xbe 0xef`), as in debug logs and
+//! some vendor tools.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::group::Grouped;
+//! # use serde_hex::{Strict, StrictPfx};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Dump {
+//!     #[serde(with = "Grouped::<StrictPfx, 2, ' '>")]
+//!     words: Vec<u8>,
+//!     #[serde(with = "Grouped::<Strict, 1, ' ', true>")]
+//!     bytes: [u8; 4],
+//! }
+//!
+//! # fn main() {
+//! let dump = Dump { words: vec![0xde, 0xad, 0xbe, 0xef, 0x01], bytes: [0xde, 0xad, 0xbe, 0xef] };
+//! let json = serde_json::to_string(&dump).unwrap();
+//! assert_eq!(json, r#"{"words":"0xdead beef 01","bytes":"0xde 0xad 0xbe 0xef"}"#);
+//! assert_eq!(serde_json::from_str::<Dump>(&json).unwrap(), dump);
+//! # }
+//! ```
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use io;
+use serde::{de, Deserializer, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+use types::{Error, ParseHexError};
+use utils;
+
+/// `with`-target which (de)serializes byte containers as hex under config
+/// `C`, in groups of `SIZE` bytes separated by `DELIM`, with every group
+/// prefixed if `EACH` is set.
+///
+/// Only the final group may be shorter than `SIZE` bytes.  Compact configs
+/// don't trim groups, so `C` only selects the prefix and case.  When
+/// parsing, a prefix is accepted on every group if `EACH` is set, and
+/// otherwise only at the start (if `C::acceptpfx()`).  `SIZE` must be
+/// non-zero (checked at compile time).
+pub struct Grouped<
+    C = Strict,
+    const SIZE: usize = 1,
+    const DELIM: char = ' ',
+    const EACH: bool = false,
+>(PhantomData<C>);
+
+impl<C: HexConf, const SIZE: usize, const DELIM: char, const EACH: bool>
+    Grouped<C, SIZE, DELIM, EACH>
+{
+    const OK: () = assert!(SIZE > 0, "`SIZE` must be non-zero");
+
+    /// serialize `bytes` as grouped hex.
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + AsRef<[u8]>,
+        S: Serializer,
+    {
+        serializer.collect_str(&GroupDisplay::<C, SIZE, DELIM, EACH>(
+            bytes.as_ref(),
+            PhantomData,
+        ))
+    }
+
+    /// deserialize grouped hex.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(GroupVisitor::<C, SIZE, DELIM, EACH, T>(PhantomData))
+    }
+
+    /// write the grouped hex of `src` to `dst`.
+    pub fn into_hex_raw<D>(src: &[u8], mut dst: D) -> Result<(), Error>
+    where
+        D: io::Write,
+    {
+        let () = Self::OK;
+        let mut delim = [0; 4];
+        let delim = DELIM.encode_utf8(&mut delim).as_bytes();
+        if C::withpfx() && !EACH {
+            dst.write_all(utils::prefix::<C>())?;
+        }
+        for (idx, group) in src.chunks(SIZE).enumerate() {
+            if idx > 0 {
+                dst.write_all(delim)?;
+            }
+            if EACH {
+                dst.write_all(utils::prefix::<C>())?;
+            }
+            if C::withcap() {
+                utils::writehexcaps(group, &mut dst)?;
+            } else {
+                utils::writehex(group, &mut dst)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// parse grouped hex.  a group of the wrong size is reported as a
+    /// `Size` error (or `OddLength`, for a short final group), and the
+    /// index of a `Char` error is relative to the whole input.
+    pub fn from_hex<S>(src: S) -> Result<Vec<u8>, ParseHexError>
+    where
+        S: AsRef<[u8]>,
+    {
+        let () = Self::OK;
+        let src = src.as_ref();
+        let mut delim = [0; 4];
+        let delim = DELIM.encode_utf8(&mut delim).as_bytes();
+        let mut bytes = Vec::with_capacity(src.len() / 2);
+        let (mut rest, mut offset) = (src, 0);
+        loop {
+            let first = offset == 0;
+            let end = rest
+                .windows(delim.len())
+                .position(|w| w == delim)
+                .unwrap_or(rest.len());
+            let last = end == rest.len();
+            let group = &rest[..end];
+            let hex = utils::strippfx(group, EACH || (first && C::acceptpfx()));
+            let pfx = group.len() - hex.len();
+            let full = SIZE * 2;
+            // only the final group may be short, and only a lone group empty.
+            let empty_ok = first && last;
+            if hex.len() > full || (!last && hex.len() < full) || (hex.is_empty() && !empty_ok) {
+                return Err(ParseHexError::Size {
+                    expect: full,
+                    actual: hex.len(),
+                });
+            }
+            if hex.len() % 2 == 1 {
+                return Err(ParseHexError::OddLength { len: hex.len() });
+            }
+            if !hex.is_empty() {
+                let start = bytes.len();
+                bytes.resize(start + hex.len() / 2, 0);
+                utils::fromhex(&mut bytes[start..], hex).map_err(|e| e.shift(offset + pfx))?;
+            }
+            if last {
+                return Ok(bytes);
+            }
+            offset += end + delim.len();
+            rest = &rest[end + delim.len()..];
+        }
+    }
+}
+
+// formats grouped hex straight into the serializer.
+struct GroupDisplay<'a, C, const SIZE: usize, const DELIM: char, const EACH: bool>(
+    &'a [u8],
+    PhantomData<C>,
+);
+
+impl<'a, C: HexConf, const SIZE: usize, const DELIM: char, const EACH: bool> fmt::Display
+    for GroupDisplay<'a, C, SIZE, DELIM, EACH>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Grouped::<C, SIZE, DELIM, EACH>::into_hex_raw(self.0, io::FmtWriter(f))
+            .map_err(|_| fmt::Error)
+    }
+}
+
+struct GroupVisitor<C, const SIZE: usize, const DELIM: char, const EACH: bool, T>(
+    PhantomData<(C, T)>,
+);
+
+impl<'de, C, const SIZE: usize, const DELIM: char, const EACH: bool, T> de::Visitor<'de>
+    for GroupVisitor<C, SIZE, DELIM, EACH, T>
+where
+    C: HexConf,
+    T: TryFrom<Vec<u8>>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a grouped hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, src: &str) -> Result<T, E> {
+        let src = src.as_bytes();
+        let bytes =
+            Grouped::<C, SIZE, DELIM, EACH>::from_hex(src).map_err(|e| e.into_de_error(src))?;
+        let len = bytes.len() * 2;
+        T::try_from(bytes).map_err(|_| ParseHexError::Length { len }.into_de_error(src))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grouped;
+    use config::{Strict, StrictCap, StrictPfx};
+    use types::ParseHexError;
+
+    #[test]
+    fn groups() {
+        let mut out = Vec::new();
+        Grouped::<StrictCap, 2, '-'>::into_hex_raw(&[0xab, 0xcd, 0xef], &mut out).unwrap();
+        assert_eq!(out, b"ABCD-EF");
+        let mut out = Vec::new();
+        Grouped::<Strict, 1, ' ', true>::into_hex_raw(&[0xde, 0xad], &mut out).unwrap();
+        assert_eq!(out, b"0xde 0xad");
+        let each = Grouped::<Strict, 1, ' ', true>::from_hex("0xde 0Xad be").unwrap();
+        assert_eq!(each, vec![0xde, 0xad, 0xbe]);
+        assert_eq!(
+            Grouped::<StrictPfx, 2, ':'>::from_hex("0x").unwrap(),
+            Vec::<u8>::new()
+        );
+        assert_eq!(
+            Grouped::<Strict, 2, ':'>::from_hex("").unwrap(),
+            Vec::<u8>::new()
+        );
+        // only the first group may be prefixed, unless `EACH` is set.
+        match Grouped::<StrictPfx, 1, ' '>::from_hex("0xde 0xad") {
+            Err(ParseHexError::Size {
+                expect: 2,
+                actual: 4,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Grouped::<Strict, 2, ' '>::from_hex("de adbe") {
+            Err(ParseHexError::Size {
+                expect: 4,
+                actual: 2,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Grouped::<Strict, 2, ' '>::from_hex("dead b") {
+            Err(ParseHexError::OddLength { len: 1 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Grouped::<Strict, 2, ' '>::from_hex("dead ") {
+            Err(ParseHexError::Size {
+                expect: 4,
+                actual: 0,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Grouped::<Strict, 1, ' ', true>::from_hex("0xde 0xag") {
+            Err(ParseHexError::Char { val: 'g', index: 8 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
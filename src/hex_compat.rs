@@ -0,0 +1,206 @@
+//! Interop with the traits of the [`hex`](https://crates.io/crates/hex) crate.
+//!
+//! Types which only implement `hex::FromHex` and `hex::ToHex` can be used
+//! with this crate by wrapping them in `ViaHex`, which implements the strict
+//! `SerHex` variants.  In the other direction, `Hex<T, C>` implements
+//! `hex::FromHex` (and, with `alloc`, `hex::ToHex`) for any `T: SerHex<C>`.
+//!
+//! ```rust
+//! # extern crate hex;
+//! # extern crate serde_hex;
+//! # use serde_hex::hex_compat::ViaHex;
+//! # use serde_hex::{SerHex, StrictPfx};
+//! # fn main() {
+//! let key: ViaHex<[u8; 2]> = SerHex::<StrictPfx>::from_hex("0xabcd").unwrap();
+//! assert_eq!(SerHex::<StrictPfx>::into_hex(&key).unwrap(), "0xabcd");
+//! # }
+//! ```
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use config::{HexConf, Strict, StrictCap, StrictCapPfx, StrictPfx};
+use hex::{FromHex, FromHexError};
+use io;
+use std::ops::{Deref, DerefMut};
+use types::{Error, ParseHexError};
+use utils;
+use wrapper::Hex;
+use SerHex;
+
+/// Adapter which implements the strict `SerHex` variants for any type
+/// implementing `hex::FromHex` and `hex::ToHex` (i.e. `AsRef<[u8]>`).
+///
+/// Encoding honours the prefix and capitalization of the config.  Decoding
+/// strips the optional prefix and defers to `T::from_hex`, so any length
+/// checks are those of `T`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ViaHex<T>(pub T);
+
+impl<T> ViaHex<T> {
+    /// unwrap the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for ViaHex<T> {
+    fn from(inner: T) -> Self {
+        ViaHex(inner)
+    }
+}
+
+impl<T> Deref for ViaHex<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ViaHex<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// convert an error raised by the `hex` crate, where `skip` is the length
+// of the prefix which was stripped before decoding.
+fn from_hex_error(err: FromHexError, skip: usize, len: usize) -> ParseHexError {
+    match err {
+        FromHexError::InvalidHexCharacter { c, index } => ParseHexError::Char {
+            val: c,
+            index: index + skip,
+        },
+        FromHexError::OddLength => ParseHexError::OddLength { len },
+        FromHexError::InvalidStringLength => ParseHexError::Length { len },
+    }
+}
+
+macro_rules! impl_via_hex {
+    ($($conf:ty),*) => {
+        $(
+            impl<T> SerHex<$conf> for ViaHex<T>
+            where
+                T: FromHex<Error = FromHexError> + AsRef<[u8]>,
+            {
+                type Error = Error;
+
+                fn into_hex_raw<D>(&self, dst: D) -> Result<(), Error>
+                where
+                    D: io::Write,
+                {
+                    utils::writehex_with::<$conf, D>(self.0.as_ref(), dst)
+                }
+
+                fn from_hex_raw<S>(src: S) -> Result<Self, Error>
+                where
+                    S: AsRef<[u8]>,
+                {
+                    let src = src.as_ref();
+                    let hex = utils::strippfx(src, <$conf as HexConf>::acceptpfx());
+                    let skip = src.len() - hex.len();
+                    match T::from_hex(hex) {
+                        Ok(inner) => Ok(ViaHex(inner)),
+                        Err(err) => Err(from_hex_error(err, skip, hex.len()).into()),
+                    }
+                }
+
+                fn encoded_len(&self) -> usize {
+                    self.0.as_ref().len() * 2 + 2
+                }
+
+                fn de_error<E>(err: Error, src: &[u8]) -> E
+                where
+                    E: ::serde::de::Error,
+                {
+                    err.into_de_error(src)
+                }
+            }
+        )*
+    };
+}
+
+impl_via_hex!(Strict, StrictPfx, StrictCap, StrictCapPfx);
+
+impl<T, C> FromHex for Hex<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    type Error = T::Error;
+
+    fn from_hex<S: AsRef<[u8]>>(src: S) -> Result<Self, Self::Error> {
+        T::from_hex_raw(src).map(Hex::new)
+    }
+}
+
+/// Encodes via `SerHex::into_hex`, capitalizing the digits for
+/// `encode_hex_upper`.
+///
+/// # panics
+///
+/// `ToHex` has no error channel, so this panics if encoding fails (e.g. a
+/// value too wide for a `Width` config, or a `RefCell` which is already
+/// mutably borrowed).  use `SerHex::into_hex` to handle such failures.
+#[cfg(feature = "alloc")]
+impl<T, C> hex::ToHex for Hex<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn encode_hex<I: ::std::iter::FromIterator<char>>(&self) -> I {
+        let hex: String = self.0.into_hex().expect("hex encoding into memory");
+        hex.chars().collect()
+    }
+
+    fn encode_hex_upper<I: ::std::iter::FromIterator<char>>(&self) -> I {
+        let hex: String = self.0.into_hex().expect("hex encoding into memory");
+        // only the digits are capitalized, not the `x` of the prefix.
+        hex.chars()
+            .map(|c| if c == 'x' { c } else { c.to_ascii_uppercase() })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ViaHex;
+    use config::{Strict, StrictCapPfx, StrictPfx, Width};
+    use hex::{FromHex, ToHex};
+    use types::ParseHexError;
+    use wrapper::Hex;
+    use SerHex;
+
+    #[test]
+    fn via_hex() {
+        let val = ViaHex([0x0a_u8, 0xbc]);
+        assert_eq!(SerHex::<StrictCapPfx>::into_hex(&val).unwrap(), "0x0ABC");
+        let back: ViaHex<[u8; 2]> = SerHex::<StrictPfx>::from_hex("0x0abc").unwrap();
+        assert_eq!(back, val);
+        let vec: ViaHex<Vec<u8>> = SerHex::<Strict>::from_hex("0abc").unwrap();
+        assert_eq!(*vec, vec![0x0a, 0xbc]);
+        let err = <ViaHex<[u8; 2]> as SerHex<StrictPfx>>::from_hex("0xabcdef").unwrap_err();
+        match err.parse_error() {
+            Some(&ParseHexError::Length { len: 6 }) => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+        let err = <ViaHex<Vec<u8>> as SerHex<StrictPfx>>::from_hex("0xabzd").unwrap_err();
+        assert_eq!(err.parse_error().and_then(|e| e.index()), Some(4));
+    }
+
+    #[test]
+    fn hex_traits() {
+        let val = Hex::<u16, StrictPfx>::from_hex("0x0abc").unwrap();
+        assert_eq!(*val, 0x0abc);
+        let lower: String = val.encode_hex();
+        let upper: String = val.encode_hex_upper();
+        assert_eq!(lower, "0x0abc");
+        assert_eq!(upper, "0x0ABC");
+    }
+
+    #[test]
+    #[should_panic(expected = "hex encoding into memory")]
+    fn to_hex_failure() {
+        let val = Hex::<u16, Width<2, Strict>>::new(0x0abc);
+        let _: String = val.encode_hex();
+    }
+}
@@ -0,0 +1,724 @@
+//! Serializer and deserializer adapters which hex-encode all byte strings.
+//!
+//! `#[serde(with = "...")]` only helps for fields which can be annotated.
+//! `HexifySerializer` instead wraps a whole serializer, and emits every
+//! `serialize_bytes` call anywhere in the object graph as a hex string under
+//! config `C` (so third-party types which serialize bytes are covered too).
+//! `HexifyDeserializer` is the dual: every `deserialize_bytes` and
+//! `deserialize_byte_buf` call is served by decoding a hex string.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde::Serialize;
+//! # use serde_hex::hexify::HexifySerializer;
+//! # use serde_hex::StrictPfx;
+//! // stand-in for a third-party type which serializes as bytes.
+//! #[derive(Debug, PartialEq)]
+//! struct Digest(Vec<u8>);
+//!
+//! impl Serialize for Digest {
+//!     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+//!         serializer.serialize_bytes(&self.0)
+//!     }
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize)]
+//! struct Block {
+//!     digests: Vec<Digest>,
+//! }
+//!
+//! # fn main() {
+//! let block = Block { digests: vec![Digest(vec![0xab, 0xcd])] };
+//! let mut json = Vec::new();
+//! let mut ser = serde_json::Serializer::new(&mut json);
+//! block.serialize(HexifySerializer::<_, StrictPfx>::new(&mut ser)).unwrap();
+//! assert_eq!(String::from_utf8(json).unwrap(), r#"{"digests":["0xabcd"]}"#);
+//! # }
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use io;
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use utils;
+
+/// Serializer adapter which emits byte strings as hex under config `C`, and
+/// defers everything else to the wrapped serializer.
+pub struct HexifySerializer<S, C = Strict> {
+    inner: S,
+    conf: PhantomData<C>,
+}
+
+impl<S, C> HexifySerializer<S, C> {
+    /// wrap `inner`.
+    pub fn new(inner: S) -> Self {
+        HexifySerializer {
+            inner,
+            conf: PhantomData,
+        }
+    }
+
+    /// unwrap the inner serializer.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+/// Deserializer adapter which decodes byte strings from hex under config
+/// `C`, and defers everything else to the wrapped deserializer.
+pub struct HexifyDeserializer<D, C = Strict> {
+    inner: D,
+    conf: PhantomData<C>,
+}
+
+impl<D, C> HexifyDeserializer<D, C> {
+    /// wrap `inner`.
+    pub fn new(inner: D) -> Self {
+        HexifyDeserializer {
+            inner,
+            conf: PhantomData,
+        }
+    }
+
+    /// unwrap the inner deserializer.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+/// Compound serializer returned by `HexifySerializer`, which applies the
+/// adapter to each element, field, key and value.
+pub struct HexifyCompound<S, C = Strict>(S, PhantomData<C>);
+
+impl<S, C> HexifyCompound<S, C> {
+    fn new(inner: S) -> Self {
+        HexifyCompound(inner, PhantomData)
+    }
+}
+
+// wraps the values, visitors and accessors
+// handed out by the inner (de)serializer, so that the adapter is applied
+// at every level of the object graph.
+struct Wrap<T, C>(T, PhantomData<C>);
+
+impl<T, C> Wrap<T, C> {
+    fn new(inner: T) -> Self {
+        Wrap(inner, PhantomData)
+    }
+}
+
+// formats a byte string as hex under config `C`.
+struct HexBytes<'a, C>(&'a [u8], PhantomData<C>);
+
+impl<'a, C: HexConf> fmt::Display for HexBytes<'a, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        utils::writehex_with::<C, _>(self.0, io::FmtWriter(f)).map_err(|_| fmt::Error)
+    }
+}
+
+macro_rules! forward_serialize {
+    ($($method: ident($ty: ty)),*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<S, C> Serializer for HexifySerializer<S, C>
+where
+    S: Serializer,
+    C: HexConf,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = HexifyCompound<S::SerializeSeq, C>;
+    type SerializeTuple = HexifyCompound<S::SerializeTuple, C>;
+    type SerializeTupleStruct = HexifyCompound<S::SerializeTupleStruct, C>;
+    type SerializeTupleVariant = HexifyCompound<S::SerializeTupleVariant, C>;
+    type SerializeMap = HexifyCompound<S::SerializeMap, C>;
+    type SerializeStruct = HexifyCompound<S::SerializeStruct, C>;
+    type SerializeStructVariant = HexifyCompound<S::SerializeStructVariant, C>;
+
+    forward_serialize!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_unit_struct(&'static str)
+    );
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> {
+        self.inner.collect_str(&HexBytes::<C>(v, PhantomData))
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.inner.serialize_some(&Wrap::<_, C>::new(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.inner
+            .serialize_newtype_struct(name, &Wrap::<_, C>::new(value))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.inner
+            .serialize_newtype_variant(name, index, variant, &Wrap::<_, C>::new(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.inner.serialize_seq(len).map(HexifyCompound::new)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.inner.serialize_tuple(len).map(HexifyCompound::new)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.inner
+            .serialize_tuple_struct(name, len)
+            .map(HexifyCompound::new)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.inner
+            .serialize_tuple_variant(name, index, variant, len)
+            .map(HexifyCompound::new)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.inner.serialize_map(len).map(HexifyCompound::new)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.inner
+            .serialize_struct(name, len)
+            .map(HexifyCompound::new)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.inner
+            .serialize_struct_variant(name, index, variant, len)
+            .map(HexifyCompound::new)
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + fmt::Display,
+    {
+        self.inner.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+impl<T, C> Serialize for Wrap<&T, C>
+where
+    T: ?Sized + Serialize,
+    C: HexConf,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(HexifySerializer::<S, C>::new(serializer))
+    }
+}
+
+macro_rules! impl_wrap_compound {
+    ($($trait: ident :: $method: ident),*) => {
+        $(
+            impl<S, C> ser::$trait for HexifyCompound<S, C>
+            where
+                S: ser::$trait,
+                C: HexConf,
+            {
+                type Ok = S::Ok;
+                type Error = S::Error;
+
+                fn $method<T>(&mut self, value: &T) -> Result<(), S::Error>
+                where
+                    T: ?Sized + Serialize,
+                {
+                    self.0.$method(&Wrap::<_, C>::new(value))
+                }
+
+                fn end(self) -> Result<S::Ok, S::Error> {
+                    self.0.end()
+                }
+            }
+        )*
+    };
+}
+
+impl_wrap_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl<S, C> ser::SerializeMap for HexifyCompound<S, C>
+where
+    S: ser::SerializeMap,
+    C: HexConf,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_key(&Wrap::<_, C>::new(key))
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_value(&Wrap::<_, C>::new(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S, C> ser::SerializeStruct for HexifyCompound<S, C>
+where
+    S: ser::SerializeStruct,
+    C: HexConf,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_field(key, &Wrap::<_, C>::new(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        self.0.skip_field(key)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S, C> ser::SerializeStructVariant for HexifyCompound<S, C>
+where
+    S: ser::SerializeStructVariant,
+    C: HexConf,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_field(key, &Wrap::<_, C>::new(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        self.0.skip_field(key)
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method: ident($($arg: ident : $ty: ty),*)),*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.inner.$method($($arg,)* Wrap::<_, C>::new(visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, D, C> Deserializer<'de> for HexifyDeserializer<D, C>
+where
+    D: Deserializer<'de>,
+    C: HexConf,
+{
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any()
+    );
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner
+            .deserialize_str(BytesVisitor::<_, C>(visitor, PhantomData))
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner
+            .deserialize_str(BytesVisitor::<_, C>(visitor, PhantomData))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+// decodes a hex string, and hands the bytes to the wrapped visitor.
+struct BytesVisitor<V, C>(V, PhantomData<C>);
+
+impl<'de, V, C> Visitor<'de> for BytesVisitor<V, C>
+where
+    V: Visitor<'de>,
+    C: HexConf,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        let src = v.as_bytes();
+        let bytes = utils::fromhex_vec_with::<C>(src).map_err(|e| e.into_de_error(src))?;
+        self.0.visit_byte_buf(bytes)
+    }
+
+    // formats with native byte strings may still supply raw bytes.
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<V::Value, E> {
+        self.0.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<V::Value, E> {
+        self.0.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<V::Value, E> {
+        self.0.visit_byte_buf(v)
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method: ident($ty: ty)),*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V, C> Visitor<'de> for Wrap<V, C>
+where
+    V: Visitor<'de>,
+    C: HexConf,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    forward_visit!(
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>)
+    );
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .visit_some(HexifyDeserializer::<D, C>::new(deserializer))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .visit_newtype_struct(HexifyDeserializer::<D, C>::new(deserializer))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.0.visit_seq(Wrap::<_, C>::new(seq))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.0.visit_map(Wrap::<_, C>::new(map))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        self.0.visit_enum(Wrap::<_, C>::new(data))
+    }
+}
+
+// seeds are wrapped so that nested values are deserialized via the adapter.
+struct WrapSeed<T, C>(T, PhantomData<C>);
+
+impl<'de, T, C> DeserializeSeed<'de> for WrapSeed<T, C>
+where
+    T: DeserializeSeed<'de>,
+    C: HexConf,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .deserialize(HexifyDeserializer::<D, C>::new(deserializer))
+    }
+}
+
+impl<'de, A, C> de::SeqAccess<'de> for Wrap<A, C>
+where
+    A: de::SeqAccess<'de>,
+    C: HexConf,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0
+            .next_element_seed(WrapSeed::<_, C>(seed, PhantomData))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A, C> de::MapAccess<'de> for Wrap<A, C>
+where
+    A: de::MapAccess<'de>,
+    C: HexConf,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.0.next_key_seed(WrapSeed::<_, C>(seed, PhantomData))
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0.next_value_seed(WrapSeed::<_, C>(seed, PhantomData))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A, C> de::EnumAccess<'de> for Wrap<A, C>
+where
+    A: de::EnumAccess<'de>,
+    C: HexConf,
+{
+    type Error = A::Error;
+    type Variant = Wrap<A::Variant, C>;
+
+    fn variant_seed<T>(self, seed: T) -> Result<(T::Value, Self::Variant), A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let (value, variant) = self.0.variant_seed(WrapSeed::<_, C>(seed, PhantomData))?;
+        Ok((value, Wrap::new(variant)))
+    }
+}
+
+impl<'de, A, C> de::VariantAccess<'de> for Wrap<A, C>
+where
+    A: de::VariantAccess<'de>,
+    C: HexConf,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0
+            .newtype_variant_seed(WrapSeed::<_, C>(seed, PhantomData))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.tuple_variant(len, Wrap::<_, C>::new(visitor))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.struct_variant(fields, Wrap::<_, C>::new(visitor))
+    }
+}
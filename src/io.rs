@@ -0,0 +1,326 @@
+//! Minimal i/o support, allowing `SerHex` to be used without `std`.
+//!
+//! With the `std` feature (the default), `Write` is just `std::io::Write`.
+//! Without it, `Write` is a small stand-in trait whose failures are reported
+//! as `Error::Write`.  It is implemented for `&mut [u8]` (which advances past
+//! the written bytes, like its `std` counterpart), `SmallVec` (with the
+//! `smallvec` feature), and (with the `alloc` feature) `Vec<u8>`.
+#[cfg(feature = "std")]
+pub use std::io::Write;
+
+use std::mem::MaybeUninit;
+use std::{fmt, str};
+
+/// Adapter which writes (utf-8) bytes to a formatter.  Used to emit hex
+/// via `Display` without buffering it first.
+pub(crate) struct FmtWriter<'a, 'b: 'a>(pub &'a mut fmt::Formatter<'b>);
+
+#[cfg(feature = "std")]
+impl<'a, 'b> Write for FmtWriter<'a, 'b> {
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        let s = str::from_utf8(buf)
+            .map_err(|e| ::std::io::Error::new(::std::io::ErrorKind::InvalidData, e))?;
+        self.0
+            .write_str(s)
+            .map_err(|_| ::std::io::Error::from(::std::io::ErrorKind::Other))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, 'b> Write for FmtWriter<'a, 'b> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), ::types::Error> {
+        let s = str::from_utf8(buf).map_err(|_| ::types::Error::Write)?;
+        self.0.write_str(s).map_err(|_| ::types::Error::Write)
+    }
+}
+
+/// Adapter which appends (utf-8) bytes to a `String`.  Used by `into_hex`,
+/// so that an impl which emits non-utf-8 bytes gets a write error rather
+/// than a panic.
+#[cfg(feature = "alloc")]
+pub(crate) struct StringWriter<'a>(pub &'a mut ::alloc::string::String);
+
+#[cfg(feature = "std")]
+impl<'a> Write for StringWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        let s = str::from_utf8(buf)
+            .map_err(|e| ::std::io::Error::new(::std::io::ErrorKind::InvalidData, e))?;
+        self.0.push_str(s);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+impl<'a> Write for StringWriter<'a> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), ::types::Error> {
+        let s = str::from_utf8(buf).map_err(|_| ::types::Error::Write)?;
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+/// Adapter which fills a buffer of uninitialized bytes from the front, and
+/// fails once it is full.  Used by `codec::encode_uninit`.
+pub(crate) struct UninitWriter<'a> {
+    pub buf: &'a mut [MaybeUninit<u8>],
+    pub len: usize,
+}
+
+impl<'a> UninitWriter<'a> {
+    // copy `src` after the bytes written so far, if there is room.
+    fn push(&mut self, src: &[u8]) -> bool {
+        match self.buf.get_mut(self.len..self.len + src.len()) {
+            Some(dst) => {
+                for (slot, byte) in dst.iter_mut().zip(src.iter()) {
+                    slot.write(*byte);
+                }
+                self.len += src.len();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Write for UninitWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        if self.push(buf) {
+            Ok(buf.len())
+        } else {
+            Err(::std::io::ErrorKind::WriteZero.into())
+        }
+    }
+
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a> Write for UninitWriter<'a> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), ::types::Error> {
+        if self.push(buf) {
+            Ok(())
+        } else {
+            Err(::types::Error::Write)
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::Write;
+
+/// Scratch buffer for serialization, which keeps small contents on the
+/// stack.  Implemented by `SmallVec` with the `smallvec` feature, and by
+/// `StackBuf` otherwise.
+pub(crate) trait Scratch: Write + AsRef<[u8]> + AsMut<[u8]> {
+    /// empty buffer expected to hold `cap` bytes.
+    fn with_capacity(cap: usize) -> Self;
+}
+
+#[cfg(feature = "smallvec")]
+impl<A> Scratch for ::smallvec::SmallVec<A>
+where
+    A: ::smallvec::Array<Item = u8>,
+{
+    fn with_capacity(cap: usize) -> Self {
+        ::smallvec::SmallVec::with_capacity(cap)
+    }
+}
+
+/// Inline storage for `StackBuf`.
+#[cfg(not(feature = "smallvec"))]
+pub(crate) trait Inline: AsRef<[u8]> + AsMut<[u8]> {
+    /// zero-filled storage.
+    fn zeroed() -> Self;
+}
+
+#[cfg(not(feature = "smallvec"))]
+impl<const N: usize> Inline for [u8; N] {
+    fn zeroed() -> Self {
+        [0; N]
+    }
+}
+
+/// Stand-in for `SmallVec` when the `smallvec` feature is disabled.  Holds
+/// its contents inline until they outgrow `A`, then moves them to the heap
+/// (or, without the `alloc` feature, fails the write).
+#[cfg(not(feature = "smallvec"))]
+pub(crate) struct StackBuf<A> {
+    inline: A,
+    len: usize,
+    #[cfg(feature = "alloc")]
+    heap: Option<::alloc::vec::Vec<u8>>,
+}
+
+#[cfg(not(feature = "smallvec"))]
+impl<A: Inline> StackBuf<A> {
+    // append `buf`, returning `false` if it doesn't fit.
+    fn extend_from_slice(&mut self, buf: &[u8]) -> bool {
+        #[cfg(feature = "alloc")]
+        {
+            if let Some(ref mut heap) = self.heap {
+                heap.extend_from_slice(buf);
+                return true;
+            }
+        }
+        let end = self.len + buf.len();
+        if end <= self.inline.as_ref().len() {
+            self.inline.as_mut()[self.len..end].copy_from_slice(buf);
+            self.len = end;
+            return true;
+        }
+        #[cfg(feature = "alloc")]
+        {
+            let mut heap = ::alloc::vec::Vec::with_capacity(end);
+            heap.extend_from_slice(&self.inline.as_ref()[..self.len]);
+            heap.extend_from_slice(buf);
+            ::utils::scrub(&mut self.inline.as_mut()[..self.len]);
+            self.heap = Some(heap);
+            true
+        }
+        #[cfg(not(feature = "alloc"))]
+        false
+    }
+}
+
+#[cfg(not(feature = "smallvec"))]
+impl<A: Inline> Scratch for StackBuf<A> {
+    fn with_capacity(cap: usize) -> Self {
+        let inline = A::zeroed();
+        #[cfg(feature = "alloc")]
+        {
+            let heap = if cap > inline.as_ref().len() {
+                Some(::alloc::vec::Vec::with_capacity(cap))
+            } else {
+                None
+            };
+            StackBuf {
+                inline,
+                len: 0,
+                heap,
+            }
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = cap;
+            StackBuf { inline, len: 0 }
+        }
+    }
+}
+
+#[cfg(not(feature = "smallvec"))]
+impl<A: Inline> AsRef<[u8]> for StackBuf<A> {
+    fn as_ref(&self) -> &[u8] {
+        #[cfg(feature = "alloc")]
+        {
+            if let Some(ref heap) = self.heap {
+                return heap;
+            }
+        }
+        &self.inline.as_ref()[..self.len]
+    }
+}
+
+#[cfg(not(feature = "smallvec"))]
+impl<A: Inline> AsMut<[u8]> for StackBuf<A> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        #[cfg(feature = "alloc")]
+        {
+            if let Some(ref mut heap) = self.heap {
+                return heap;
+            }
+        }
+        &mut self.inline.as_mut()[..self.len]
+    }
+}
+
+#[cfg(all(feature = "std", not(feature = "smallvec")))]
+impl<A: Inline> Write for StackBuf<A> {
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        // `std` implies `alloc`, so the buffer can always spill.
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(all(not(feature = "std"), not(feature = "smallvec")))]
+impl<A: Inline> Write for StackBuf<A> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), ::types::Error> {
+        if self.extend_from_slice(buf) {
+            Ok(())
+        } else {
+            Err(::types::Error::Write)
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "smallvec")]
+    use smallvec::{Array, SmallVec};
+    use std::mem;
+    use types::Error;
+
+    /// Stand-in for `std::io::Write` when `std` is unavailable.
+    pub trait Write {
+        /// Attempt to write the entirety of `buf`.
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error>;
+    }
+
+    impl<W> Write for &mut W
+    where
+        W: Write + ?Sized,
+    {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            (**self).write_all(buf)
+        }
+    }
+
+    impl Write for &mut [u8] {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            if buf.len() > self.len() {
+                return Err(Error::Write);
+            }
+            let (head, tail) = mem::take(self).split_at_mut(buf.len());
+            head.copy_from_slice(buf);
+            *self = tail;
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "smallvec")]
+    impl<A> Write for SmallVec<A>
+    where
+        A: Array<Item = u8>,
+    {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Interop with [`serde_json::Value`](https://docs.rs/serde_json/latest/serde_json/enum.Value.html).
+//!
+//! Code which manipulates dynamic JSON trees can insert and extract
+//! hex-encoded fields with `to_hex_value` and `from_hex_value`, without
+//! declaring an intermediate struct with `#[serde(with = "...")]` fields.
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::json_compat::{from_hex_value, to_hex_value};
+//! # use serde_hex::{CompactPfx, StrictPfx};
+//! # use serde_json::{Map, Value};
+//! # fn main() {
+//! let mut tree = Map::new();
+//! tree.insert("nonce".into(), to_hex_value::<_, CompactPfx>(&255u64).unwrap());
+//! tree.insert("hash".into(), to_hex_value::<_, StrictPfx>(&[0xabu8, 0xcd]).unwrap());
+//! let tree = Value::Object(tree);
+//! assert_eq!(tree["nonce"], "0xff");
+//! let hash: [u8; 2] = from_hex_value::<_, StrictPfx>(&tree["hash"]).unwrap();
+//! assert_eq!(hash, [0xab, 0xcd]);
+//! # }
+//! ```
+use config::HexConf;
+use serde_json::{value, Error, Value};
+use SerHex;
+
+/// Encode `src` as a JSON string holding its hex encoding under config `C`.
+pub fn to_hex_value<T, C>(src: &T) -> Result<Value, Error>
+where
+    T: SerHex<C>,
+    C: HexConf,
+{
+    <T as SerHex<C>>::serialize(src, value::Serializer)
+}
+
+/// Decode a JSON string holding a hex encoding under config `C`.  Fails if
+/// `src` is not a string, or if the string is not valid for `T`.
+pub fn from_hex_value<T, C>(src: &Value) -> Result<T, Error>
+where
+    T: SerHex<C>,
+    C: HexConf,
+{
+    <T as SerHex<C>>::deserialize(src)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_hex_value, to_hex_value};
+    use config::{Compact, StrictPfx};
+    use serde_json::Value;
+
+    #[test]
+    fn round_trip() {
+        let value = to_hex_value::<_, StrictPfx>(&0x1234u16).unwrap();
+        assert_eq!(value, Value::String("0x1234".into()));
+        assert_eq!(from_hex_value::<u16, StrictPfx>(&value).unwrap(), 0x1234);
+        let value = to_hex_value::<_, Compact>(&0x0fu32).unwrap();
+        assert_eq!(value, Value::String("f".into()));
+    }
+
+    #[test]
+    fn errors() {
+        let err = from_hex_value::<u16, StrictPfx>(&Value::Bool(true)).unwrap_err();
+        assert!(err.to_string().contains("invalid type"));
+        let bad = Value::String("0x12".into());
+        let err = from_hex_value::<u16, StrictPfx>(&bad).unwrap_err();
+        assert!(err.to_string().contains("4 hex chars"));
+    }
+}
@@ -0,0 +1,199 @@
+//! `with`-target for self-describing, length-prefixed hex.
+//!
+//! `#[serde(with = "LenPrefixed::<C>")]` (de)serializes byte containers as
+//! their length in bytes (as compact hex), a separator, and then the hex of
+//! the bytes themselves under config `C` (e.g. `4:deadbeef`).  Parsing checks
+//! the declared length against the payload.  The separator defaults to `:`,
+//! and can be changed with the second parameter (`LenPrefixed::<C, '/'>`).
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::lenpfx::LenPrefixed;
+//! # use serde_hex::{Strict, StrictPfx};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Frame {
+//!     #[serde(with = "LenPrefixed::<Strict>")]
+//!     body: Vec<u8>,
+//!     #[serde(with = "LenPrefixed::<StrictPfx, '/'>")]
+//!     tag: [u8; 2],
+//! }
+//!
+//! # fn main() {
+//! let frame = Frame { body: vec![0xde, 0xad, 0xbe, 0xef], tag: [0, 1] };
+//! let json = serde_json::to_string(&frame).unwrap();
+//! assert_eq!(json, r#"{"body":"4:deadbeef","tag":"2/0x0001"}"#);
+//! assert_eq!(serde_json::from_str::<Frame>(&json).unwrap(), frame);
+//! let short = r#"{"body":"5:deadbeef","tag":"2/0x0001"}"#;
+//! assert!(serde_json::from_str::<Frame>(short).is_err());
+//! # }
+//! ```
+use alloc::vec::Vec;
+use config::{Compact, CompactCap, HexConf, Strict};
+use io;
+use serde::{de, Deserializer, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+use types::{Error, ParseHexError};
+use utils;
+
+/// `with`-target which (de)serializes byte containers as hex under config
+/// `C`, preceded by their length in bytes and the separator `SEP`.
+///
+/// The payload is always written in full (compact configs don't trim it,
+/// since the declared length already fixes its width), so `C` only selects
+/// the prefix and case.  The length is written in the same case, without a
+/// prefix.
+pub struct LenPrefixed<C = Strict, const SEP: char = ':'>(PhantomData<C>);
+
+impl<C: HexConf, const SEP: char> LenPrefixed<C, SEP> {
+    /// serialize `bytes` as length-prefixed hex.
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + AsRef<[u8]>,
+        S: Serializer,
+    {
+        serializer.collect_str(&LenDisplay::<C, SEP>(bytes.as_ref(), PhantomData))
+    }
+
+    /// deserialize length-prefixed hex, failing if the payload is not of
+    /// the declared length.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(LenVisitor::<C, SEP, T>(PhantomData))
+    }
+
+    /// write the length of `src`, the separator, and the hex of `src`
+    /// to `dst`.
+    pub fn into_hex_raw<D>(src: &[u8], mut dst: D) -> Result<(), Error>
+    where
+        D: io::Write,
+    {
+        let len = (src.len() as u64).to_be_bytes();
+        if C::withcap() {
+            utils::writehex_with::<CompactCap, _>(&len, &mut dst)?;
+        } else {
+            utils::writehex_with::<Compact, _>(&len, &mut dst)?;
+        }
+        dst.write_all(SEP.encode_utf8(&mut [0; 4]).as_bytes())?;
+        if C::withpfx() {
+            dst.write_all(utils::prefix::<C>())?;
+        }
+        if C::withcap() {
+            utils::writehexcaps(src, dst)
+        } else {
+            utils::writehex(src, dst)
+        }
+    }
+
+    /// parse length-prefixed hex.  the length may be in either case, and
+    /// the payload is parsed under config `C`.  a payload which doesn't
+    /// match the declared length is reported as a `Size` error.
+    pub fn from_hex<S>(src: S) -> Result<Vec<u8>, ParseHexError>
+    where
+        S: AsRef<[u8]>,
+    {
+        let src = src.as_ref();
+        let mut sep = [0; 4];
+        let sep = SEP.encode_utf8(&mut sep).as_bytes();
+        let at = src
+            .windows(sep.len())
+            .position(|w| w == sep)
+            .ok_or(ParseHexError::MissingSeparator)?;
+        let mut len = [0u8; 8];
+        utils::fromhex_with::<Compact>(&mut len, &src[..at])?;
+        let len = u64::from_be_bytes(len);
+        let declared = usize::try_from(len).map_err(|_| ParseHexError::Overflow {
+            bits: usize::BITS as usize,
+            value: Some(u128::from(len)),
+        })?;
+        let skip = at + sep.len();
+        let payload = &src[skip..];
+        let bytes = utils::fromhex_vec_with::<C>(payload).map_err(|e| e.shift(skip))?;
+        if bytes.len() != declared {
+            let actual = utils::strippfx(payload, C::acceptpfx()).len();
+            return Err(ParseHexError::Size {
+                expect: declared.saturating_mul(2),
+                actual,
+            });
+        }
+        Ok(bytes)
+    }
+}
+
+// formats length-prefixed hex straight into the serializer.
+struct LenDisplay<'a, C, const SEP: char>(&'a [u8], PhantomData<C>);
+
+impl<'a, C: HexConf, const SEP: char> fmt::Display for LenDisplay<'a, C, SEP> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        LenPrefixed::<C, SEP>::into_hex_raw(self.0, io::FmtWriter(f)).map_err(|_| fmt::Error)
+    }
+}
+
+struct LenVisitor<C, const SEP: char, T>(PhantomData<(C, T)>);
+
+impl<'de, C, const SEP: char, T> de::Visitor<'de> for LenVisitor<C, SEP, T>
+where
+    C: HexConf,
+    T: TryFrom<Vec<u8>>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a length-prefixed hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, src: &str) -> Result<T, E> {
+        let src = src.as_bytes();
+        let bytes = LenPrefixed::<C, SEP>::from_hex(src).map_err(|e| e.into_de_error(src))?;
+        let len = bytes.len() * 2;
+        T::try_from(bytes).map_err(|_| ParseHexError::Length { len }.into_de_error(src))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LenPrefixed;
+    use config::{CompactPfx, StrictCap};
+    use types::ParseHexError;
+
+    #[test]
+    fn declared_lengths() {
+        let mut out = Vec::new();
+        LenPrefixed::<StrictCap>::into_hex_raw(&[0xab; 32], &mut out).unwrap();
+        assert_eq!(&out[..5], b"20:AB");
+        assert_eq!(out.len(), 3 + 64);
+        assert_eq!(
+            LenPrefixed::<StrictCap>::from_hex(&out).unwrap(),
+            vec![0xab; 32]
+        );
+        let mut out = Vec::new();
+        LenPrefixed::<CompactPfx, '#'>::into_hex_raw(&[0x00, 0x01], &mut out).unwrap();
+        assert_eq!(out, b"2#0x0001");
+        assert_eq!(
+            LenPrefixed::<StrictCap>::from_hex("0:").unwrap(),
+            Vec::<u8>::new()
+        );
+        match LenPrefixed::<StrictCap>::from_hex("3:abcd") {
+            Err(ParseHexError::Size {
+                expect: 6,
+                actual: 4,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match LenPrefixed::<StrictCap>::from_hex("abcd") {
+            Err(ParseHexError::MissingSeparator) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match LenPrefixed::<StrictCap>::from_hex("2:abxd") {
+            Err(ParseHexError::Char { val: 'x', index: 4 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -27,32 +27,194 @@
 //! `StrictCapPfx`, etc...).
 //!
 //! This crate provides implementations of `SerHex` for all unsigned integer types,
-//! as well as generic impls for arrays of types which implement `SerHex`.  The generic
-//! impls apply only to strict variants of the trait, and only for arrays of length 1
-//! through 64 (no impl is provided for arrays of length 0 since there isn't really
-//! a reasonable way to represent a zero-sized value in hex).
-//!
+//! `i128` and `isize` (as their two's-complement bit patterns), as well as generic
+//! impls for arrays of types which implement `SerHex`.  `usize` and `isize` are
+//! always written at 64 bits, so the output doesn't depend on the platform.  The
+//! generic impls apply only to strict variants of the trait, and only for arrays of
+//! length 1 through 64 (no impl is provided for arrays of length 0 since there isn't
+//! really a reasonable way to represent a zero-sized value in hex).
 //!
+//! The crate supports `no_std` targets via three feature tiers.  Without any
+//! features, `SerHex` and the slice-based helpers in `utils` are available,
+//! with writes going through the stand-in `io::Write` trait.  The `alloc`
+//! feature adds `String`/`Vec` conveniences such as `SerHex::into_hex`, and
+//! the `std` feature (enabled by default) adds `std::io` support, including
+//! the readers and writers in `stream` and the file helpers in `file`.
 //!
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+#[cfg(any(feature = "alloc", feature = "quickcheck"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+extern crate core as std;
+
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
 extern crate array_init;
-extern crate serde;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "defmt")]
+extern crate defmt;
+#[cfg(feature = "ed25519-dalek")]
+extern crate ed25519_dalek;
+#[cfg(feature = "futures-io")]
+extern crate futures_io;
+#[cfg(feature = "hex")]
+extern crate hex;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+#[cfg(feature = "k256")]
+extern crate k256;
+#[cfg(feature = "proptest")]
+extern crate proptest;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "schemars")]
+extern crate schemars;
+#[cfg(feature = "secp256k1")]
+extern crate secp256k1;
+#[doc(hidden)]
+pub extern crate serde;
+#[cfg(feature = "serde_bytes")]
+extern crate serde_bytes;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "smallvec")]
 extern crate smallvec;
+#[cfg(feature = "subtle")]
+extern crate subtle;
+#[cfg(feature = "time")]
+extern crate time;
+#[cfg(feature = "utoipa")]
+extern crate utoipa;
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
 
 #[macro_use]
 pub mod macros;
+#[cfg(feature = "alloc")]
+pub mod armor;
+pub mod atomic;
+#[cfg(feature = "alloc")]
+pub mod bigint;
+pub mod bits;
+#[cfg(all(feature = "serde_bytes", feature = "alloc"))]
+pub mod bytes_compat;
+pub mod codec;
+pub mod combinators;
 pub mod config;
+#[cfg(feature = "alloc")]
+pub mod convert;
+#[cfg(any(feature = "ed25519-dalek", feature = "k256", feature = "secp256k1"))]
+pub mod crypto_compat;
+pub mod digest;
+#[cfg(feature = "std")]
+pub mod file;
+#[cfg(all(feature = "arbitrary", feature = "alloc"))]
+pub mod fuzz;
+pub mod git;
+#[cfg(feature = "alloc")]
+pub mod group;
+#[cfg(feature = "hex")]
+pub mod hex_compat;
+#[cfg(feature = "alloc")]
+pub mod hexify;
+pub mod io;
+#[cfg(all(feature = "serde_json", feature = "alloc"))]
+pub mod json_compat;
+#[cfg(feature = "alloc")]
+pub mod lenpfx;
+#[cfg(feature = "alloc")]
+pub mod literal;
+pub mod map_values;
+pub mod matrix;
+pub mod nibble;
+#[cfg(feature = "alloc")]
+pub mod preserve;
+pub mod presets;
+#[cfg(all(any(feature = "schemars", feature = "utoipa"), feature = "alloc"))]
+pub mod schema;
+#[cfg(feature = "alloc")]
+pub mod sets;
+pub mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod time_compat;
 pub mod types;
 pub mod utils;
+pub mod validate;
+#[cfg(feature = "alloc")]
+pub mod value;
+pub mod wrapper;
 
+#[cfg(feature = "alloc")]
+pub use bigint::HexUint;
 pub use config::*;
+#[cfg(feature = "alloc")]
+pub use convert::{decode, encode};
+pub use nibble::Nibble;
 pub use types::{Error, ParseHexError};
+pub use validate::{validate, ValidatedInfo};
+#[cfg(feature = "alloc")]
+pub use value::HexStrDeserializer;
+pub use wrapper::{Hex, Redacted};
+#[cfg(feature = "alloc")]
+pub use wrapper::{HexCow, HexString};
 
-use serde::{Deserialize, Deserializer, Serializer};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use io::Scratch;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "smallvec")]
 use smallvec::SmallVec;
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
 use std::iter::FromIterator;
-use std::{error, io};
+use std::marker::PhantomData;
+use std::{array, error, fmt};
+
+// stack-first scratch buffer used by the provided `serialize` impls.
+#[cfg(feature = "smallvec")]
+type ScratchBuf<A> = SmallVec<A>;
+#[cfg(not(feature = "smallvec"))]
+type ScratchBuf<A> = io::StackBuf<A>;
+
+// re-exports used by the exported macros, which can't assume that
+// `std` is available in the calling crate.
+#[doc(hidden)]
+pub mod export {
+    #[cfg(feature = "alloc")]
+    pub use alloc::string::String;
+    #[cfg(feature = "alloc")]
+    pub use alloc::vec::Vec;
+    #[cfg(feature = "arbitrary")]
+    pub mod arbitrary {
+        pub use arbitrary::{Arbitrary, Result, Unstructured};
+    }
+    #[cfg(feature = "defmt")]
+    pub use defmt::{Format, Formatter};
+    #[cfg(feature = "proptest")]
+    pub mod proptest {
+        pub use proptest::{arbitrary, array, strategy};
+    }
+    #[cfg(feature = "quickcheck")]
+    pub mod quickcheck {
+        pub use alloc::boxed::Box;
+        pub use quickcheck::{Arbitrary, Gen};
+    }
+    #[cfg(feature = "rand")]
+    pub use rand::distributions::{Distribution, Standard};
+    #[cfg(feature = "rand")]
+    pub use rand::{Rng, RngCore};
+    pub use std::result::Result;
+    pub use std::{array, borrow, cmp, convert, fmt, hash, iter, mem, ops, slice, str};
+    #[cfg(feature = "subtle")]
+    pub use subtle::{Choice, ConstantTimeEq};
+}
 
 /// Trait specifying custom serialization and deserialization logic from a
 /// hexadecimal string to some arbitrary type.  This trait can be used to apply
@@ -74,6 +236,7 @@ where
     type Error: error::Error;
 
     /// Attept to convert `self` to hexadecimal, writing the resultant bytes to some buffer.
+    #[allow(clippy::wrong_self_convention)]
     fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
     where
         D: io::Write;
@@ -83,11 +246,42 @@ where
     where
         S: AsRef<[u8]>;
 
+    /// Hint for the number of bytes that `into_hex_raw` will write for `self`.
+    ///
+    /// Used to preallocate buffers in `into_hex` and `serialize`.  The hint does
+    /// not need to be exact (an underestimate just costs a reallocation), but
+    /// implementations for fixed-size types should return the exact size, or an
+    /// upper bound for compact representations.  Defaults to `32`.
+    fn encoded_len(&self) -> usize {
+        32
+    }
+
+    /// Convert an error raised by `from_hex_raw` into a deserialization error,
+    /// where `src` is the input which failed to parse.
+    ///
+    /// The default implementation falls back to `de::Error::custom`.  Impls
+    /// which use this crate's error type should forward to
+    /// `Error::into_de_error`, which reports errors via `invalid_length` and
+    /// `invalid_value` so that messages describe what was expected.
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        let _ = src;
+        E::custom(err)
+    }
+
     /// Attempt to convert `self` into a hexadecimal string representation.
+    ///
+    /// Never panics: if `into_hex_raw` writes bytes which aren't valid utf-8
+    /// (each write must be valid on its own), the write fails and the error
+    /// is returned.
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::wrong_self_convention)]
     fn into_hex(&self) -> Result<String, Self::Error> {
-        let mut dst: Vec<u8> = Vec::with_capacity(32);
-        self.into_hex_raw(&mut dst)?;
-        Ok(String::from_utf8(dst).expect("invalid UTF-8 bytes in parsing"))
+        let mut dst = String::with_capacity(self.encoded_len());
+        self.into_hex_raw(io::StringWriter(&mut dst))?;
+        Ok(dst)
     }
 
     /// Attempt to convert a slice of hexadecimal bytes into an instance of `Self`.
@@ -98,23 +292,57 @@ where
         Self::from_hex_raw(src)
     }
 
+    /// Attempt to parse a slice of hexadecimal bytes directly into `dst`.
+    ///
+    /// The default implementation parses a new value and moves it into `dst`.
+    /// The array impls override this to decode straight into the existing
+    /// storage, which avoids building (and then copying) a temporary array.
+    /// If parsing fails, `dst` may be left partially overwritten.
+    fn from_hex_into<S>(src: S, dst: &mut Self) -> Result<(), Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        *dst = Self::from_hex_raw(src)?;
+        Ok(())
+    }
+
+    /// Attempt to parse the hexadecimal characters yielded by `src` into an
+    /// instance of `Self`.
+    ///
+    /// Lets pipelines which filter or map `str::chars` decode the result
+    /// without collecting it into a `String` first.  The characters are
+    /// gathered into the same stack-first scratch buffer used by `serialize`
+    /// (so inputs of up to 256 characters never touch the heap, and without
+    /// `alloc` longer inputs fail with `Error::Write`).  Non-ASCII characters
+    /// are rejected with `ParseHexError::Char`.
+    fn from_hex_chars<I>(src: I) -> Result<Self, Self::Error>
+    where
+        I: IntoIterator<Item = char>,
+        Self::Error: From<Error>,
+    {
+        let mut buf = ScratchBuf::<[u8; 256]>::with_capacity(256);
+        let rslt = match collect_chars(&mut buf, src) {
+            Ok(()) => Self::from_hex_raw(buf.as_ref()),
+            Err(err) => Err(err.into()),
+        };
+        utils::scrub(buf.as_mut());
+        rslt
+    }
+
     /// Attempt to serialize `self` into a hexadecimal string representation.
     ///
-    /// *NOTE*: The default implementation attempts to avoid heap-allocation with a
-    /// [`SmallVec`](https://docs.rs/smallvec/) of size `[u8;64]`. This default will
-    /// prevent heap-alloc for non-prefixed serializations of `[u8;32]` or smaller.
+    /// *NOTE*: The default implementation avoids heap-allocation by encoding into a
+    /// [`SmallVec`](https://docs.rs/smallvec/) whose inline size is picked from
+    /// `encoded_len` (`[u8;64]`, `[u8;128]` or `[u8;256]`).  For fixed-size types
+    /// the choice is resolved at compile time, so serializations of up to 256
+    /// bytes (e.g. prefixed 64-byte signatures) never touch the heap.  Larger
+    /// values, and all values under configs with `HexConf::collectstr`, are
+    /// formatted directly into the serializer via `collect_str`.
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        use serde::ser::Error;
-        let mut dst = SmallVec::<[u8; 64]>::new();
-        self.into_hex_raw(&mut dst).map_err(S::Error::custom)?;
-        // if `dst` is not valid UTF-8 bytes, the underlying implementation
-        // is very broken, and you should be ashamed of yourelf.
-        debug_assert!(::std::str::from_utf8(dst.as_ref()).is_ok());
-        let s = unsafe { ::std::str::from_utf8_unchecked(dst.as_ref()) };
-        serializer.serialize_str(s)
+        serialize_hex::<C, Self, S, EmitPlain>(self, serializer)
     }
 
     /// Attempt to deserialize a hexadecimal string into an instance of `Self`.
@@ -122,11 +350,21 @@ where
     where
         D: Deserializer<'de>,
     {
-        use serde::de::Error;
         let buff: &[u8] = Deserialize::deserialize(deserializer)?;
-        let rslt = Self::from_hex_raw(buff).map_err(D::Error::custom)?;
+        let rslt = Self::from_hex_raw(buff).map_err(|e| Self::de_error(e, buff))?;
         Ok(rslt)
     }
+
+    /// Attempt to deserialize a hexadecimal string directly into `place`,
+    /// via `from_hex_into`.  Backs `Deserialize::deserialize_in_place` for
+    /// the types which derive their serde impls from `SerHex`.
+    fn deserialize_in_place<'de, D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let buff: &[u8] = Deserialize::deserialize(deserializer)?;
+        Self::from_hex_into(buff, place).map_err(|e| Self::de_error(e, buff))
+    }
 }
 
 /// Variant of `SerHex` for serializing/deserializing `Option` types.
@@ -165,16 +403,8 @@ where
     where
         S: Serializer,
     {
-        use serde::ser::Error;
         if let Some(ref src) = *option {
-            let mut dst = SmallVec::<[u8; 64]>::new();
-            Self::into_hex_raw(src, &mut dst).map_err(S::Error::custom)?;
-            // if `dst` is not valid UTF-8 bytes, the underlying implementation
-            // is very broken, and you should be ashamed of yourelf.
-            debug_assert!(::std::str::from_utf8(dst.as_ref()).is_ok());
-            let s = unsafe { ::std::str::from_utf8_unchecked(dst.as_ref()) };
-            //serializer.serialize_str(s)
-            serializer.serialize_some(s)
+            serialize_hex::<C, Self, S, EmitSome>(src, serializer)
         } else {
             serializer.serialize_none()
         }
@@ -185,10 +415,9 @@ where
     where
         D: Deserializer<'de>,
     {
-        use serde::de::Error;
         let option: Option<&[u8]> = Deserialize::deserialize(deserializer)?;
-        if let Some(ref buff) = option {
-            let rslt = Self::from_hex_raw(buff).map_err(D::Error::custom)?;
+        if let Some(buff) = option {
+            let rslt = Self::from_hex_raw(buff).map_err(|e| Self::de_error(e, buff))?;
             Ok(Some(rslt))
         } else {
             Ok(None)
@@ -242,22 +471,28 @@ where
         Self: 'a,
     {
         use serde::ser::Error;
-        let mut dst = SmallVec::<[u8; 128]>::new();
+        let sequence = sequence.into_iter();
+        let (count, _) = sequence.size_hint();
+        let mut dst = ScratchBuf::<[u8; 128]>::with_capacity(2 + count * Self::size() * 2);
         if <C as HexConf>::withpfx() {
-            dst.extend_from_slice(b"0x");
+            io::Write::write_all(&mut dst, utils::prefix::<C>()).map_err(S::Error::custom)?;
         }
         if <C as HexConf>::withcap() {
-            for elem in sequence.into_iter() {
+            for elem in sequence {
                 <Self as SerHex<StrictCap>>::into_hex_raw(elem, &mut dst)
                     .map_err(S::Error::custom)?;
             }
         } else {
-            for elem in sequence.into_iter() {
+            for elem in sequence {
                 <Self as SerHex<Strict>>::into_hex_raw(elem, &mut dst).map_err(S::Error::custom)?;
             }
         }
-        let s = unsafe { ::std::str::from_utf8_unchecked(dst.as_ref()) };
-        serializer.serialize_str(s)
+        let rslt = match ::std::str::from_utf8(dst.as_ref()) {
+            Ok(s) => serializer.serialize_str(s),
+            Err(err) => Err(S::Error::custom(err)),
+        };
+        utils::scrub(dst.as_mut());
+        rslt
     }
 
     /// Same as `SerHex::deserialize`, but for sequences of `Self`.
@@ -268,30 +503,477 @@ where
     {
         use serde::de::Error;
         let raw: &[u8] = Deserialize::deserialize(deserializer)?;
-        let src = if raw.starts_with(b"0x") {
+        let src = if raw.starts_with(b"0x") || raw.starts_with(b"0X") {
             &raw[2..]
         } else {
-            &raw[..]
+            raw
         };
         let hexsize = Self::size() * 2;
         if src.len() % hexsize == 0 {
-            let mut buff = Vec::with_capacity(src.len() / hexsize);
-            for chunk in src.chunks(hexsize) {
-                let elem =
-                    <Self as SerHex<Strict>>::from_hex_raw(chunk).map_err(D::Error::custom)?;
-                buff.push(elem);
-            }
-            Ok(buff.into_iter().collect())
+            src.chunks(hexsize)
+                .map(|chunk| {
+                    <Self as SerHex<Strict>>::from_hex_raw(chunk)
+                        .map_err(|err| <Self as SerHex<Strict>>::de_error(err, chunk))
+                })
+                .collect()
         } else {
-            Err(D::Error::custom("bad hexadecimal sequence size"))
+            let expect = SeqLen(hexsize);
+            Err(D::Error::invalid_length(src.len(), &expect))
+        }
+    }
+}
+
+/// Variant of `SerHex` for serializing/deserializing sequences as lists of
+/// individual hexadecimal strings (e.g. `["0x01","0x02"]`).
+///
+/// Any type `T` which implements `SerHex<C>` implements `SerHexList<C>`
+/// automatically.  Serialization accepts any container which derefs to a
+/// slice (`Vec<T>`, `[T; N]`, `&[T]`, ...) and deserialization any container
+/// which implements `FromIterator<T>`.  Fixed-size arrays can be deserialized
+/// via `deserialize_array` instead.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_json;
+/// # extern crate serde_hex;
+/// # use serde_hex::{SerHexList,CompactPfx,StrictPfx};
+/// #
+/// #[derive(Debug,PartialEq,Eq,Serialize,Deserialize)]
+/// struct Block {
+///     #[serde(with = "SerHexList::<StrictPfx>")]
+///     hashes: Vec<[u8;2]>,
+///     #[serde(serialize_with = "SerHexList::<CompactPfx>::serialize")]
+///     #[serde(deserialize_with = "SerHexList::<CompactPfx>::deserialize_array")]
+///     bounds: [u32;2],
+/// }
+///
+/// # fn main() {
+/// let block = Block { hashes: vec![[0xab,0xcd],[0x00,0x01]], bounds: [1,255] };
+/// let json = serde_json::to_string(&block).unwrap();
+/// assert_eq!(json,r#"{"hashes":["0xabcd","0x0001"],"bounds":["0x1","0xff"]}"#);
+/// assert_eq!(serde_json::from_str::<Block>(&json).unwrap(),block);
+/// # }
+/// ```
+pub trait SerHexList<C>: Sized + SerHex<C>
+where
+    C: HexConf,
+{
+    /// Same as `SerHex::serialize`, but for a sequence of `Self`.
+    fn serialize<S, L>(items: &L, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        L: ?Sized + AsRef<[Self]>,
+    {
+        let items = items.as_ref().iter().map(|item| HexRef {
+            src: item,
+            conf: PhantomData::<C>,
+        });
+        serializer.collect_seq(items)
+    }
+
+    /// Same as `SerHex::deserialize`, but for a sequence of `Self`.
+    fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromIterator<Self>,
+    {
+        deserializer.deserialize_seq(ListVisitor::<C, Self, T>(PhantomData))
+    }
+
+    /// Same as `SerHex::deserialize`, but for an array of `Self`.
+    fn deserialize_array<'de, D, const N: usize>(deserializer: D) -> Result<[Self; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(N, ArrayVisitor::<C, Self, N>(PhantomData))
+    }
+}
+
+impl<T, C> SerHexList<C> for T
+where
+    T: Sized + SerHex<C>,
+    C: HexConf,
+{
+}
+
+// serializes a borrowed value via `SerHex<C>`; elements of `SerHexList`.
+struct HexRef<'a, C, T: 'a> {
+    src: &'a T,
+    conf: PhantomData<C>,
+}
+
+impl<'a, C, T> Serialize for HexRef<'a, C, T>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        <T as SerHex<C>>::serialize(self.src, serializer)
+    }
+}
+
+// collects a sequence of hex strings into any `FromIterator` container.
+struct ListVisitor<C, E, T>(PhantomData<(C, E, T)>);
+
+impl<'de, C, E, T> de::Visitor<'de> for ListVisitor<C, E, T>
+where
+    C: HexConf,
+    E: SerHex<C>,
+    T: FromIterator<E>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of hex strings")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        utils::collect_until_err(|| {
+            seq.next_element::<Hex<E, C>>()
+                .map(|elem| elem.map(Hex::into_inner))
+        })
+    }
+}
+
+// collects a sequence of exactly `N` hex strings into an array.
+struct ArrayVisitor<C, E, const N: usize>(PhantomData<(C, E)>);
+
+impl<'de, C, E, const N: usize> de::Visitor<'de> for ArrayVisitor<C, E, N>
+where
+    C: HexConf,
+    E: SerHex<C>,
+{
+    type Value = [E; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an array of {} hex strings", N)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<[E; N], A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut items: [Option<E>; N] = array::from_fn(|_| None);
+        for (idx, item) in items.iter_mut().enumerate() {
+            match seq.next_element::<Hex<E, C>>()? {
+                Some(elem) => *item = Some(elem.into_inner()),
+                None => return Err(de::Error::invalid_length(idx, &self)),
+            }
         }
+        Ok(items.map(|item| item.expect("all elements are present")))
     }
 }
 
+// expectation reported when a sequence can't be split into elements.
+struct SeqLen(usize);
+
+impl de::Expected for SeqLen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a multiple of {} hex chars", self.0)
+    }
+}
+
+// how a hex value is handed to the serializer; either as-is (`SerHex`),
+// or wrapped in `Some` (`SerHexOpt`).
+trait Emit {
+    fn emit<S, V>(serializer: S, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        V: ?Sized + Serialize;
+}
+
+struct EmitPlain;
+
+impl Emit for EmitPlain {
+    fn emit<S, V>(serializer: S, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        V: ?Sized + Serialize,
+    {
+        value.serialize(serializer)
+    }
+}
+
+struct EmitSome;
+
+impl Emit for EmitSome {
+    fn emit<S, V>(serializer: S, value: &V) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        V: ?Sized + Serialize,
+    {
+        serializer.serialize_some(value)
+    }
+}
+
+// formats `src` as hex straight into a formatter, and serializes via
+// `collect_str`.  serializers which implement `collect_str` by writing
+// to their output (e.g. `serde_json`) never buffer the hex at all.
+struct HexDisplay<'a, C: HexConf, T: 'a + SerHex<C>> {
+    src: &'a T,
+    // if set, the first encoding error is recorded here and formatting
+    // succeeds, since `collect_str` can only see a bare `fmt::Error` (which
+    // e.g. `serde_json` treats as a bug, and panics).
+    err: Option<Cell<Option<T::Error>>>,
+    conf: PhantomData<C>,
+}
+
+impl<'a, C, T> fmt::Display for HexDisplay<'a, C, T>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.src.into_hex_raw(io::FmtWriter(f)), &self.err) {
+            (Ok(()), _) => Ok(()),
+            (Err(err), Some(cell)) => {
+                let first = cell.take().unwrap_or(err);
+                cell.set(Some(first));
+                Ok(())
+            }
+            (Err(_), None) => Err(fmt::Error),
+        }
+    }
+}
+
+impl<'a, C, T> Serialize for HexDisplay<'a, C, T>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+// serialize `src` as a hex string, using a stack buffer large enough to
+// hold `src.encoded_len()` bytes if one of the inline sizes fits (and the
+// config doesn't ask for `collect_str`), and formatting directly into the
+// serializer otherwise.  no path allocates unless `encoded_len` is an
+// underestimate (or the serializer allocates in `collect_str`).
+fn serialize_hex<C, T, S, E>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    C: HexConf,
+    T: SerHex<C>,
+    S: Serializer,
+    E: Emit,
+{
+    let len = src.encoded_len();
+    if C::collectstr() {
+        serialize_display::<C, T, S, E>(src, serializer)
+    } else if len <= 64 {
+        serialize_buffered::<ScratchBuf<[u8; 64]>, C, T, S, E>(src, len, serializer)
+    } else if len <= 128 {
+        serialize_buffered::<ScratchBuf<[u8; 128]>, C, T, S, E>(src, len, serializer)
+    } else if len <= 256 {
+        serialize_buffered::<ScratchBuf<[u8; 256]>, C, T, S, E>(src, len, serializer)
+    } else {
+        serialize_display::<C, T, S, E>(src, serializer)
+    }
+}
+
+fn serialize_display<C, T, S, E>(src: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    C: HexConf,
+    T: SerHex<C>,
+    S: Serializer,
+    E: Emit,
+{
+    use serde::ser::Error;
+    let display = HexDisplay {
+        src,
+        err: Some(Cell::new(None)),
+        conf: PhantomData::<C>,
+    };
+    let rslt = E::emit(serializer, &display);
+    // an encoding error leaves partial output, so takes precedence.
+    match display.err.and_then(Cell::into_inner) {
+        Some(err) => Err(S::Error::custom(err)),
+        None => rslt,
+    }
+}
+
+fn serialize_buffered<B, C, T, S, E>(src: &T, len: usize, serializer: S) -> Result<S::Ok, S::Error>
+where
+    B: Scratch,
+    C: HexConf,
+    T: SerHex<C>,
+    S: Serializer,
+    E: Emit,
+{
+    use serde::ser::Error;
+    let mut dst = B::with_capacity(len);
+    let rslt = match src.into_hex_raw(&mut dst) {
+        // if `dst` is not valid UTF-8 bytes, the underlying implementation
+        // is very broken, but that's still an error rather than a panic.
+        Ok(()) => match ::std::str::from_utf8(dst.as_ref()) {
+            Ok(s) => E::emit(serializer, s),
+            Err(err) => Err(S::Error::custom(err)),
+        },
+        Err(err) => Err(S::Error::custom(err)),
+    };
+    // `dst` is sized from `encoded_len`, so it is only reallocated (leaving
+    // an unscrubbed copy behind) if the hint was an underestimate.
+    utils::scrub(dst.as_mut());
+    rslt
+}
+
+// copy the characters of `src` into `dst`, failing on any which aren't
+// ascii (and so can't be hex).
+fn collect_chars<B, I>(dst: &mut B, src: I) -> Result<(), Error>
+where
+    B: Scratch,
+    I: IntoIterator<Item = char>,
+{
+    for (index, val) in src.into_iter().enumerate() {
+        if !val.is_ascii() {
+            return Err(ParseHexError::Char { val, index }.into());
+        }
+        io::Write::write_all(dst, &[val as u8])?;
+    }
+    Ok(())
+}
+
+// length-agnostic body of `into_hex_raw` for the blanket array impls
+// generated by `impl_serhex_strict_array`.  the first element is
+// serialized with `C` to allow prefixing if specified.  plus this has
+// the handy side-effect of preventing impls of `Compact` variants,
+// since they are not part of the constraints on `T`.
+fn into_hex_array<C, T, D, E>(src: &[T], mut dst: D) -> Result<(), E>
+where
+    C: HexConf,
+    T: SerHex<C, Error = E> + SerHex<Strict, Error = E> + SerHex<StrictCap, Error = E>,
+    D: io::Write,
+{
+    let mut items = src.iter();
+    match items.next() {
+        Some(itm) => <T as SerHex<C>>::into_hex_raw(itm, &mut dst)?,
+        // should only happen in the `[T;0]` case.
+        None => return Ok(()),
+    }
+    if <C as HexConf>::withcap() {
+        for itm in items {
+            <T as SerHex<StrictCap>>::into_hex_raw(itm, &mut dst)?;
+        }
+    } else {
+        for itm in items {
+            <T as SerHex<Strict>>::into_hex_raw(itm, &mut dst)?;
+        }
+    }
+    Ok(())
+}
+
+// width of each element when `hex` is split evenly into `len` elements,
+// for the blanket array impls.
+fn array_width<E>(hex: &[u8], len: usize) -> Result<usize, E>
+where
+    E: From<Error>,
+{
+    let width = hex.len() / len;
+    if width == 0 || width * len != hex.len() {
+        // input can't be split evenly into `len` elements.  report the
+        // closest size which could be, given elements of at least a byte.
+        let expect = std::cmp::max(width, 2) * len;
+        let inner = ParseHexError::size(expect, hex.len());
+        return Err(Error::from(inner).into());
+    }
+    Ok(width)
+}
+
+// length-agnostic body of `encoded_len` for the blanket array impls.
+fn encoded_len_array<C, T, E>(src: &[T]) -> usize
+where
+    C: HexConf,
+    T: SerHex<C, Error = E> + SerHex<Strict, Error = E>,
+{
+    let mut items = src.iter();
+    let head = items.next().map_or(0, <T as SerHex<C>>::encoded_len);
+    items.fold(head, |len, itm| {
+        len + <T as SerHex<Strict>>::encoded_len(itm)
+    })
+}
+
 impl_serhex_uint!(u8, 1);
 impl_serhex_uint!(u16, 2);
 impl_serhex_uint!(u32, 4);
 impl_serhex_uint!(u64, 8);
+impl_serhex_uint!(u128, 16);
+// signed integers are written as their two's-complement bit pattern, so
+// negative values are never trimmed by compact configs.
+impl_serhex_uint!(i128, 16);
+
+// implement `SerHex` for a pointer-sized integer at the fixed width of
+// `$wide` (carried as the bits of a `u64`), so that output doesn't vary with
+// the platform.  parsing a value which doesn't fit the target fails with
+// `ParseHexError::Overflow`.
+macro_rules! impl_serhex_portable {
+    ($type: ty, $wide: ty) => {
+        impl_serhex_seq!($type, 8);
+        impl<C> SerHex<C> for $type
+        where
+            C: HexConf,
+        {
+            type Error = types::Error;
+            fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+            where
+                D: io::Write,
+            {
+                <u64 as SerHex<C>>::into_hex_raw(&(*self as $wide as u64), dst)
+            }
+            fn encoded_len(&self) -> usize {
+                <u64 as SerHex<C>>::encoded_len(&(*self as $wide as u64))
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+            fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                // parsed as `$wide` so that saturation at 64 bits follows its
+                // sign, then clamped again to the (possibly narrower) target.
+                let src = src.as_ref();
+                let mut buf = [0u8; 8];
+                if let Err(err) = utils::fromhex_with::<config::IntConf<C>>(&mut buf, src) {
+                    utils::int_overflow::<C>(err, src, <$wide>::MIN != 0, &mut buf)?;
+                }
+                let bits = u64::from_be_bytes(buf);
+                let wide = <$wide>::from_be_bytes(buf);
+                match (<$type>::try_from(wide), C::overflow()) {
+                    (Ok(val), _) => Ok(val),
+                    (Err(_), OverflowMode::Wrap) => Ok(wide as $type),
+                    (Err(_), OverflowMode::Saturate) => {
+                        Ok(wide.clamp(<$type>::MIN as $wide, <$type>::MAX as $wide) as $type)
+                    }
+                    (Err(_), OverflowMode::Error) => Err(ParseHexError::Overflow {
+                        bits: <$type>::BITS as usize,
+                        value: Some(u128::from(bits)),
+                    }
+                    .into()),
+                }
+            }
+        }
+    };
+}
+
+// `usize` and `isize` are always written at 64 bits (`isize` as its
+// two's-complement bit pattern), so that values written on 64-bit targets
+// parse predictably on 32-bit ones.
+impl_serhex_portable!(usize, u64);
+impl_serhex_portable!(isize, i64);
 
 // implement strict variants of `SerHex` for arrays of `T` with
 // lengths of 1 through 64 (where `T` implements the strict variants
@@ -301,3 +983,132 @@ impl_serhex_strict_array!(
     27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50,
     51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64
 );
+
+// implement constant-time variants of `SerHex` for byte-arrays
+// of lengths 1 through 64.
+#[cfg(feature = "constant-time")]
+impl_serhex_ct_bytearray!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50,
+    51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64
+);
+
+// `Cell` and `RefCell` forward to the impls for the values they hold, which
+// are copied out of (or borrowed from) the cell at serialize time.  encoding
+// a `RefCell` which is mutably borrowed fails (with `Error::Borrowed`, via
+// `into_hex_raw`), as serde's own impl does.
+impl<C, T> SerHex<C> for Cell<T>
+where
+    C: HexConf,
+    T: SerHex<C> + Copy,
+{
+    type Error = T::Error;
+
+    fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+    where
+        D: io::Write,
+    {
+        self.get().into_hex_raw(dst)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.get().encoded_len()
+    }
+
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        T::de_error(err, src)
+    }
+
+    fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        T::from_hex_raw(src).map(Cell::new)
+    }
+
+    fn from_hex_into<S>(src: S, dst: &mut Self) -> Result<(), Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        T::from_hex_into(src, dst.get_mut())
+    }
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.get().serialize(serializer)
+    }
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Cell::new)
+    }
+}
+
+impl<C, T> SerHex<C> for RefCell<T>
+where
+    C: HexConf,
+    T: SerHex<C>,
+    T::Error: From<types::Error>,
+{
+    type Error = T::Error;
+
+    fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+    where
+        D: io::Write,
+    {
+        match self.try_borrow() {
+            Ok(val) => val.into_hex_raw(dst),
+            Err(_) => Err(types::Error::Borrowed.into()),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        // nothing is written if the borrow fails.
+        self.try_borrow().map_or(0, |val| val.encoded_len())
+    }
+
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        T::de_error(err, src)
+    }
+
+    fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        T::from_hex_raw(src).map(RefCell::new)
+    }
+
+    fn from_hex_into<S>(src: S, dst: &mut Self) -> Result<(), Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        T::from_hex_into(src, dst.get_mut())
+    }
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.try_borrow() {
+            Ok(val) => val.serialize(serializer),
+            Err(_) => Err(ser::Error::custom(types::Error::Borrowed)),
+        }
+    }
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(RefCell::new)
+    }
+}
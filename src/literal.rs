@@ -0,0 +1,281 @@
+//! Byte data as source-code array literals.
+//!
+//! Code generators often need to embed bytes in Rust (`[0xde, 0xad]`) or C
+//! (`{0xde, 0xad}`) source.  `Literal` renders and parses these, optionally
+//! wrapped onto lines of a fixed number of elements:
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # use serde_hex::literal::{Lang, Literal};
+//! # use serde_hex::{Strict, StrictCap};
+//! # fn main() {
+//! let rust = Literal::<Strict>::new(Lang::Rust);
+//! assert_eq!(rust.encode(&[0xde, 0xad, 0xbe, 0xef]), "[0xde, 0xad, 0xbe, 0xef]");
+//! let c = Literal::<StrictCap>::new(Lang::C).width(2);
+//! let text = c.encode(&[0xde, 0xad, 0xbe]);
+//! assert_eq!(text, "{\n    0xDE, 0xAD,\n    0xBE,\n}");
+//! assert_eq!(c.decode(&text).unwrap(), vec![0xde, 0xad, 0xbe]);
+//! # }
+//! ```
+use alloc::string::String;
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::str::CharIndices;
+use std::{error, fmt};
+use types::ParseHexError;
+use utils;
+
+/// Source language of a literal, which selects its brackets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    /// an array expression, in square brackets
+    Rust,
+    /// an initializer list, in braces
+    C,
+}
+
+impl Lang {
+    // opening and closing brackets.
+    fn brackets(self) -> (char, char) {
+        match self {
+            Lang::Rust => ('[', ']'),
+            Lang::C => ('{', '}'),
+        }
+    }
+}
+
+/// Renderer and parser for array literals in a given language.
+///
+/// Elements are always written as `0x` and two digits, in the case
+/// selected by `C` (the other options of `C` don't apply).  Literals are
+/// written on a single line unless a width is set with `width`, in which
+/// case each line holds that many elements (indented by `indent` spaces)
+/// and ends in a comma.
+pub struct Literal<C = Strict> {
+    lang: Lang,
+    width: Option<usize>,
+    indent: usize,
+    conf: PhantomData<C>,
+}
+
+impl<C: HexConf> Literal<C> {
+    /// literals in `lang`, on a single line.
+    pub fn new(lang: Lang) -> Self {
+        Literal {
+            lang,
+            width: None,
+            indent: 4,
+            conf: PhantomData,
+        }
+    }
+
+    /// wrap literals onto lines of `width` elements (zero is treated as one).
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width.max(1));
+        self
+    }
+
+    /// set the number of spaces before each wrapped line (default 4).
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// render `data` as a literal.
+    pub fn encode(&self, data: &[u8]) -> String {
+        let (open, close) = self.lang.brackets();
+        let mut out = String::with_capacity(data.len() * 6 + 2);
+        out.push(open);
+        match self.width {
+            Some(width) if !data.is_empty() => {
+                for line in data.chunks(width) {
+                    out.push('\n');
+                    out.extend((0..self.indent).map(|_| ' '));
+                    for (idx, byte) in line.iter().enumerate() {
+                        if idx > 0 {
+                            out.push(' ');
+                        }
+                        self.push_byte(&mut out, *byte);
+                        out.push(',');
+                    }
+                }
+                out.push('\n');
+            }
+            _ => {
+                for (idx, byte) in data.iter().enumerate() {
+                    if idx > 0 {
+                        out.push_str(", ");
+                    }
+                    self.push_byte(&mut out, *byte);
+                }
+            }
+        }
+        out.push(close);
+        out
+    }
+
+    /// parse a literal in this language.  elements may have one or two
+    /// digits in either case, whitespace (including line breaks) is
+    /// allowed between tokens, and a trailing comma is optional.  the
+    /// index of a `Char` error is relative to `text`.
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, LiteralError> {
+        let (open, close) = self.lang.brackets();
+        let mut chars = text.char_indices().peekable();
+        skip_ws(&mut chars);
+        match chars.next() {
+            Some((_, c)) if c == open => {}
+            _ => return Err(LiteralError::MissingOpen),
+        }
+        let mut data = Vec::new();
+        loop {
+            skip_ws(&mut chars);
+            match chars.peek() {
+                None => return Err(LiteralError::Unterminated),
+                Some(&(_, c)) if c == close => {
+                    chars.next();
+                    break;
+                }
+                _ => {}
+            }
+            data.push(element(&mut chars)?);
+            skip_ws(&mut chars);
+            match chars.next() {
+                None => return Err(LiteralError::Unterminated),
+                Some((_, ',')) => {}
+                Some((_, c)) if c == close => break,
+                Some((index, val)) => return Err(ParseHexError::Char { val, index }.into()),
+            }
+        }
+        skip_ws(&mut chars);
+        match chars.next() {
+            None => Ok(data),
+            Some((index, val)) => Err(ParseHexError::Char { val, index }.into()),
+        }
+    }
+
+    fn push_byte(&self, out: &mut String, byte: u8) {
+        let digit = if C::withcap() {
+            utils::fromvalcaps
+        } else {
+            utils::fromval
+        };
+        out.push_str("0x");
+        out.push(char::from(digit(byte >> 4)));
+        out.push(char::from(digit(byte & 0x0f)));
+    }
+}
+
+impl<C> Clone for Literal<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for Literal<C> {}
+
+/// error raised when parsing a literal.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LiteralError {
+    /// the literal did not begin with the language's opening bracket
+    MissingOpen,
+    /// the input ended before the closing bracket
+    Unterminated,
+    /// an element was not a `0x`-prefixed byte, or elements were not
+    /// separated by commas
+    Parsing(ParseHexError),
+}
+
+impl fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LiteralError::MissingOpen => f.write_str("missing opening bracket"),
+            LiteralError::Unterminated => f.write_str("missing closing bracket"),
+            LiteralError::Parsing(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for LiteralError {}
+
+impl From<ParseHexError> for LiteralError {
+    fn from(err: ParseHexError) -> Self {
+        LiteralError::Parsing(err)
+    }
+}
+
+type Chars<'t> = Peekable<CharIndices<'t>>;
+
+fn skip_ws(chars: &mut Chars) {
+    while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+}
+
+// parse a single `0x`-prefixed element of one or two digits.
+fn element(chars: &mut Chars) -> Result<u8, LiteralError> {
+    let zero = chars.next_if(|&(_, c)| c == '0').is_some();
+    if !zero || chars.next_if(|&(_, c)| c == 'x' || c == 'X').is_none() {
+        return Err(ParseHexError::MissingPrefix.into());
+    }
+    let mut val = 0u8;
+    let mut digits = 0;
+    while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_hexdigit()) {
+        if digits == 2 {
+            let value = None;
+            return Err(ParseHexError::Overflow { bits: 8, value }.into());
+        }
+        val = (val << 4) | utils::intoval(c as u8)?;
+        digits += 1;
+    }
+    match chars.peek() {
+        _ if digits > 0 => Ok(val),
+        None => Err(LiteralError::Unterminated),
+        Some(&(index, val)) => Err(ParseHexError::Char { val, index }.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lang, Literal, LiteralError};
+    use config::Strict;
+    use types::ParseHexError;
+
+    #[test]
+    fn literals() {
+        let rust = Literal::<Strict>::new(Lang::Rust);
+        assert_eq!(rust.encode(&[]), "[]");
+        assert_eq!(rust.width(2).encode(&[]), "[]");
+        assert_eq!(
+            rust.width(1).indent(2).encode(&[1, 2]),
+            "[\n  0x01,\n  0x02,\n]"
+        );
+        let text = " [ 0xA,0x0b ,\n 0Xff, ] ";
+        assert_eq!(rust.decode(text).unwrap(), vec![0x0a, 0x0b, 0xff]);
+        assert_eq!(rust.decode("[]").unwrap(), Vec::<u8>::new());
+        match rust.decode("{0x01}") {
+            Err(LiteralError::MissingOpen) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match rust.decode("[0x01, 0x02") {
+            Err(LiteralError::Unterminated) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match rust.decode("[0x01 0x02]") {
+            Err(LiteralError::Parsing(ParseHexError::Char { val: '0', index: 6 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match rust.decode("[0x100]") {
+            Err(LiteralError::Parsing(ParseHexError::Overflow { bits: 8, .. })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match rust.decode("[1]") {
+            Err(LiteralError::Parsing(ParseHexError::MissingPrefix)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match rust.decode("[0x1];") {
+            Err(LiteralError::Parsing(ParseHexError::Char { val: ';', index: 5 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -30,138 +30,1076 @@ macro_rules! impl_serhex_seq {
     };
 }
 
+/// Implement `SerHex` for an unsigned integer, or for a newtype around one.
+///
+/// The newtype form (e.g. `impl_serhex_uint!(BlockNumber(u64))`) delegates
+/// to the impls for the inner integer, so strict configs always emit the full
+/// width of the integer and compact configs trim leading zeroes.
+///
+/// The primitive form (e.g. `impl_serhex_uint!(u64, 8)`) implements `SerHex`
+/// for an integer with a size equivalent to `$bytes` (signed integers are
+/// written as their two's-complement bit pattern).  Conversion goes through
+/// the byte-array helpers via `to_be_bytes`/`from_be_bytes`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactPfx, SerHex, StrictPfx};
+/// #[derive(Debug, PartialEq)]
+/// struct ChainId(u32);
+/// impl_serhex_uint!(ChainId(u32));
+///
+/// # fn main() {
+/// let id = ChainId(0x2a);
+/// assert_eq!(<ChainId as SerHex<StrictPfx>>::into_hex(&id).unwrap(), "0x0000002a");
+/// assert_eq!(<ChainId as SerHex<CompactPfx>>::into_hex(&id).unwrap(), "0x2a");
+/// assert_eq!(<ChainId as SerHex<CompactPfx>>::from_hex("0x2a").unwrap(), id);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_serhex_uint {
+    ($outer: ident($inner: ty)) => {
+        impl_serhex_seq!($outer, $crate::export::mem::size_of::<$inner>());
+        impl<C> $crate::SerHex<C> for $outer
+        where
+            C: $crate::HexConf,
+        {
+            type Error = <$inner as $crate::SerHex<C>>::Error;
+            fn into_hex_raw<D>(&self, dst: D) -> $crate::export::Result<(), Self::Error>
+            where
+                D: $crate::io::Write,
+            {
+                <$inner as $crate::SerHex<C>>::into_hex_raw(&self.0, dst)
+            }
+            fn encoded_len(&self) -> usize {
+                <$inner as $crate::SerHex<C>>::encoded_len(&self.0)
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                <$inner as $crate::SerHex<C>>::de_error(err, src)
+            }
+            fn from_hex_raw<S>(src: S) -> $crate::export::Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                <$inner as $crate::SerHex<C>>::from_hex_raw(src).map($outer)
+            }
+            fn from_hex_into<S>(src: S, dst: &mut Self) -> $crate::export::Result<(), Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                <$inner as $crate::SerHex<C>>::from_hex_into(src, &mut dst.0)
+            }
+        }
+    };
+    ($type: ty, $bytes: expr) => {
+        impl_serhex_seq!($type, $bytes);
+        impl<C> $crate::SerHex<C> for $type
+        where
+            C: $crate::HexConf,
+        {
+            type Error = $crate::types::Error;
+            fn into_hex_raw<D>(&self, mut dst: D) -> $crate::export::Result<(), Self::Error>
+            where
+                D: $crate::io::Write,
+            {
+                let bytes: [u8; $bytes] = self.to_be_bytes();
+                into_hex_bytearray!(bytes, dst, $bytes, $crate::config::IntConf<C>)?;
+                Ok(())
+            }
+            fn encoded_len(&self) -> usize {
+                encoded_len_bytearray!($bytes)
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+            fn from_hex_raw<S>(src: S) -> $crate::export::Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                let src = src.as_ref();
+                let rslt: $crate::export::Result<[u8; $bytes], Self::Error> =
+                    from_hex_bytearray!(src, $bytes, $crate::config::IntConf<C>);
+                match rslt {
+                    Ok(buf) => Ok(<$type>::from_be_bytes(buf)),
+                    Err($crate::types::Error::Parsing(err)) => {
+                        let mut buf = [0u8; $bytes];
+                        let signed = <$type>::MIN != 0;
+                        match $crate::utils::int_overflow::<C>(err, src, signed, &mut buf) {
+                            Ok(()) => Ok(<$type>::from_be_bytes(buf)),
+                            Err(err) => Err(err.into()),
+                        }
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    };
+}
+
 /// helper macro for implementing the `into_hex_raw` function for
-/// bytearray-style types.
+/// bytearray-style types.  offloads encoding to the non-generic
+/// helpers in `utils` so that each expansion stays small.  encodes under
+/// config `C` unless another is given.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! into_hex_bytearray {
-    ($src: ident, $dst: ident, $len: expr) => {{
+    ($src: ident, $dst: ident, $len: expr) => {
+        into_hex_bytearray!($src, $dst, $len, C)
+    };
+    ($src: ident, $dst: ident, $len: expr, $conf: ty) => {{
         let src: &[u8] = $src.as_ref();
         debug_assert!(src.len() == $len);
-        // add prefix if we are doing such things.
-        if <C as $crate::HexConf>::withpfx() {
-            $dst.write_all("0x".as_bytes())?;
-        }
-        // if
-        if <C as $crate::HexConf>::compact() {
-            // find index and location of first non-zero byte.
-            if let Some((idx, val)) = src.iter().enumerate().find(|&(_, v)| *v > 0u8) {
-                // if first non-zero byte is less than `0x10`, repr w/ one hex char.
-                if *val < 0x10 {
-                    if <C as $crate::HexConf>::withcap() {
-                        $dst.write_all(&[$crate::utils::fromvalcaps(*val)])?;
-                        $crate::utils::writehexcaps(&src[(idx + 1)..], $dst)
-                    } else {
-                        $dst.write_all(&[$crate::utils::fromval(*val)])?;
-                        $crate::utils::writehex(&src[(idx + 1)..], $dst)
-                    }
-                } else {
-                    if <C as $crate::HexConf>::withcap() {
-                        $crate::utils::writehexcaps(&src[idx..], $dst)
-                    } else {
-                        $crate::utils::writehex(&src[idx..], $dst)
-                    }
-                }
-            // if no non-zero byte was found, just write in a zero.
-            } else {
-                $dst.write_all(&[b'0'])?;
-                Ok(())
-            }
-        } else {
-            if <C as $crate::HexConf>::withcap() {
-                $crate::utils::writehexcaps(src, $dst)
-            } else {
-                $crate::utils::writehex(src, $dst)
+        match <$conf as $crate::HexConf>::width() {
+            Some(width) => $crate::utils::writehex_width::<$conf, _>(src, width, &mut $dst),
+            _ => {
+                let mut buf = [0u8; $len * 2 + 2];
+                let len = $crate::utils::intohex_with::<$conf>(&mut buf, src);
+                let rslt = $dst.write_all(&buf[..len]);
+                $crate::utils::scrub(&mut buf);
+                rslt.map_err($crate::types::Error::from)
             }
         }
     }};
 }
 
-/// helper macro for implementing the `into_hex_raw` function for
-/// bytearray-style types.
+/// helper macro for implementing the `encoded_len` function for
+/// bytearray-style types.  yields the strict size (or the fixed width of
+/// the config, if any), which is also an upper bound for compact
+/// representations.
 #[doc(hidden)]
 #[macro_export]
-macro_rules! from_hex_bytearray {
-    ($src: ident, $len: expr) => {{
-        let raw: &[u8] = $src.as_ref();
-        let hex = if <C as $crate::HexConf>::withpfx() {
-            let pfx = "0x".as_bytes();
-            if raw.starts_with(pfx) {
-                &raw[2..]
-            } else {
-                raw
-            }
+macro_rules! encoded_len_bytearray {
+    ($len: expr) => {{
+        let pfx = if <C as $crate::HexConf>::withpfx() {
+            2
         } else {
-            raw
+            0
         };
+        pfx + <C as $crate::HexConf>::width().unwrap_or($len * 2)
+    }};
+}
+
+/// helper macro for implementing the `from_hex_raw` function for
+/// bytearray-style types.  offloads decoding to the non-generic
+/// helpers in `utils` so that each expansion stays small.  decodes under
+/// config `C` unless another is given.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! from_hex_bytearray {
+    ($src: ident, $len: expr) => {
+        from_hex_bytearray!($src, $len, C)
+    };
+    ($src: ident, $len: expr, $conf: ty) => {{
         let mut buf = [0u8; $len];
-        if <C as $crate::HexConf>::compact() {
-            let min = 1;
-            let max = $len * 2;
-            let got = hex.len();
-            if got < min || got > max {
-                let inner = $crate::types::ParseHexError::Range { min, max, got };
-                let error = $crate::types::Error::from(inner);
-                return Err(error.into());
-            }
-            let body = $len - (got / 2);
-            let head = got % 2;
-            if head > 0 {
-                buf[body - head] = $crate::utils::intobyte(b'0', hex[0])?;
-            }
-            $crate::utils::fromhex(&mut buf[body..], &hex[head..])?;
-        } else {
-            $crate::utils::fromhex(&mut buf[..], hex)?;
+        match $crate::utils::fromhex_with::<$conf>(&mut buf, $src.as_ref()) {
+            Ok(()) => Ok(buf),
+            Err(inner) => Err($crate::types::Error::from(inner).into()),
         }
-        Ok(buf)
+    }};
+}
+
+/// Parse a hexadecimal literal into a byte-array at compile time, following
+/// the same rules as the runtime parser for the named config.
+///
+/// The config must be one of the built-in configs (e.g. `StrictPfx`), named
+/// without a path.  The size of the array is inferred from the literal
+/// unless supplied explicitly, which allows compact literals to be padded.
+/// Invalid literals fail compilation when used in a const context.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate serde_hex;
+///
+/// const KEY: [u8; 4] = serhex!(StrictPfx, "0xdeadbeef");
+/// const NUM: [u8; 4] = serhex!(Compact, "abc", 4);
+///
+/// # fn main() {
+/// assert_eq!(KEY, [0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(NUM, [0x00, 0x00, 0x0a, 0xbc]);
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// const BAD: [u8; 2] = serhex!(Strict, "0x00");
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! serhex {
+    (@conf Strict) => {
+        (false, false)
+    };
+    (@conf StrictPfx) => {
+        (false, true)
+    };
+    (@conf StrictCap) => {
+        (false, false)
+    };
+    (@conf StrictCapPfx) => {
+        (false, true)
+    };
+    (@conf Compact) => {
+        (true, false)
+    };
+    (@conf CompactPfx) => {
+        (true, true)
+    };
+    (@conf CompactCap) => {
+        (true, false)
+    };
+    (@conf CompactCapPfx) => {
+        (true, true)
+    };
+    ($conf: ident, $src: expr) => {{
+        const SRC: &str = $src;
+    const CONF: (bool, bool) = $crate::serhex!(@conf $conf);
+        const LEN: usize = $crate::utils::const_decoded_len(SRC, CONF.0, CONF.1);
+        const BYTES: [u8; LEN] = $crate::utils::const_decode::<LEN>(SRC, CONF.0, CONF.1);
+        BYTES
+    }};
+    ($conf: ident, $src: expr, $len: expr) => {{
+        const SRC: &str = $src;
+    const CONF: (bool, bool) = $crate::serhex!(@conf $conf);
+        const BYTES: [u8; $len] = $crate::utils::const_decode::<{ $len }>(SRC, CONF.0, CONF.1);
+        BYTES
     }};
 }
 
 /// macro for implementing `SerHex` for a type which implements
 /// `From<[u8;n]>` and `AsRef<[u8]>`.
+///
+/// The `serde` form additionally implements `Serialize` and `Deserialize`
+/// via `SerHex<$conf>`, so the type is hex-encoded without needing a
+/// `#[serde(with = "...")]` attribute at each use site.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// #[derive(Debug, Clone)]
+/// struct Key([u8; 4]);
+/// impl_newtype_bytearray!(Key, 4);
+/// impl_serhex_bytearray!(Key, 4, serde_hex::StrictPfx, serde);
+///
+/// # fn main() {
+/// let json = serde_json::to_string(&Key([0xde, 0xad, 0xbe, 0xef])).unwrap();
+/// assert_eq!(json, "\"0xdeadbeef\"");
+/// # }
+/// ```
 #[macro_export]
 macro_rules! impl_serhex_bytearray {
-    ($type: ty, $len: expr) => {
+    (@serde $type: ty, $conf: ty) => {
+        impl $crate::serde::Serialize for $type {
+            fn serialize<S>(&self, serializer: S) -> $crate::export::Result<S::Ok, S::Error>
+            where
+                S: $crate::serde::Serializer,
+            {
+                <$type as $crate::SerHex<$conf>>::serialize(self, serializer)
+            }
+        }
+        impl<'de> $crate::serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> $crate::export::Result<Self, D::Error>
+            where
+                D: $crate::serde::Deserializer<'de>,
+            {
+                <$type as $crate::SerHex<$conf>>::deserialize(deserializer)
+            }
+            fn deserialize_in_place<D>(
+                deserializer: D,
+                place: &mut Self,
+            ) -> $crate::export::Result<(), D::Error>
+            where
+                D: $crate::serde::Deserializer<'de>,
+            {
+                <$type as $crate::SerHex<$conf>>::deserialize_in_place(deserializer, place)
+            }
+        }
+    };
+    // newtypes over `[u8; $len]` decode straight into the wrapped array.
+    (@newtype $outer: ident, $len: expr) => {
+        impl_serhex_bytearray!(@impl $outer, $len, {
+            fn from_hex_into<S>(src: S, dst: &mut Self) -> $crate::export::Result<(), Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                $crate::utils::fromhex_with::<C>(&mut dst.0, src.as_ref())?;
+                Ok(())
+            }
+        });
+    };
+    (@impl $type: ty, $len: expr, { $($into: tt)* }) => {
         impl_serhex_seq!($type, $len);
         impl<C> $crate::SerHex<C> for $type
         where
             C: $crate::HexConf,
         {
             type Error = $crate::types::Error;
-            fn into_hex_raw<D>(&self, mut dst: D) -> ::std::result::Result<(), Self::Error>
+            fn into_hex_raw<D>(&self, mut dst: D) -> $crate::export::Result<(), Self::Error>
             where
-                D: ::std::io::Write,
+                D: $crate::io::Write,
             {
                 into_hex_bytearray!(self, dst, $len)?;
                 Ok(())
             }
-            fn from_hex_raw<S>(src: S) -> ::std::result::Result<Self, Self::Error>
+            fn encoded_len(&self) -> usize {
+                encoded_len_bytearray!($len)
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+            fn from_hex_raw<S>(src: S) -> $crate::export::Result<Self, Self::Error>
             where
                 S: AsRef<[u8]>,
             {
-                let rslt: ::std::result::Result<[u8; $len], Self::Error> =
+                let rslt: $crate::export::Result<[u8; $len], Self::Error> =
                     from_hex_bytearray!(src, $len);
                 match rslt {
                     Ok(buf) => Ok(buf.into()),
                     Err(e) => Err(e),
                 }
             }
+            $($into)*
+        }
+    };
+    ($type: ty, $len: expr, $conf: ty, serde) => {
+        impl_serhex_bytearray!($type, $len);
+        impl_serhex_bytearray!(@serde $type, $conf);
+    };
+    ($type: ty, $len: expr) => {
+        impl_serhex_bytearray!(@impl $type, $len, {});
+    };
+}
+
+/// Declare a byte-array newtype and implement the full suite of traits for it
+/// in one go: the `impl_newtype_bytearray` conveniences, `SerHex` for all
+/// configs, `Serialize`/`Deserialize`, `Display` and `FromStr` via the given
+/// config, as well as `Clone`, `Copy`, `PartialEq`, `Eq`, `PartialOrd`,
+/// `Ord`, `Hash`, `Default`, and a `Debug` impl which shows the hex encoding.
+/// Any attributes (e.g. doc comments) are applied to the declared struct.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// define_hex_array!(
+///     /// hash of a transaction.
+///     pub struct TxHash(4),
+///     config = serde_hex::StrictPfx
+/// );
+///
+/// # fn main() {
+/// let hash: TxHash = "0xdeadbeef".parse().unwrap();
+/// assert_eq!(hash.to_string(), "0xdeadbeef");
+/// assert_eq!(format!("{:?}", hash), "TxHash(0xdeadbeef)");
+/// assert_eq!(serde_json::to_string(&hash).unwrap(), "\"0xdeadbeef\"");
+/// assert_eq!(TxHash::default(), TxHash([0; 4]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! define_hex_array {
+    ($(#[$meta: meta])* $vis: vis struct $name: ident($len: expr), config = $conf: ty $(,)*) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $name(pub [u8; $len]);
+
+        impl_newtype_bytearray!($name, $len, $conf);
+        impl_serhex_bytearray!(@newtype $name, $len);
+        impl_serhex_bytearray!(@serde $name, $conf);
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name([0u8; $len])
+            }
+        }
+
+        impl $crate::export::fmt::Debug for $name {
+            fn fmt(&self, f: &mut $crate::export::fmt::Formatter) -> $crate::export::fmt::Result {
+                write!(f, "{}({})", stringify!($name), self)
+            }
         }
     };
 }
 
+/// Generate a module containing `serialize` and `deserialize` functions for
+/// `$type` under config `$conf`, for use as `#[serde(with = "module")]`.  This
+/// avoids the turbofish syntax of `SerHex::<Conf>`, which some tooling can't
+/// parse.  Paths are resolved relative to the parent of the new module.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_json;
+/// use serde_hex::StrictPfx;
+///
+/// serhex_mod!(tx_hash_hex, [u8; 4], StrictPfx);
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Tx {
+///     #[serde(with = "tx_hash_hex")]
+///     hash: [u8; 4],
+/// }
+///
+/// # fn main() {
+/// let tx = Tx { hash: [0xde, 0xad, 0xbe, 0xef] };
+/// assert_eq!(serde_json::to_string(&tx).unwrap(), r#"{"hash":"0xdeadbeef"}"#);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! serhex_mod {
+    ($vis: vis $name: ident, $type: ty, $conf: ty) => {
+        /// hexadecimal (de)serialization via `SerHex`.
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// serialize `value` as a hexadecimal string.
+            pub fn serialize<S>(value: &$type, serializer: S) -> $crate::export::Result<S::Ok, S::Error>
+            where
+                S: $crate::serde::Serializer,
+            {
+                <$type as $crate::SerHex<$conf>>::serialize(value, serializer)
+            }
+
+            /// deserialize a value from a hexadecimal string.
+            pub fn deserialize<'de, D>(deserializer: D) -> $crate::export::Result<$type, D::Error>
+            where
+                D: $crate::serde::Deserializer<'de>,
+            {
+                <$type as $crate::SerHex<$conf>>::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+/// macro for implementing `SerHex` for a variable-length type which
+/// implements `From<Vec<u8>>` and `AsRef<[u8]>` (e.g. a newtype around
+/// `Vec<u8>` or `Box<[u8]>`, see `impl_newtype_bytevec`).  Strict configs
+/// accept any even number of digits, and compact configs any number of
+/// digits.  Requires the `alloc` feature.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactPfx, SerHex, StrictPfx};
+/// #[derive(Debug, PartialEq)]
+/// struct Blob(Box<[u8]>);
+/// impl_newtype_bytevec!(Blob, Box<[u8]>);
+/// impl_serhex_bytevec!(Blob);
+///
+/// # fn main() {
+/// let blob = <Blob as SerHex<StrictPfx>>::from_hex("0x00abcd").unwrap();
+/// assert_eq!(&blob[..], &[0x00, 0xab, 0xcd]);
+/// assert_eq!(<Blob as SerHex<CompactPfx>>::into_hex(&blob).unwrap(), "0xabcd");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_serhex_bytevec {
+    ($type: ty) => {
+        impl<C> $crate::SerHex<C> for $type
+        where
+            C: $crate::HexConf,
+        {
+            type Error = $crate::types::Error;
+            fn into_hex_raw<D>(&self, dst: D) -> $crate::export::Result<(), Self::Error>
+            where
+                D: $crate::io::Write,
+            {
+                $crate::utils::writehex_with::<C, D>(self.as_ref(), dst)
+            }
+            fn encoded_len(&self) -> usize {
+                let src: &[u8] = self.as_ref();
+                encoded_len_bytearray!(src.len())
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+            fn from_hex_raw<S>(src: S) -> $crate::export::Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                let buf = $crate::utils::fromhex_vec_with::<C>(src.as_ref())?;
+                Ok(buf.into())
+            }
+        }
+    };
+}
+
+/// macro for implementing `SerHex` for a struct whose fields are all
+/// fixed-size hex types (integers, byte-arrays, or types implemented via
+/// `impl_serhex_bytearray`).  The struct is encoded as the concatenation of
+/// the strict encoding of each field, in order, with a single leading prefix
+/// if the config specifies one.  Each field is always encoded at its full
+/// width (the compact flag is ignored), so that the input can be split at the
+/// known width of each field when decoding.  Fields are decoded with the
+/// ordinary `Strict` parser, so constant-time configs are not honoured.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # use serde_hex::{SerHex, StrictPfx};
+/// #[derive(Debug, PartialEq)]
+/// struct Header {
+///     version: u8,
+///     length: u16,
+///     nonce: [u8; 4],
+/// }
+/// impl_serhex_struct!(Header {
+///     version: u8,
+///     length: u16,
+///     nonce: [u8; 4],
+/// });
+///
+/// # fn main() {
+/// let header = Header { version: 1, length: 0x200, nonce: [0xab; 4] };
+/// let hex = <Header as SerHex<StrictPfx>>::into_hex(&header).unwrap();
+/// assert_eq!(hex, "0x010200abababab");
+/// assert_eq!(<Header as SerHex<StrictPfx>>::from_hex(&hex).unwrap(), header);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_serhex_struct {
+    ($type: ident { $($field: ident : $fty: ty),+ $(,)* }) => {
+        impl<C> $crate::SerHex<C> for $type
+        where
+            C: $crate::HexConf,
+        {
+            type Error = $crate::types::Error;
+            fn into_hex_raw<D>(&self, mut dst: D) -> $crate::export::Result<(), Self::Error>
+            where
+                D: $crate::io::Write,
+            {
+                if <C as $crate::HexConf>::withpfx() {
+                    dst.write_all($crate::utils::prefix::<C>())
+                        .map_err($crate::types::Error::from)?;
+                }
+                $(
+                    $crate::utils::intohex_field::<C, $fty, _>(&self.$field, &mut dst)?;
+                )+
+                Ok(())
+            }
+            fn encoded_len(&self) -> usize {
+                let pfx = if <C as $crate::HexConf>::withpfx() {
+                    2
+                } else {
+                    0
+                };
+                pfx $(+ <$fty as $crate::SerHexSeq<$crate::Strict>>::size() * 2)+
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+            fn from_hex_raw<S>(src: S) -> $crate::export::Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                let width = 0 $(+ <$fty as $crate::SerHexSeq<$crate::Strict>>::size() * 2)+;
+                let (mut rest, mut offset) =
+                    $crate::utils::fromhex_fields::<C>(src.as_ref(), width)?;
+                Ok($type {
+                    $(
+                        $field: $crate::utils::fromhex_field::<$fty>(&mut rest, &mut offset)?,
+                    )+
+                })
+            }
+        }
+    };
+}
+
+/// macro for implementing `SerHex` for a type which already implements
+/// `fmt::LowerHex` and `fmt::UpperHex`, and which can be parsed from bare
+/// hex digits via an inherent `from_str_radix` (or, in the `from_str` form,
+/// via `FromStr`).  Useful for third-party big-integer types.
+///
+/// If a width in bytes is supplied, strict configs pad to (and expect)
+/// exactly that width, compact configs accept up to that width, and
+/// `SerHexSeq` is implemented as well.  Otherwise strict configs pad to (and
+/// expect) a whole number of bytes.  Values which the parser rejects after
+/// the digits have been validated (e.g. because they overflow) are reported
+/// as `ParseHexError::Length`.  Constant-time configs are not honoured.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_hex;
+/// # use serde_hex::{CompactPfx, SerHex, StrictPfx};
+/// # use std::fmt;
+/// #[derive(Debug, PartialEq)]
+/// struct Amount(u128);
+///
+/// impl fmt::LowerHex for Amount {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         fmt::LowerHex::fmt(&self.0, f)
+///     }
+/// }
+///
+/// impl fmt::UpperHex for Amount {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         fmt::UpperHex::fmt(&self.0, f)
+///     }
+/// }
+///
+/// impl Amount {
+///     fn from_str_radix(src: &str, radix: u32) -> Result<Self, std::num::ParseIntError> {
+///         u128::from_str_radix(src, radix).map(Amount)
+///     }
+/// }
+///
+/// impl_serhex_via_fmt!(Amount, 16);
+///
+/// # fn main() {
+/// let amount = Amount(0xabc);
+/// assert_eq!(<Amount as SerHex<CompactPfx>>::into_hex(&amount).unwrap(), "0xabc");
+/// assert_eq!(
+///     <Amount as SerHex<StrictPfx>>::into_hex(&amount).unwrap(),
+///     "0x00000000000000000000000000000abc"
+/// );
+/// assert_eq!(<Amount as SerHex<CompactPfx>>::from_hex("0xabc").unwrap(), amount);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_serhex_via_fmt {
+    (@impl $type: ty, $width: expr, $parse: expr) => {
+        impl<C> $crate::SerHex<C> for $type
+        where
+            C: $crate::HexConf,
+        {
+            type Error = $crate::types::Error;
+            fn into_hex_raw<D>(&self, dst: D) -> $crate::export::Result<(), Self::Error>
+            where
+                D: $crate::io::Write,
+            {
+                $crate::utils::writehex_fmt::<C, Self, D>(self, $width, dst)
+            }
+            fn encoded_len(&self) -> usize {
+                let width: Option<usize> = $width;
+                match width {
+                    Some(bytes) => encoded_len_bytearray!(bytes),
+                    None => 32,
+                }
+            }
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+            fn from_hex_raw<S>(src: S) -> $crate::export::Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                let digits = $crate::utils::fromhex_fmt::<C>(src.as_ref(), $width)?;
+                match ($parse)(digits) {
+                    Ok(value) => Ok(value),
+                    Err(_) => Err($crate::types::ParseHexError::Length { len: digits.len() }.into()),
+                }
+            }
+        }
+    };
+    ($type: ty, from_str) => {
+        impl_serhex_via_fmt!(@impl $type, None, |src: &str| {
+            <$type as $crate::export::str::FromStr>::from_str(src)
+        });
+    };
+    ($type: ty, $bytes: expr, from_str) => {
+        impl_serhex_seq!($type, $bytes);
+        impl_serhex_via_fmt!(@impl $type, Some($bytes), |src: &str| {
+            <$type as $crate::export::str::FromStr>::from_str(src)
+        });
+    };
+    ($type: ty) => {
+        impl_serhex_via_fmt!(@impl $type, None, |src: &str| <$type>::from_str_radix(src, 16));
+    };
+    ($type: ty, $bytes: expr) => {
+        impl_serhex_seq!($type, $bytes);
+        impl_serhex_via_fmt!(@impl $type, Some($bytes), |src: &str| {
+            <$type>::from_str_radix(src, 16)
+        });
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use {
-        Compact, CompactCap, CompactCapPfx, CompactPfx, SerHex, Strict, StrictCap, StrictCapPfx,
-        StrictPfx,
+        Compact, CompactCap, CompactCapPfx, CompactPfx, HexConf, SerHex, Strict, StrictCap,
+        StrictCapPfx, StrictPfx,
     };
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
     struct Foo([u8; 4]);
     impl_newtype_bytearray!(Foo, 4);
     impl_serhex_bytearray!(Foo, 4);
 
+    // compact config which treats a bare `0x` as zero.
+    struct CompactPfxZero;
+    impl HexConf for CompactPfxZero {
+        fn compact() -> bool {
+            true
+        }
+        fn withpfx() -> bool {
+            true
+        }
+        fn emptyzero() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        use types::{Error, ParseHexError};
+        fn is_empty<T>(rslt: Result<T, Error>) -> bool {
+            matches!(rslt, Err(Error::Parsing(ParseHexError::EmptyInput)))
+        }
+        assert!(is_empty(<Foo as SerHex<Strict>>::from_hex("")));
+        assert!(is_empty(<Foo as SerHex<Compact>>::from_hex("")));
+        assert!(is_empty(<u64 as SerHex<StrictPfx>>::from_hex("0x")));
+        assert!(is_empty(<[u8; 4] as SerHex<Strict>>::from_hex("")));
+        assert!(is_empty(<[u16; 2] as SerHex<StrictPfx>>::from_hex("0x")));
+    }
+
+    // strict config which left-pads odd-length input.
+    struct StrictPfxPad;
+    impl HexConf for StrictPfxPad {
+        fn withpfx() -> bool {
+            true
+        }
+        fn padodd() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn odd_padding() {
+        let foo = <Foo as SerHex<StrictPfxPad>>::from_hex("0xabcdef0").unwrap();
+        assert_eq!(foo, Foo([0x0a, 0xbc, 0xde, 0xf0]));
+        assert_eq!(
+            <u16 as SerHex<StrictPfxPad>>::from_hex("abc").unwrap(),
+            0xabc
+        );
+        assert_eq!(
+            <u16 as SerHex<StrictPfxPad>>::from_hex("0abc").unwrap(),
+            0xabc
+        );
+        // only a single nibble is ever padded.
+        assert!(<Foo as SerHex<StrictPfxPad>>::from_hex("0xabcdef").is_err());
+        assert!(<Foo as SerHex<StrictPfx>>::from_hex("0xabcdef0").is_err());
+        // serialization is unaffected.
+        let hex = <u16 as SerHex<StrictPfxPad>>::into_hex(&0xabc).unwrap();
+        assert_eq!(hex, "0x0abc");
+    }
+
+    #[test]
+    fn fixed_width() {
+        use config::Width;
+        use types::{Error, ParseHexError};
+        // widths beyond the type are zero-padded, and must parse back.
+        let hex = <u8 as SerHex<Width<4, StrictPfx>>>::into_hex(&0xff).unwrap();
+        assert_eq!(hex, "0x00ff");
+        assert_eq!(
+            <u8 as SerHex<Width<4, StrictPfx>>>::from_hex(&hex).unwrap(),
+            0xff
+        );
+        assert_eq!(
+            <u8 as SerHex<Width<4, Compact>>>::from_hex("0ff").unwrap(),
+            0xff
+        );
+        match <u8 as SerHex<Width<4, StrictPfx>>>::from_hex("0x10ff") {
+            Err(Error::Parsing(ParseHexError::Overflow { bits: 8, .. })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let foo = Foo([0x00, 0x00, 0xab, 0xcd]);
+        let hex = <Foo as SerHex<Width<5, CompactCap>>>::into_hex(&foo).unwrap();
+        assert_eq!(hex, "0ABCD");
+        assert_eq!(
+            hex.len(),
+            <Foo as SerHex<Width<5, CompactCap>>>::encoded_len(&foo)
+        );
+        assert!(<Foo as SerHex<Width<5, Strict>>>::from_hex("abcd").is_err());
+        match <u16 as SerHex<Width<3, Strict>>>::into_hex(&0x1234) {
+            Err(Error::Parsing(ParseHexError::Overflow { bits: 12, .. })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn const_literals() {
+        const STRICT: [u8; 4] = serhex!(StrictPfx, "0xDEADbeef");
+        const UNPREFIXED: [u8; 2] = serhex!(StrictPfx, "abcd");
+        const COMPACT: [u8; 3] = serhex!(CompactPfx, "0xabcde", 3);
+        const INFERRED: [u8; 3] = serhex!(Compact, "abcde");
+        assert_eq!(
+            STRICT,
+            <[u8; 4] as SerHex<StrictPfx>>::from_hex("0xdeadbeef").unwrap()
+        );
+        assert_eq!(UNPREFIXED, [0xab, 0xcd]);
+        assert_eq!(
+            COMPACT,
+            <[u8; 3] as SerHex<Strict>>::from_hex("0abcde").unwrap()
+        );
+        assert_eq!(INFERRED, COMPACT);
+    }
+
+    #[test]
+    fn bare_prefix() {
+        use types::{Error, ParseHexError};
+        match <u32 as SerHex<CompactPfx>>::from_hex("0x") {
+            Err(Error::Parsing(ParseHexError::EmptyInput)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let foo = <Foo as SerHex<CompactPfxZero>>::from_hex("0x").unwrap();
+        assert_eq!(foo, Foo([0; 4]));
+        assert_eq!(<u32 as SerHex<CompactPfxZero>>::from_hex("").unwrap(), 0);
+        assert_eq!(
+            <u32 as SerHex<CompactPfxZero>>::from_hex("0xf").unwrap(),
+            15
+        );
+        // serialization of zero is unaffected.
+        let hex = <u32 as SerHex<CompactPfxZero>>::into_hex(&0).unwrap();
+        assert_eq!(hex, "0x0");
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Header {
+        version: u16,
+        foo: Foo,
+        hash: [u8; 8],
+    }
+    impl_serhex_struct!(Header {
+        version: u16,
+        foo: Foo,
+        hash: [u8; 8],
+    });
+
+    #[test]
+    fn packed_struct() {
+        use types::{Error, ParseHexError};
+        let header = Header {
+            version: 0x0a,
+            foo: Foo([0xff, 0, 1, 2]),
+            hash: [0xcd; 8],
+        };
+        let hex = <Header as SerHex<StrictCapPfx>>::into_hex(&header).unwrap();
+        assert_eq!(hex, "0x000AFF000102CDCDCDCDCDCDCDCD");
+        assert_eq!(
+            hex.len(),
+            <Header as SerHex<StrictCapPfx>>::encoded_len(&header)
+        );
+        // fields are never trimmed, even for compact configs.
+        let compact = <Header as SerHex<Compact>>::into_hex(&header).unwrap();
+        assert_eq!(compact, hex[2..].to_lowercase());
+        assert_eq!(
+            <Header as SerHex<StrictPfx>>::from_hex(&hex).unwrap(),
+            header
+        );
+        match <Header as SerHex<Strict>>::from_hex(&compact[..10]) {
+            Err(Error::Parsing(ParseHexError::Size {
+                expect: 28,
+                actual: 10,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let bad = hex.replace("0102", "01g2");
+        match <Header as SerHex<StrictPfx>>::from_hex(&bad) {
+            Err(Error::Parsing(ParseHexError::Char {
+                val: 'g',
+                index: 12,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Bytes(Vec<u8>);
+    impl_newtype_bytevec!(Bytes, Vec<u8>);
+    impl_serhex_bytevec!(Bytes);
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Boxed(Box<[u8]>);
+    impl_newtype_bytevec!(Boxed, Box<[u8]>);
+    impl_serhex_bytevec!(Boxed);
+
+    #[test]
+    fn bytevec() {
+        use types::{Error, ParseHexError};
+        let bytes = Bytes(vec![0x00, 0x0f, 0xab]);
+        let hex = <Bytes as SerHex<StrictCapPfx>>::into_hex(&bytes).unwrap();
+        assert_eq!(hex, "0x000FAB");
+        assert_eq!(<Bytes as SerHex<StrictPfx>>::from_hex(&hex).unwrap(), bytes);
+        let hex = <Bytes as SerHex<Compact>>::into_hex(&bytes).unwrap();
+        assert_eq!(hex, "fab");
+        assert_eq!(
+            <Bytes as SerHex<Compact>>::from_hex(&hex).unwrap(),
+            Bytes(vec![0x0f, 0xab])
+        );
+        // large values are encoded in chunks.
+        let boxed = Boxed(vec![0xee; 300].into_boxed_slice());
+        let hex = <Boxed as SerHex<Strict>>::into_hex(&boxed).unwrap();
+        assert_eq!(hex.len(), 600);
+        assert_eq!(<Boxed as SerHex<Strict>>::from_hex(&hex).unwrap(), boxed);
+        // strict configs accept any even length, including zero.
+        let empty = <Boxed as SerHex<StrictPfx>>::from_hex("0x").unwrap();
+        assert!(empty.is_empty());
+        match <Boxed as SerHex<StrictPfx>>::from_hex("0xabc") {
+            Err(Error::Parsing(ParseHexError::OddLength { len: 3 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match <Bytes as SerHex<CompactPfx>>::from_hex("0xabz") {
+            Err(Error::Parsing(ParseHexError::Char { val: 'z', index: 4 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            <Bytes as SerHex<Compact>>::into_hex(&Bytes(vec![])).unwrap(),
+            "0"
+        );
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct BlockNumber(u64);
+    impl_serhex_uint!(BlockNumber(u64));
+
+    #[test]
+    fn uint_newtype() {
+        use SerHexSeq;
+        let num = BlockNumber(0x1b4);
+        let hex = <BlockNumber as SerHex<StrictCapPfx>>::into_hex(&num).unwrap();
+        assert_eq!(hex, "0x00000000000001B4");
+        assert_eq!(
+            <BlockNumber as SerHex<StrictPfx>>::from_hex(&hex).unwrap(),
+            num
+        );
+        let hex = <BlockNumber as SerHex<Compact>>::into_hex(&num).unwrap();
+        assert_eq!(hex, "1b4");
+        assert_eq!(
+            <BlockNumber as SerHex<Compact>>::from_hex(&hex).unwrap(),
+            num
+        );
+        assert!(<BlockNumber as SerHex<Strict>>::from_hex("1b4").is_err());
+        assert_eq!(<BlockNumber as SerHexSeq<Strict>>::size(), 8);
+    }
+
+    // stand-in for a third-party big integer.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Wide(u128);
+
+    impl ::std::fmt::LowerHex for Wide {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            ::std::fmt::LowerHex::fmt(&self.0, f)
+        }
+    }
+
+    impl ::std::fmt::UpperHex for Wide {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            ::std::fmt::UpperHex::fmt(&self.0, f)
+        }
+    }
+
+    impl Wide {
+        fn from_str_radix(src: &str, radix: u32) -> Result<Self, ::std::num::ParseIntError> {
+            u128::from_str_radix(src, radix).map(Wide)
+        }
+    }
+
+    impl_serhex_via_fmt!(Wide, 16);
+
+    // parses bare hex digits via `FromStr`, with no fixed width.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Digits(u128);
+
+    impl ::std::fmt::LowerHex for Digits {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            ::std::fmt::LowerHex::fmt(&self.0, f)
+        }
+    }
+
+    impl ::std::fmt::UpperHex for Digits {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            ::std::fmt::UpperHex::fmt(&self.0, f)
+        }
+    }
+
+    impl ::std::str::FromStr for Digits {
+        type Err = ::std::num::ParseIntError;
+        fn from_str(src: &str) -> Result<Self, Self::Err> {
+            u128::from_str_radix(src, 16).map(Digits)
+        }
+    }
+
+    impl_serhex_via_fmt!(Digits, from_str);
+
+    #[test]
+    fn via_fmt() {
+        use types::{Error, ParseHexError};
+        use SerHexSeq;
+        let wide = Wide(0x1b4);
+        let hex = <Wide as SerHex<StrictCapPfx>>::into_hex(&wide).unwrap();
+        assert_eq!(hex, format!("0x{:0>32}", "1B4"));
+        assert_eq!(<Wide as SerHex<StrictPfx>>::from_hex(&hex).unwrap(), wide);
+        assert_eq!(<Wide as SerHex<Compact>>::into_hex(&wide).unwrap(), "1b4");
+        assert_eq!(<Wide as SerHex<Compact>>::from_hex("1b4").unwrap(), wide);
+        assert_eq!(<Wide as SerHex<Compact>>::into_hex(&Wide(0)).unwrap(), "0");
+        assert_eq!(<Wide as SerHexSeq<Strict>>::size(), 16);
+        match <Wide as SerHex<Strict>>::from_hex("1b4") {
+            Err(Error::Parsing(ParseHexError::Size {
+                expect: 32,
+                actual: 3,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match <Wide as SerHex<CompactPfx>>::from_hex("0x1z4") {
+            Err(Error::Parsing(ParseHexError::Char { val: 'z', index: 3 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let digits = Digits(0xabc);
+        let hex = <Digits as SerHex<StrictPfx>>::into_hex(&digits).unwrap();
+        assert_eq!(hex, "0x0abc");
+        assert_eq!(
+            <Digits as SerHex<StrictPfx>>::from_hex(&hex).unwrap(),
+            digits
+        );
+        assert!(<Digits as SerHex<StrictPfx>>::from_hex("0xabc").is_err());
+        // digits are valid, but the value overflows the parser.
+        match <Digits as SerHex<Compact>>::from_hex("f".repeat(33)) {
+            Err(Error::Parsing(ParseHexError::Length { len: 33 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    define_hex_array!(
+        /// a 40 byte identifier.
+        struct Ident(40),
+        config = CompactCapPfx,
+    );
+
+    #[test]
+    fn defined_array() {
+        use std::collections::BTreeSet;
+        let mut ident = Ident::default();
+        ident[39] = 0xab;
+        assert_eq!(ident.to_string(), "0xAB");
+        assert_eq!(format!("{:?}", ident), "Ident(0xAB)");
+        assert_eq!("0xab".parse::<Ident>().unwrap(), ident);
+        let hex = <Ident as SerHex<StrictPfx>>::into_hex(&ident).unwrap();
+        assert_eq!(hex.len(), 82);
+        let set: BTreeSet<_> = vec![ident, Ident::default()].into_iter().collect();
+        assert_eq!(set.iter().next(), Some(&Ident::default()));
+    }
+
     #[test]
     fn hex_strict_ok() {
         let f1 = Foo([0, 1, 2, 3]);
@@ -211,6 +1149,235 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encoded_len_hint() {
+        let f = Foo([0x00, 0x0f, 0xff, 0x11]);
+        let hs = <Foo as SerHex<StrictPfx>>::into_hex(&f).unwrap();
+        assert_eq!(hs.len(), <Foo as SerHex<StrictPfx>>::encoded_len(&f));
+        assert_eq!(hs.len(), hs.capacity());
+        let hs = <Foo as SerHex<Compact>>::into_hex(&f).unwrap();
+        assert!(hs.len() <= <Foo as SerHex<Compact>>::encoded_len(&f));
+        let v = [0xabu8; 64];
+        let hs = <[u8; 64] as SerHex<StrictCapPfx>>::into_hex(&v).unwrap();
+        assert_eq!(hs.len(), 130);
+        assert_eq!(hs.capacity(), 130);
+    }
+
+    #[test]
+    fn size_errors() {
+        let err = <Foo as SerHex<Strict>>::from_hex("0011223").unwrap_err();
+        assert_eq!(err.to_string(), "expected `8` hex chars, got `7`");
+        let err = <Foo as SerHex<Compact>>::from_hex("001122334").unwrap_err();
+        assert_eq!(err.to_string(), "expected `1...8` hex chars, got `9`");
+        let err = <[u8; 2] as SerHex<Strict>>::from_hex("aabbc").unwrap_err();
+        assert_eq!(err.to_string(), "expected `4` hex chars, got `5`");
+        let err = <[u8; 4] as SerHex<Strict>>::from_hex("aa").unwrap_err();
+        assert_eq!(err.to_string(), "expected `8` hex chars, got `2`");
+    }
+
+    #[test]
+    fn wide_ints() {
+        let v: u128 = 0xabc;
+        let hs = <u128 as SerHex<StrictPfx>>::into_hex(&v).unwrap();
+        assert_eq!(hs, format!("0x{:032x}", v));
+        assert_eq!(<u128 as SerHex<Compact>>::into_hex(&v).unwrap(), "abc");
+        assert_eq!(<u128 as SerHex<Compact>>::from_hex("abc").unwrap(), v);
+        let max = <u128 as SerHex<CompactCap>>::into_hex(&u128::MAX).unwrap();
+        assert_eq!(
+            <u128 as SerHex<Compact>>::from_hex(&max).unwrap(),
+            u128::MAX
+        );
+        let hs = <i128 as SerHex<Compact>>::into_hex(&-1).unwrap();
+        assert_eq!(hs, "f".repeat(32));
+        assert_eq!(<i128 as SerHex<Strict>>::from_hex(&hs).unwrap(), -1);
+        assert_eq!(<i128 as SerHex<Compact>>::into_hex(&0x10).unwrap(), "10");
+        assert_eq!(
+            <i128 as SerHex<Strict>>::from_hex(format!("8{}", "0".repeat(31))).unwrap(),
+            i128::MIN
+        );
+    }
+
+    #[test]
+    fn decode_in_place() {
+        let mut bytes = [0u8; 64];
+        let hex = "ab".repeat(64);
+        <[u8; 64] as SerHex<Strict>>::from_hex_into(&hex, &mut bytes).unwrap();
+        assert_eq!(bytes, [0xab; 64]);
+        let mut words = [[0u8; 2]; 2];
+        <[[u8; 2]; 2] as SerHex<StrictPfx>>::from_hex_into("0x0102beef", &mut words).unwrap();
+        assert_eq!(words, [[0x01, 0x02], [0xbe, 0xef]]);
+        let err = <[u8; 4] as SerHex<Strict>>::from_hex_into("aabbc", &mut [0; 4]).unwrap_err();
+        assert_eq!(err.to_string(), "expected `8` hex chars, got `5`");
+        let mut foo = Foo([0; 4]);
+        <Foo as SerHex<Compact>>::from_hex_into("abc", &mut foo).unwrap();
+        assert_eq!(foo, Foo([0, 0, 0x0a, 0xbc]));
+    }
+
+    #[test]
+    fn int_overflow() {
+        use types::{Error, ParseHexError};
+        match <u16 as SerHex<CompactPfx>>::from_hex("0x1ffff") {
+            Err(Error::Parsing(ParseHexError::Overflow {
+                bits: 16,
+                value: Some(0x1ffff),
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let err = <u8 as SerHex<Compact>>::from_hex("abc").unwrap_err();
+        assert_eq!(err.to_string(), "value `0xabc` does not fit in `8` bits");
+        // extra leading zeroes are a size error, not an overflow.
+        match <u8 as SerHex<Compact>>::from_hex("00ff") {
+            Err(Error::Parsing(ParseHexError::Range { max: 2, got: 4, .. })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match <u128 as SerHex<Compact>>::from_hex("1".repeat(33)) {
+            Err(Error::Parsing(ParseHexError::Overflow {
+                bits: 128,
+                value: None,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn overflow_modes() {
+        use config::{Saturating, Wrapping};
+        use types::{Error, ParseHexError};
+        use HexConf;
+        let val = <u32 as SerHex<Wrapping<CompactPfx>>>::from_hex("0x123456789").unwrap();
+        assert_eq!(val, 0x23456789);
+        let val = <u8 as SerHex<Saturating<Compact>>>::from_hex("100").unwrap();
+        assert_eq!(val, 0xff);
+        let val = <usize as SerHex<Saturating<Compact>>>::from_hex("ff").unwrap();
+        assert_eq!(val, 0xff);
+        let max = format!("1{}", "0".repeat(32));
+        let val = <i128 as SerHex<Saturating<Compact>>>::from_hex(&max).unwrap();
+        assert_eq!(val, i128::MAX);
+        let min = format!("f{}", "0".repeat(32));
+        let val = <i128 as SerHex<Saturating<Compact>>>::from_hex(&min).unwrap();
+        assert_eq!(val, i128::MIN);
+        let val = <isize as SerHex<Saturating<Compact>>>::from_hex("1ffffffffffffffff").unwrap();
+        assert_eq!(val, isize::MAX);
+        let val = <isize as SerHex<Saturating<Compact>>>::from_hex("80000000000000000").unwrap();
+        assert_eq!(val, isize::MIN);
+        let val = <isize as SerHex<Saturating<Compact>>>::from_hex("ffffffffffffffff").unwrap();
+        assert_eq!(val, -1);
+        // sign-extended input is in range, so is kept rather than clamped.
+        let ext = format!("0x{}", "f".repeat(34));
+        let val = <i128 as SerHex<Saturating<CompactPfx>>>::from_hex(&ext).unwrap();
+        assert_eq!(val, -1);
+        let ext = format!("0xff{}", "8".repeat(32));
+        let val = <i128 as SerHex<Saturating<CompactPfx>>>::from_hex(&ext).unwrap();
+        assert_eq!(
+            val,
+            <i128 as SerHex<Wrapping<CompactPfx>>>::from_hex(&ext).unwrap()
+        );
+        let ext = format!("0xff7{}", "0".repeat(31));
+        let val = <i128 as SerHex<Saturating<CompactPfx>>>::from_hex(&ext).unwrap();
+        assert_eq!(val, i128::MIN);
+        let val =
+            <isize as SerHex<Saturating<CompactPfx>>>::from_hex("0xffffffffffffffffff").unwrap();
+        assert_eq!(val, -1);
+        let val =
+            <isize as SerHex<Saturating<CompactPfx>>>::from_hex("0xff8000000000000000").unwrap();
+        assert_eq!(val, isize::MIN);
+        // only overflow is forgiven; malformed input still fails.
+        match <u8 as SerHex<Wrapping<Compact>>>::from_hex("1g0") {
+            Err(Error::Parsing(ParseHexError::Range { got: 3, .. })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(<u8 as SerHex<Saturating<Strict>>>::from_hex("100").is_err());
+        let desc = Wrapping::<CompactPfx>::describe();
+        assert_eq!(desc.to_string(), "CompactPfx (wrap)");
+    }
+
+    #[test]
+    fn compact_zero() {
+        use config::CompactZero;
+        struct ZeroFull;
+        impl HexConf for ZeroFull {
+            fn compact() -> bool {
+                true
+            }
+            fn compactzero() -> CompactZero {
+                CompactZero::Full
+            }
+        }
+        struct ZeroByte;
+        impl HexConf for ZeroByte {
+            fn compact() -> bool {
+                true
+            }
+            fn withpfx() -> bool {
+                true
+            }
+            fn compactzero() -> CompactZero {
+                CompactZero::Byte
+            }
+        }
+        assert_eq!(<u32 as SerHex<ZeroByte>>::into_hex(&0).unwrap(), "0x00");
+        assert_eq!(<u32 as SerHex<ZeroByte>>::into_hex(&0x1).unwrap(), "0x1");
+        assert_eq!(<u32 as SerHex<ZeroFull>>::into_hex(&0).unwrap(), "00000000");
+        let hex = <Foo as SerHex<ZeroFull>>::into_hex(&Foo([0; 4])).unwrap();
+        assert_eq!(hex, "00000000");
+        assert_eq!(
+            <Foo as SerHex<ZeroFull>>::from_hex(&hex).unwrap(),
+            Foo([0; 4])
+        );
+        let zero = ::HexUint::zero();
+        assert_eq!(
+            <::HexUint as SerHex<ZeroByte>>::into_hex(&zero).unwrap(),
+            "0x00"
+        );
+        assert_eq!(ZeroFull::describe().to_string(), "Compact (zero full)");
+    }
+
+    #[test]
+    fn trim_end() {
+        use config::TrimEnd;
+        use types::{Error, ParseHexError};
+        let foo = Foo([0xab, 0x0c, 0x00, 0x00]);
+        let hex = <Foo as SerHex<TrimEnd<CompactCap>>>::into_hex(&foo).unwrap();
+        assert_eq!(hex, "AB0C");
+        assert_eq!(<Foo as SerHex<TrimEnd>>::from_hex("ab0c").unwrap(), foo);
+        let zero = <Foo as SerHex<TrimEnd<CompactPfx>>>::into_hex(&Foo([0; 4])).unwrap();
+        assert_eq!(zero, "0x00");
+        let mut out = Vec::new();
+        ::utils::writehex_with::<TrimEnd, _>(&[0x01, 0x00, 0x02, 0x00], &mut out).unwrap();
+        assert_eq!(out, b"010002");
+        match <Foo as SerHex<TrimEnd>>::from_hex("ab0") {
+            Err(Error::Parsing(ParseHexError::OddLength { len: 3 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // integers are big-endian, so keep trimming leading zeroes.
+        let hex = <u32 as SerHex<TrimEnd>>::into_hex(&0x12340000).unwrap();
+        assert_eq!(hex, "12340000");
+        assert_eq!(<u32 as SerHex<TrimEnd>>::from_hex("1234").unwrap(), 0x1234);
+        let hex = <usize as SerHex<TrimEnd>>::into_hex(&0x100).unwrap();
+        assert_eq!(hex, "100");
+        assert_eq!(<usize as SerHex<TrimEnd>>::from_hex("100").unwrap(), 0x100);
+    }
+
+    #[test]
+    fn portable_sizes() {
+        use types::ParseHexError;
+        let hs = <usize as SerHex<Strict>>::into_hex(&0x2a).unwrap();
+        assert_eq!(hs, "000000000000002a");
+        assert_eq!(<usize as SerHex<Compact>>::from_hex("2a").unwrap(), 0x2a);
+        let hs = <isize as SerHex<Compact>>::into_hex(&-2).unwrap();
+        assert_eq!(hs, "fffffffffffffffe");
+        assert_eq!(<isize as SerHex<Strict>>::from_hex(&hs).unwrap(), -2);
+        let big = <usize as SerHex<Compact>>::from_hex("100000000");
+        if cfg!(target_pointer_width = "64") {
+            assert_eq!(big.unwrap() as u64, 1 << 32);
+        } else {
+            match big.unwrap_err().parse_error() {
+                Some(ParseHexError::Overflow { bits: 32, .. }) => {}
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn blanket_array() {
         let v: [Foo; 2] = <[Foo; 2] as SerHex<StrictPfx>>::from_hex("0xffaaffaa11221122").unwrap();
@@ -1,44 +1,5 @@
 //! macros which are only useful inside of this crate.
 
-/// Implement `SerHex` for a unsigned integer with a size equivalent
-/// to `$bytes`.  Currently just offloads conversion to the appropriately
-/// sized byte-array logic, and then does a endianness-aware transmute to
-/// the target type.  TODO: benchmark this and determine if it is worth
-/// writing a custom impl instead.
-macro_rules! impl_serhex_uint {
-    ($type: ty, $bytes: expr) => {
-        impl_serhex_seq!($type, $bytes);
-        impl<C> $crate::SerHex<C> for $type
-        where
-            C: $crate::HexConf,
-        {
-            type Error = $crate::types::Error;
-            fn into_hex_raw<D>(&self, mut dst: D) -> ::std::result::Result<(), Self::Error>
-            where
-                D: ::std::io::Write,
-            {
-                let bytes: [u8; $bytes] = unsafe { ::std::mem::transmute(self.to_be()) };
-                into_hex_bytearray!(bytes, dst, $bytes)?;
-                Ok(())
-            }
-            fn from_hex_raw<S>(src: S) -> ::std::result::Result<Self, Self::Error>
-            where
-                S: AsRef<[u8]>,
-            {
-                let rslt: ::std::result::Result<[u8; $bytes], Self::Error> =
-                    from_hex_bytearray!(src, $bytes);
-                match rslt {
-                    Ok(buf) => {
-                        let val: $type = unsafe { ::std::mem::transmute(buf) };
-                        Ok(Self::from_be(val))
-                    }
-                    Err(e) => Err(e),
-                }
-            }
-        }
-    };
-}
-
 /// implement `SerHexSeq` for an array of elements which implement
 /// `SerHexSeq`.
 macro_rules! impl_serhex_seq_array {
@@ -64,9 +25,8 @@ macro_rules! impl_serhex_seq_array {
 /// `SerHex<Strict> + SerHex<StrictCap>`.  this macro is invoked
 /// by the `impl_serhex_strict_array` macro for all `Strict`
 /// variants, so prefer that macro over calling this one directly.
-/// *TODO*: the `from_hex_raw` impl generated by this macro takes
-/// twice as long for the worst `Err` case as it does for the `Ok`
-/// case.  This needs to be updated.
+/// Encoding is offloaded to length-agnostic helpers in the crate
+/// root so that each expansion stays small.
 macro_rules! impl_serhex_strictconf_array {
     ($conf:ty,$len:expr) => {
         impl_serhex_seq_array!($conf, $len);
@@ -81,88 +41,74 @@ macro_rules! impl_serhex_strictconf_array {
         {
             type Error = E;
 
-            fn into_hex_raw<D>(&self, mut dst: D) -> Result<(), Self::Error>
+            fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
             where
                 D: io::Write,
             {
-                let src: &[T] = self.as_ref();
-                debug_assert!(src.len() == $len);
-                let mut items = src.iter();
-                // first element is serialized with `$conf` to allow prefixing if specified.
-                // plus this has the handy side-effect of preventing impls of `Compact` variants,
-                // since they are not part of the constraints on `T`.
-                match items.next() {
-                    Some(itm) => <T as $crate::SerHex<$conf>>::into_hex_raw(itm, &mut dst)?,
-                    None => {
-                        // should only happen in the `[T;0]` case.
-                        debug_assert!($len == 0);
-                        return Ok(());
-                    }
-                }
-                if <$conf as $crate::HexConf>::withcap() {
-                    for itm in items {
-                        <T as SerHex<$crate::StrictCap>>::into_hex_raw(&itm, &mut dst)?;
-                    }
-                } else {
-                    for itm in items {
-                        <T as SerHex<$crate::Strict>>::into_hex_raw(&itm, &mut dst)?;
-                    }
-                }
-                Ok(())
+                debug_assert!(self.len() == $len);
+                $crate::into_hex_array::<$conf, T, D, E>(self.as_ref(), dst)
+            }
+
+            fn encoded_len(&self) -> usize {
+                $crate::encoded_len_array::<$conf, T, E>(self.as_ref())
+            }
+
+            fn de_error<F>(err: Self::Error, src: &[u8]) -> F
+            where
+                F: $crate::serde::de::Error,
+            {
+                <T as $crate::SerHex<$crate::Strict>>::de_error(err, src)
             }
 
             fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
             where
                 S: AsRef<[u8]>,
             {
-                let raw: &[u8] = src.as_ref();
-                let hex = if <$conf as $crate::HexConf>::withpfx() {
-                    let pfx = "0x".as_bytes();
-                    if raw.starts_with(pfx) {
-                        &raw[2..]
-                    } else {
-                        raw
-                    }
-                } else {
-                    raw
-                };
+                let hex =
+                    $crate::utils::strippfx(src.as_ref(), <$conf as $crate::HexConf>::acceptpfx());
+                let width = $crate::array_width::<E>(hex, $len)?;
                 // get iterator over chunks of expected size.  the underlying
                 // `SerHex<Strict>` implementation must raise an appropriate
-                // error if chunks are not of the proper size.
-                let chunks = hex.chunks(hex.len() / $len);
-                let values =
-                    chunks.filter_map(
-                        |chunk| match <T as $crate::SerHex<$crate::Strict>>::from_hex(chunk) {
-                            Ok(val) => Some(val),
-                            Err(_) => None,
-                        },
-                    );
+                // error if chunks are not of the proper size.  the first error
+                // encountered (if any) is stashed, since `array_init::from_iter`
+                // only reports that the iterator ended early.
+                let mut error = None;
+                let values = hex.chunks(width).scan((), |_, chunk| {
+                    match <T as $crate::SerHex<$crate::Strict>>::from_hex(chunk) {
+                        Ok(val) => Some(val),
+                        Err(err) => {
+                            error = Some(err);
+                            None
+                        }
+                    }
+                });
                 match array_init::from_iter(values) {
                     Some(rslt) => Ok(rslt),
-                    None => {
-                        // TODO: currently just repeats above process until
-                        // error is found.  this is a workaround for the fact
-                        // that `aray_init::from_iter` consumes the iterator.
-                        // need to find a better workaround.
-                        let chunks = hex.chunks(hex.len() / $len);
-                        let mut errors = chunks.filter_map(|chunk| {
-                            match <T as $crate::SerHex<$crate::Strict>>::from_hex(chunk) {
-                                Ok(_) => None,
-                                Err(e) => Some(e),
-                            }
-                        });
-                        if let Some(err) = errors.next() {
-                            Err(err.into())
-                        } else {
-                            let expect = $len;
-                            let actual = hex.len() / $len;
+                    None => match error {
+                        Some(err) => Err(err),
+                        None => {
+                            let expect = width * $len;
+                            let actual = hex.len();
                             let inner = $crate::types::ParseHexError::Size { expect, actual };
                             let error = $crate::types::Error::from(inner);
                             Err(error.into())
                         }
-                    }
+                    },
                 }
             }
+
+            fn from_hex_into<S>(src: S, dst: &mut Self) -> Result<(), Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                let hex =
+                    $crate::utils::strippfx(src.as_ref(), <$conf as $crate::HexConf>::acceptpfx());
+                let width = $crate::array_width::<E>(hex, $len)?;
+                for (itm, chunk) in dst.iter_mut().zip(hex.chunks(width)) {
+                    <T as $crate::SerHex<$crate::Strict>>::from_hex_into(chunk, itm)?;
+                }
+                Ok(())
+            }
         }
     };
 }
@@ -184,3 +130,72 @@ macro_rules! impl_serhex_strict_array {
         )+
     }
 }
+
+/// implement a constant-time config of `SerHex` for byte-arrays
+/// of length `$len`.  the blanket array impls decode element-wise
+/// via `SerHex<Strict>`, so byte-arrays get dedicated impls instead.
+/// invoked by `impl_serhex_ct_bytearray` for all constant-time configs.
+#[cfg(feature = "constant-time")]
+macro_rules! impl_serhex_ctconf_bytearray {
+    ($conf:ty, $len: expr) => {
+        impl $crate::SerHex<$conf> for [u8; $len] {
+            type Error = $crate::types::Error;
+
+            fn into_hex_raw<D>(&self, mut dst: D) -> Result<(), Self::Error>
+            where
+                D: io::Write,
+            {
+                let mut buf = [0u8; $len * 2 + 2];
+                let len = $crate::utils::intohex_with::<$conf>(&mut buf, self);
+                let rslt = dst.write_all(&buf[..len]);
+                $crate::utils::scrub(&mut buf);
+                rslt.map_err($crate::types::Error::from)
+            }
+
+            fn encoded_len(&self) -> usize {
+                let pfx = if <$conf as $crate::HexConf>::withpfx() {
+                    2
+                } else {
+                    0
+                };
+                pfx + $len * 2
+            }
+
+            fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+            where
+                E: $crate::serde::de::Error,
+            {
+                err.into_de_error(src)
+            }
+
+            fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                let mut buf = [0u8; $len];
+                $crate::utils::fromhex_with::<$conf>(&mut buf, src.as_ref())?;
+                Ok(buf)
+            }
+
+            fn from_hex_into<S>(src: S, dst: &mut Self) -> Result<(), Self::Error>
+            where
+                S: AsRef<[u8]>,
+            {
+                $crate::utils::fromhex_with::<$conf>(dst, src.as_ref())?;
+                Ok(())
+            }
+        }
+    };
+}
+
+/// implement all constant-time configs of `SerHex` for byte-arrays
+/// of the given lengths.
+#[cfg(feature = "constant-time")]
+macro_rules! impl_serhex_ct_bytearray {
+    ( $($len: expr),+ ) => {
+        $(
+            impl_serhex_ctconf_bytearray!($crate::StrictCt,$len);
+            impl_serhex_ctconf_bytearray!($crate::StrictCtPfx,$len);
+        )+
+    }
+}
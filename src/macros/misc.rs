@@ -30,7 +30,7 @@ macro_rules! impl_newtype {
             }
         }
 
-        impl ::std::ops::Deref for $outer {
+        impl $crate::export::ops::Deref for $outer {
             type Target = $inner;
 
             fn deref(&self) -> &Self::Target {
@@ -38,19 +38,19 @@ macro_rules! impl_newtype {
             }
         }
 
-        impl ::std::ops::DerefMut for $outer {
+        impl $crate::export::ops::DerefMut for $outer {
             fn deref_mut(&mut self) -> &mut Self::Target {
                 &mut self.0
             }
         }
 
-        impl ::std::borrow::Borrow<$inner> for $outer {
+        impl $crate::export::borrow::Borrow<$inner> for $outer {
             fn borrow(&self) -> &$inner {
                 &self.0
             }
         }
 
-        impl ::std::borrow::BorrowMut<$inner> for $outer {
+        impl $crate::export::borrow::BorrowMut<$inner> for $outer {
             fn borrow_mut(&mut self) -> &mut $inner {
                 &mut self.0
             }
@@ -66,7 +66,7 @@ macro_rules! impl_newtype {
 macro_rules! impl_newtype_old {
     ($outer: ident, $inner: ty) => {
         // dereference to inner value.
-        impl ::std::ops::Deref for $outer {
+        impl $crate::export::ops::Deref for $outer {
             type Target = $inner;
             fn deref(&self) -> &Self::Target {
                 &self.0
@@ -74,14 +74,14 @@ macro_rules! impl_newtype_old {
         }
 
         // dereference to inner value.
-        impl ::std::ops::DerefMut for $outer {
+        impl $crate::export::ops::DerefMut for $outer {
             fn deref_mut(&mut self) -> &mut Self::Target {
                 &mut self.0
             }
         }
 
         // convert from the inner value to the outer value.
-        impl ::std::convert::From<$inner> for $outer {
+        impl $crate::export::convert::From<$inner> for $outer {
             fn from(inner: $inner) -> Self {
                 $outer(inner)
             }
@@ -105,26 +105,86 @@ macro_rules! impl_newtype_old {
 
 /// implements useful traits for array newtypes
 /// (e.g.; `Foo([Bar;n])`).  Includes all implementations from
-/// the `impl_newtype` macro.
+/// the `impl_newtype` macro, as well as slice-style borrowing,
+/// indexing, iteration and fallible conversion from slices.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_newtype_array {
     ($outer: ident, $inner: ty, $len: expr) => {
-        impl_newtype!($outer, [u8; $len]);
-        /*
-                // get reference as byte-slice.
-                impl AsRef<[$inner]> for $outer {
-                    fn as_ref(&self) -> &[$inner] {
-                        self.0.as_ref()
-                    }
-                }
+        impl_newtype!($outer, [$inner; $len]);
 
-                impl AsMut<[$inner]> for $outer {
-                    fn as_mut(&mut self) -> &mut [$inner] {
-                        self.0.as_mut()
-                    }
-                }
-        */
+        // borrow as a slice (e.g. for lookups in maps keyed by slices).
+        impl $crate::export::borrow::Borrow<[$inner]> for $outer {
+            fn borrow(&self) -> &[$inner] {
+                &self.0
+            }
+        }
+
+        impl $crate::export::borrow::BorrowMut<[$inner]> for $outer {
+            fn borrow_mut(&mut self) -> &mut [$inner] {
+                &mut self.0
+            }
+        }
+
+        // index with anything that can index the inner slice.
+        impl<I> $crate::export::ops::Index<I> for $outer
+        where
+            [$inner]: $crate::export::ops::Index<I>,
+        {
+            type Output = <[$inner] as $crate::export::ops::Index<I>>::Output;
+
+            fn index(&self, index: I) -> &Self::Output {
+                let slice: &[$inner] = &self.0;
+                $crate::export::ops::Index::index(slice, index)
+            }
+        }
+
+        impl<I> $crate::export::ops::IndexMut<I> for $outer
+        where
+            [$inner]: $crate::export::ops::IndexMut<I>,
+        {
+            fn index_mut(&mut self, index: I) -> &mut Self::Output {
+                let slice: &mut [$inner] = &mut self.0;
+                $crate::export::ops::IndexMut::index_mut(slice, index)
+            }
+        }
+
+        impl IntoIterator for $outer {
+            type Item = $inner;
+            type IntoIter = $crate::export::array::IntoIter<$inner, $len>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                IntoIterator::into_iter(self.0)
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $outer {
+            type Item = &'a $inner;
+            type IntoIter = $crate::export::slice::Iter<'a, $inner>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a mut $outer {
+            type Item = &'a mut $inner;
+            type IntoIter = $crate::export::slice::IterMut<'a, $inner>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter_mut()
+            }
+        }
+
+        // convert from a slice of the correct length.
+        impl<'a> $crate::export::convert::TryFrom<&'a [$inner]> for $outer {
+            type Error = $crate::export::array::TryFromSliceError;
+
+            fn try_from(src: &'a [$inner]) -> $crate::export::Result<Self, Self::Error> {
+                let inner: [$inner; $len] = $crate::export::convert::TryFrom::try_from(src)?;
+                Ok($outer(inner))
+            }
+        }
     };
 }
 
@@ -136,8 +196,8 @@ macro_rules! impl_newtype_hexfmt {
     ($outer: ident, $lowtoken: expr, $uptoken: expr) => {
         // implement the `LowerHex` trait to allow generation
         // of lowercase hexadecimal representations.
-        impl ::std::fmt::LowerHex for $outer {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        impl $crate::export::fmt::LowerHex for $outer {
+            fn fmt(&self, f: &mut $crate::export::fmt::Formatter) -> $crate::export::fmt::Result {
                 let bytes: &[u8] = self.as_ref();
                 for val in bytes.iter() {
                     write!(f, $lowtoken, val)?;
@@ -148,8 +208,8 @@ macro_rules! impl_newtype_hexfmt {
 
         // implement the `UpperHex` trait to allow generation
         // of uppercase hexadecimal representations.
-        impl ::std::fmt::UpperHex for $outer {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        impl $crate::export::fmt::UpperHex for $outer {
+            fn fmt(&self, f: &mut $crate::export::fmt::Formatter) -> $crate::export::fmt::Result {
                 let bytes: &[u8] = self.as_ref();
                 for val in bytes.iter() {
                     write!(f, $uptoken, val)?;
@@ -160,15 +220,456 @@ macro_rules! impl_newtype_hexfmt {
     };
 }
 
+/// Apply the `defmt::Format` trait, emitting contiguous lowercase hex.
+/// Expands to nothing unless the `defmt` feature is enabled.
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_defmt {
+    ($outer: ident) => {
+        impl $crate::export::Format for $outer {
+            fn format(&self, fmt: $crate::export::Formatter) {
+                $crate::utils::defmt_hex(self.as_ref(), fmt)
+            }
+        }
+    };
+}
+
+/// Apply the `defmt::Format` trait, emitting contiguous lowercase hex.
+/// Expands to nothing unless the `defmt` feature is enabled.
+#[cfg(not(feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_defmt {
+    ($outer: ident) => {};
+}
+
+/// Apply the `Display`, `FromStr` and `TryFrom<&str>` traits, encoding
+/// and decoding hex with the config `$conf`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_hexstr {
+    ($outer: ident, $len: expr, $conf: ty) => {
+        impl $crate::export::fmt::Display for $outer {
+            fn fmt(&self, f: &mut $crate::export::fmt::Formatter) -> $crate::export::fmt::Result {
+                let src: &[u8] = self.as_ref();
+                let mut buf = [0u8; $len * 2 + 2];
+                let len = $crate::utils::intohex_with::<$conf>(&mut buf, src);
+                let rslt = match $crate::export::str::from_utf8(&buf[..len]) {
+                    Ok(hex) => f.pad(hex),
+                    Err(_) => Err($crate::export::fmt::Error),
+                };
+                $crate::utils::scrub(&mut buf);
+                rslt
+            }
+        }
+
+        impl $crate::export::str::FromStr for $outer {
+            type Err = $crate::ParseHexError;
+
+            fn from_str(src: &str) -> $crate::export::Result<Self, Self::Err> {
+                let mut buf = [0u8; $len];
+                $crate::utils::fromhex_with::<$conf>(&mut buf, src.as_bytes())?;
+                Ok($outer(buf))
+            }
+        }
+
+        impl<'a> $crate::export::convert::TryFrom<&'a str> for $outer {
+            type Error = $crate::ParseHexError;
+
+            fn try_from(src: &'a str) -> $crate::export::Result<Self, Self::Error> {
+                $crate::export::str::FromStr::from_str(src)
+            }
+        }
+    };
+}
+
+/// Apply the inherent `as_bytes`, `len`, `is_empty` and `iter` accessors,
+/// as well as (with `alloc`) the `to_vec` and `to_hex_string` methods.  Not
+/// applied by `impl_newtype_bytearray`, so invoke it alongside that macro
+/// where wanted.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_accessors {
+    ($outer: ident, $len: expr) => {
+        #[allow(dead_code)]
+        impl $outer {
+            /// borrow the bytes of this instance.
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.0
+            }
+
+            /// number of bytes in this instance.
+            pub const fn len(&self) -> usize {
+                $len
+            }
+
+            /// check if this instance holds no bytes.
+            pub const fn is_empty(&self) -> bool {
+                $len == 0
+            }
+
+            /// iterate over the bytes of this instance.
+            pub fn iter(&self) -> $crate::export::slice::Iter<'_, u8> {
+                self.0.iter()
+            }
+        }
+
+        impl_newtype_owned!(@accessors $outer);
+    };
+}
+
+/// Apply `From<$outer> for Vec<u8>`, copying the inner array into a new
+/// vector.  The `@accessors` form applies the `to_vec` and `to_hex_string`
+/// methods for `impl_newtype_accessors`.  Expands to nothing unless the
+/// `alloc` feature is enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_owned {
+    (@accessors $outer: ident) => {
+        #[allow(dead_code)]
+        impl $outer {
+            /// copy the bytes of this instance into a new vector.
+            #[allow(clippy::wrong_self_convention)]
+            pub fn to_vec(&self) -> $crate::export::Vec<u8> {
+                self.0.to_vec()
+            }
+
+            /// encode the bytes of this instance as hex under config `C`,
+            /// as `SerHex::into_hex` would for a byte-array.  fails only if
+            /// the value needs more digits than a fixed `C::width()`.
+            #[allow(clippy::wrong_self_convention)]
+            pub fn to_hex_string<C: $crate::HexConf>(
+                &self,
+            ) -> $crate::export::Result<$crate::export::String, $crate::Error> {
+                $crate::utils::intohex_string_with::<C>(&self.0)
+            }
+        }
+    };
+    ($outer: ident) => {
+        impl From<$outer> for $crate::export::Vec<u8> {
+            fn from(outer: $outer) -> Self {
+                outer.0.to_vec()
+            }
+        }
+    };
+}
+
+/// Apply `From<$outer> for Vec<u8>`, copying the inner array into a new
+/// vector.  The `@accessors` form applies the `to_vec` and `to_hex_string`
+/// methods for `impl_newtype_accessors`.  Expands to nothing unless the
+/// `alloc` feature is enabled.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_owned {
+    ($($any: tt)*) => {};
+}
+
+/// Apply the `Distribution<Standard>` trait and a `random` constructor,
+/// filling the inner array with random bytes.  Expands to nothing unless
+/// the `rand` feature is enabled.
+#[cfg(feature = "rand")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_rand {
+    ($outer: ident, $len: expr) => {
+        impl $crate::export::Distribution<$outer> for $crate::export::Standard {
+            fn sample<R>(&self, rng: &mut R) -> $outer
+            where
+                R: $crate::export::Rng + ?Sized,
+            {
+                let mut inner = [0u8; $len];
+                $crate::export::RngCore::fill_bytes(rng, &mut inner);
+                $outer(inner)
+            }
+        }
+
+        impl $outer {
+            /// generate an instance filled with random bytes.
+            #[allow(dead_code)]
+            pub fn random<R>(rng: &mut R) -> Self
+            where
+                R: $crate::export::Rng + ?Sized,
+            {
+                $crate::export::Distribution::sample(&$crate::export::Standard, rng)
+            }
+        }
+    };
+}
+
+/// Apply the `Distribution<Standard>` trait and a `random` constructor,
+/// filling the inner array with random bytes.  Expands to nothing unless
+/// the `rand` feature is enabled.
+#[cfg(not(feature = "rand"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_rand {
+    ($outer: ident, $len: expr) => {};
+}
+
+/// Apply `proptest::arbitrary::Arbitrary`, generating uniformly random
+/// bytes.  Not applied by `impl_newtype_bytearray`, so invoke it alongside
+/// that macro where wanted.  Expands to nothing unless the `proptest`
+/// feature is enabled, in which case the newtype must also implement `Debug`.
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_proptest {
+    ($outer: ident, $len: expr) => {
+        impl $crate::export::proptest::arbitrary::Arbitrary for $outer {
+            type Parameters = ();
+            type Strategy = $crate::export::proptest::strategy::Map<
+                $crate::export::proptest::array::UniformArrayStrategy<
+                    <u8 as $crate::export::proptest::arbitrary::Arbitrary>::Strategy,
+                    [u8; $len],
+                >,
+                fn([u8; $len]) -> $outer,
+            >;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                let bytes = $crate::export::proptest::array::uniform::<_, { $len }>(
+                    $crate::export::proptest::arbitrary::any::<u8>(),
+                );
+                $crate::export::proptest::strategy::Strategy::prop_map(
+                    bytes,
+                    $outer as fn([u8; $len]) -> $outer,
+                )
+            }
+        }
+    };
+}
+
+/// Apply `proptest::arbitrary::Arbitrary`, generating uniformly random
+/// bytes.  Not applied by `impl_newtype_bytearray`, so invoke it alongside
+/// that macro where wanted.  Expands to nothing unless the `proptest`
+/// feature is enabled, in which case the newtype must also implement `Debug`.
+#[cfg(not(feature = "proptest"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_proptest {
+    ($outer: ident, $len: expr) => {};
+}
+
+/// Apply `quickcheck::Arbitrary`, generating random bytes and shrinking
+/// towards zero.  Not applied by `impl_newtype_bytearray`, so invoke it
+/// alongside that macro where wanted.  Expands to nothing unless the
+/// `quickcheck` feature is enabled, in which case the newtype must also
+/// implement `Clone`.
+#[cfg(feature = "quickcheck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_quickcheck {
+    ($outer: ident, $len: expr) => {
+        impl $crate::export::quickcheck::Arbitrary for $outer {
+            fn arbitrary(g: &mut $crate::export::quickcheck::Gen) -> Self {
+                let mut inner = [0u8; $len];
+                for byte in inner.iter_mut() {
+                    *byte = $crate::export::quickcheck::Arbitrary::arbitrary(g);
+                }
+                $outer(inner)
+            }
+
+            fn shrink(&self) -> $crate::export::quickcheck::Box<dyn Iterator<Item = Self>> {
+                // zero one more byte (from the front) per step.
+                let inner = self.0;
+                let start = inner.iter().position(|b| *b != 0).unwrap_or($len);
+                $crate::export::quickcheck::Box::new((start..$len).map(move |end| {
+                    let mut shrunk = inner;
+                    for byte in shrunk[..=end].iter_mut() {
+                        *byte = 0;
+                    }
+                    $outer(shrunk)
+                }))
+            }
+        }
+    };
+}
+
+/// Apply `quickcheck::Arbitrary`, generating random bytes and shrinking
+/// towards zero.  Not applied by `impl_newtype_bytearray`, so invoke it
+/// alongside that macro where wanted.  Expands to nothing unless the
+/// `quickcheck` feature is enabled, in which case the newtype must also
+/// implement `Clone`.
+#[cfg(not(feature = "quickcheck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_quickcheck {
+    ($outer: ident, $len: expr) => {};
+}
+
+/// Apply `arbitrary::Arbitrary`, taking the bytes straight from the fuzzer
+/// input.  Expands to nothing unless the `arbitrary` feature is enabled.
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_arbitrary {
+    ($outer: ident, $len: expr) => {
+        impl<'a> $crate::export::arbitrary::Arbitrary<'a> for $outer {
+            fn arbitrary(
+                u: &mut $crate::export::arbitrary::Unstructured<'a>,
+            ) -> $crate::export::arbitrary::Result<Self> {
+                let inner: [u8; $len] = $crate::export::arbitrary::Arbitrary::arbitrary(u)?;
+                Ok($outer(inner))
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <[u8; $len] as $crate::export::arbitrary::Arbitrary>::size_hint(depth)
+            }
+        }
+    };
+}
+
+/// Apply `arbitrary::Arbitrary`, taking the bytes straight from the fuzzer
+/// input.  Expands to nothing unless the `arbitrary` feature is enabled.
+#[cfg(not(feature = "arbitrary"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_arbitrary {
+    ($outer: ident, $len: expr) => {};
+}
+
+/// Apply `subtle::ConstantTimeEq`, and `PartialEq`/`Eq` in terms of it, so
+/// that comparisons don't leak the position of the first differing byte.
+/// Fails to compile unless the `subtle` feature is enabled.
+#[cfg(feature = "subtle")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_ct_eq {
+    ($outer: ident) => {
+        impl $crate::export::ConstantTimeEq for $outer {
+            fn ct_eq(&self, other: &$outer) -> $crate::export::Choice {
+                let sref: &[u8] = self.as_ref();
+                let oref: &[u8] = other.as_ref();
+                $crate::export::ConstantTimeEq::ct_eq(sref, oref)
+            }
+        }
+
+        impl $crate::export::cmp::PartialEq for $outer {
+            fn eq(&self, other: &$outer) -> bool {
+                $crate::export::ConstantTimeEq::ct_eq(self, other).into()
+            }
+        }
+
+        impl $crate::export::cmp::Eq for $outer {}
+    };
+}
+
+/// Apply `subtle::ConstantTimeEq`, and `PartialEq`/`Eq` in terms of it, so
+/// that comparisons don't leak the position of the first differing byte.
+/// Fails to compile unless the `subtle` feature is enabled.
+#[cfg(not(feature = "subtle"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_ct_eq {
+    ($outer: ident) => {
+        compile_error!("constant-time equality requires the `subtle` feature of `serde-hex`");
+    };
+}
+
 /// Implement useful traits for byte-array newtypes
 /// (e.g.; `Foo([u8;n])`).  includes implementations
-/// from `impl_newtype_array`.
+/// from `impl_newtype_array` (among them the length-checked
+/// `TryFrom<&[u8]>`) and, with `alloc`, conversion to `Vec<u8>`
+/// via `impl_newtype_owned`.  If a config is supplied,
+/// also implements string conversions via `impl_newtype_hexstr`.  The
+/// `consttime` flag additionally implements `PartialEq`, `Eq` and
+/// `ConstantTimeEq` via `impl_newtype_ct_eq` (requires the `subtle` feature).
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_newtype_bytearray {
+    ($outer: ident, $len: expr, consttime) => {
+        impl_newtype_bytearray!($outer, $len);
+        impl_newtype_ct_eq!($outer);
+    };
+    ($outer: ident, $len: expr, $conf: ty) => {
+        impl_newtype_bytearray!($outer, $len);
+        impl_newtype_hexstr!($outer, $len, $conf);
+    };
     ($outer: ident, $len: expr) => {
         impl_newtype_array!($outer, u8, $len);
         impl_newtype_hexfmt!($outer, "{:02x}", "{:02X}");
+        impl_newtype_defmt!($outer);
+        impl_newtype_owned!($outer);
+        impl_newtype_rand!($outer, $len);
+        impl_newtype_arbitrary!($outer, $len);
+    };
+}
+
+/// implements useful traits for variable-length byte newtypes
+/// (e.g.; `Foo(Vec<u8>)` or `Foo(Box<[u8]>)`), including the
+/// `From<Vec<u8>>` conversion used by `impl_serhex_bytevec` (and its
+/// inverse).
+/// Requires the `alloc` feature.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_newtype_bytevec {
+    ($outer: ident, $inner: ty) => {
+        impl From<$crate::export::Vec<u8>> for $outer {
+            fn from(inner: $crate::export::Vec<u8>) -> Self {
+                $outer(inner.into())
+            }
+        }
+
+        impl AsRef<[u8]> for $outer {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl AsMut<[u8]> for $outer {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
+        impl $crate::export::ops::Deref for $outer {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl $crate::export::ops::DerefMut for $outer {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl $crate::export::borrow::Borrow<[u8]> for $outer {
+            fn borrow(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl $crate::export::borrow::BorrowMut<[u8]> for $outer {
+            fn borrow_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
+        impl Default for $outer {
+            fn default() -> Self {
+                $outer(Default::default())
+            }
+        }
+
+        impl $crate::export::iter::FromIterator<u8> for $outer {
+            fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+                $outer(iter.into_iter().collect())
+            }
+        }
+
+        impl From<$outer> for $crate::export::Vec<u8> {
+            fn from(outer: $outer) -> Self {
+                outer.0.into()
+            }
+        }
+
+        impl_newtype_hexfmt!($outer, "{:02x}", "{:02X}");
+        impl_newtype_defmt!($outer);
     };
 }
 
@@ -180,20 +681,47 @@ macro_rules! impl_newtype_bytearray {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_newtype_array_ext {
-    ($outer: ident, $inner: ty, $len:expr) => {
+    (@noeq $outer: ident, $inner: ty, $len:expr) => {
         // implement everything from the nomral bytearray macro.
         impl_newtype_array!($outer, $inner, $len);
         /*
                 // manually implemented `Debug` trait for printouts.
-                impl ::std::fmt::Debug for $outer {
-                    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                impl $crate::export::fmt::Debug for $outer {
+                    fn fmt(&self, f: &mut $crate::export::fmt::Formatter) -> $crate::export::fmt::Result {
                         let s: &[$inner] = self.as_ref();
                         write!(f, "{}({:?})",stringify!($ident),s)
                     }
                 }
         */
+        // manually implement `Hash` (consistent with `Borrow<[$inner]>`)
+        // so that instances can be used as map keys.
+        impl $crate::export::hash::Hash for $outer {
+            fn hash<H: $crate::export::hash::Hasher>(&self, state: &mut H) {
+                let sref: &[$inner] = self.as_ref();
+                sref.hash(state)
+            }
+        }
+
+        // manually implement `PartialOrd` and `Ord` for sorting.
+        impl $crate::export::cmp::PartialOrd for $outer {
+            fn partial_cmp(&self, other: &$outer) -> Option<$crate::export::cmp::Ordering> {
+                Some($crate::export::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl $crate::export::cmp::Ord for $outer {
+            fn cmp(&self, other: &$outer) -> $crate::export::cmp::Ordering {
+                let sref: &[$inner] = self.as_ref();
+                let oref: &[$inner] = other.as_ref();
+                sref.cmp(oref)
+            }
+        }
+    };
+    ($outer: ident, $inner: ty, $len:expr) => {
+        impl_newtype_array_ext!(@noeq $outer, $inner, $len);
+
         // manually implement `PartialEq` for comparison operations.
-        impl ::std::cmp::PartialEq for $outer {
+        impl $crate::export::cmp::PartialEq for $outer {
             fn eq(&self, other: &$outer) -> bool {
                 let sref: &[$inner] = self.as_ref();
                 let oref: &[$inner] = other.as_ref();
@@ -202,7 +730,7 @@ macro_rules! impl_newtype_array_ext {
         }
 
         // manually flag type as `Eq` for full equivalence relations.
-        impl ::std::cmp::Eq for $outer {}
+        impl $crate::export::cmp::Eq for $outer {}
     };
 }
 
@@ -237,23 +765,201 @@ macro_rules! impl_newtype_numarray_ext {
 /// implements useful traits for byte-array newtypes
 /// (e.g.; `Foo([u8;n])`) for arrays of greater than 32 elements.
 /// Includes all implementations the `impl_newtype_array_ext`
-/// and `impl_newtype_numarray_ext` macros.
+/// and `impl_newtype_numarray_ext` macros.  If a config is
+/// supplied, also implements string conversions via `impl_newtype_hexstr`.
+/// The `consttime` flag implements `PartialEq` via `impl_newtype_ct_eq`
+/// (requires the `subtle` feature), rather than by slice comparison.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_newtype_bytearray_ext {
+    ($outer: ident, $len: expr, consttime) => {
+        impl_newtype_array_ext!(@noeq $outer, u8, $len);
+        impl_newtype_ct_eq!($outer);
+        impl_newtype_bytearray_ext!(@common $outer, $len);
+    };
+    ($outer: ident, $len: expr, $conf: ty) => {
+        impl_newtype_bytearray_ext!($outer, $len);
+        impl_newtype_hexstr!($outer, $len, $conf);
+    };
     ($outer: ident, $len:expr) => {
         impl_newtype_array_ext!($outer, u8, $len);
+        impl_newtype_bytearray_ext!(@common $outer, $len);
+    };
+    (@common $outer: ident, $len:expr) => {
         impl_newtype_hexfmt!($outer, "{:02x}", "{:02X}");
+        impl_newtype_defmt!($outer);
+        impl_newtype_owned!($outer);
+        impl_newtype_rand!($outer, $len);
+        impl_newtype_arbitrary!($outer, $len);
         impl_newtype_numarray_ext!($outer, u8, $len);
     };
 }
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Borrow;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Foo([u8; 4]);
+    impl_newtype_bytearray!(Foo, 4);
+    impl_newtype_accessors!(Foo, 4);
+    impl_newtype_proptest!(Foo, 4);
+    impl_newtype_quickcheck!(Foo, 4);
+
+    #[test]
+    fn containers() {
+        let mut foo = Foo::try_from(&[1u8, 2, 3, 4][..]).unwrap();
+        assert!(Foo::try_from(&[1u8, 2, 3][..]).is_err());
+        foo[0] = 0;
+        assert_eq!(foo[0], 0);
+        assert_eq!(&foo[1..3], &[2, 3]);
+        for byte in &mut foo {
+            *byte += 1;
+        }
+        assert_eq!((&foo).into_iter().count(), 4);
+        let slice: &[u8] = foo.borrow();
+        assert_eq!(slice, &[1, 3, 4, 5]);
+        let mut map = HashMap::new();
+        map.insert(Foo([0xff; 4]), ());
+        assert!(map.contains_key(&Foo([0xff; 4])));
+        assert_eq!(foo.as_bytes(), &[1, 3, 4, 5]);
+        assert_eq!((foo.len(), foo.is_empty()), (4, false));
+        assert_eq!(foo.iter().max(), Some(&5));
+        let hex = foo.to_hex_string::<::config::StrictCapPfx>().unwrap();
+        assert_eq!(hex, "0x01030405");
+        assert_eq!(foo.to_hex_string::<::config::Compact>().unwrap(), "1030405");
+        let hex = Foo([0, 0, 0, 1]).to_hex_string::<::config::Width<6, ::config::Compact>>();
+        assert_eq!(hex.unwrap(), "000001");
+        assert_eq!(foo.to_vec(), vec![1, 3, 4, 5]);
+        assert_eq!(Vec::from(foo.clone()), vec![1, 3, 4, 5]);
+        assert_eq!(foo.into_iter().sum::<u8>(), 13);
+        #[derive(Debug)]
+        struct Buf(Box<[u8]>);
+        impl_newtype_bytevec!(Buf, Box<[u8]>);
+        let buf: Buf = vec![0xab, 0xcd].into();
+        assert_eq!(Vec::<u8>::from(buf), vec![0xab, 0xcd]);
+    }
 
     #[test]
     fn implementation() {
+        use std::collections::{BTreeSet, HashSet};
+        #[derive(Debug)]
         struct Bar([u8; 36]);
         impl_newtype_bytearray_ext!(Bar, 36);
+        let bar = Bar::default();
+        assert!(bar == bar.clone());
+        let big = Bar([0xff; 36]);
+        assert!(bar < big);
+        let sorted: BTreeSet<_> = vec![big.clone(), bar.clone()].into_iter().collect();
+        assert!(sorted.iter().next() == Some(&bar));
+        let set: HashSet<_> = vec![big.clone(), big.clone()].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&[0xffu8; 36][..]));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random() {
+        use rand::rngs::mock::StepRng;
+        use rand::Rng;
+
+        #[derive(Debug)]
+        struct Big([u8; 40]);
+        impl_newtype_bytearray_ext!(Big, 40);
+        let mut rng = StepRng::new(1, 1);
+        let foo = Foo::random(&mut rng);
+        assert_eq!(foo.0, [1, 0, 0, 0]);
+        let big: Big = rng.gen();
+        assert!(big.iter().any(|b| *b != 0));
+    }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn proptest_arbitrary() {
+        use proptest::arbitrary::any;
+        use proptest::strategy::{Strategy, ValueTree};
+        use proptest::test_runner::TestRunner;
+
+        let mut runner = TestRunner::deterministic();
+        let tree = any::<Foo>().new_tree(&mut runner).unwrap();
+        let foo = tree.current();
+        assert_eq!(foo.len(), 4);
+        let other = any::<Foo>().new_tree(&mut runner).unwrap().current();
+        assert!(foo != other);
+    }
+
+    #[cfg(feature = "quickcheck")]
+    #[test]
+    fn quickcheck_arbitrary() {
+        use quickcheck::{Arbitrary, Gen};
+
+        let foo = Foo::arbitrary(&mut Gen::new(16));
+        assert_eq!(foo.len(), 4);
+        let shrunk: Vec<_> = Foo([0, 1, 2, 3]).shrink().collect();
+        assert_eq!(
+            shrunk,
+            vec![Foo([0, 0, 2, 3]), Foo([0, 0, 0, 3]), Foo([0; 4])]
+        );
+        assert_eq!(Foo([0; 4]).shrink().count(), 0);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_impl() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let mut u = Unstructured::new(&[1, 2, 3, 4, 5]);
+        assert_eq!(Foo::arbitrary(&mut u).unwrap(), Foo([1, 2, 3, 4]));
+        assert_eq!(Foo::size_hint(0), (4, Some(4)));
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn consttime_eq() {
+        use subtle::ConstantTimeEq;
+
+        #[derive(Debug, Clone)]
+        struct Mac([u8; 4]);
+        impl_newtype_bytearray!(Mac, 4, consttime);
+        assert!(Mac([1, 2, 3, 4]) == Mac([1, 2, 3, 4]));
+        assert!(Mac([1, 2, 3, 4]) != Mac([1, 2, 3, 5]));
+
+        #[derive(Debug)]
+        struct Secret([u8; 48]);
+        impl_newtype_bytearray_ext!(Secret, 48, consttime);
+        let secret = Secret([0xaa; 48]);
+        assert!(bool::from(secret.ct_eq(&secret.clone())));
+        assert!(secret != Secret::default());
+        assert!(Secret::default() < secret);
+    }
+
+    #[test]
+    fn string_conversions() {
+        use config::{CompactPfx, StrictCapPfx};
+        use types::ParseHexError;
+
+        #[derive(Debug, Clone)]
+        struct Baz([u8; 4]);
+        impl_newtype_bytearray!(Baz, 4, StrictCapPfx);
+        let baz: Baz = "0xdeadBEEF".parse().unwrap();
+        assert_eq!(baz.0, [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(baz.to_string(), "0xDEADBEEF");
+        assert_eq!(format!("{:>12}", baz), "  0xDEADBEEF");
+        match Baz::try_from("0xdeadbee") {
+            Err(ParseHexError::Size {
+                expect: 8,
+                actual: 7,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other.map(|b| b.0)),
+        }
+
+        #[derive(Debug)]
+        struct Qux([u8; 40]);
+        impl_newtype_bytearray_ext!(Qux, 40, CompactPfx);
+        let qux: Qux = "0xabc".parse().unwrap();
+        assert_eq!(&qux[38..], &[0x0a, 0xbc]);
+        assert_eq!(qux.to_string(), "0xabc");
     }
 }
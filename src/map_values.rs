@@ -0,0 +1,105 @@
+//! `with`-target for maps whose values are hex-encoded.
+//!
+//! `#[serde(with = "HexValues::<C>")]` encodes every value of a map (e.g. a
+//! `HashMap` or `BTreeMap`) via its `SerHex<C>` impl, leaving the keys to
+//! their own `Serialize`/`Deserialize` impls.  This is equivalent to using
+//! `Hex<V, C>` as the value type, without changing the type of the field.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::map_values::HexValues;
+//! # use serde_hex::StrictPfx;
+//! # use std::collections::BTreeMap;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "HexValues::<StrictPfx>")]
+//!     keys: BTreeMap<String, [u8; 2]>,
+//! }
+//!
+//! # fn main() {
+//! let mut keys = BTreeMap::new();
+//! keys.insert("alice".to_string(), [0xab, 0xcd]);
+//! let config = Config { keys };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"keys":{"alice":"0xabcd"}}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! # }
+//! ```
+use config::{HexConf, Strict};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use utils;
+use wrapper::Hex;
+use {HexRef, SerHex};
+
+/// `with`-target which (de)serializes the values of a map as hex under
+/// config `C`.
+///
+/// Serialization accepts any map which iterates by reference as key/value
+/// pairs, and deserialization any container which implements
+/// `FromIterator<(K, V)>`.
+pub struct HexValues<C = Strict>(PhantomData<C>);
+
+impl<C: HexConf> HexValues<C> {
+    /// serialize `map`, encoding each value as hex.
+    pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        M: ?Sized,
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+        K: 'a + Serialize,
+        V: 'a + SerHex<C>,
+        S: Serializer,
+    {
+        let entries = map.into_iter().map(|(key, value)| {
+            let value = HexRef {
+                src: value,
+                conf: PhantomData::<C>,
+            };
+            (key, value)
+        });
+        serializer.collect_map(entries)
+    }
+
+    /// deserialize a map whose values are hex strings.
+    pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: FromIterator<(K, V)>,
+        K: Deserialize<'de>,
+        V: SerHex<C>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor::<C, K, V, M>(PhantomData))
+    }
+}
+
+// collects a map with hex string values into any `FromIterator` container.
+struct MapVisitor<C, K, V, M>(PhantomData<(C, K, V, M)>);
+
+impl<'de, C, K, V, M> de::Visitor<'de> for MapVisitor<C, K, V, M>
+where
+    C: HexConf,
+    K: Deserialize<'de>,
+    V: SerHex<C>,
+    M: FromIterator<(K, V)>,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map with hex string values")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<M, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        utils::collect_until_err(|| {
+            map.next_entry::<K, Hex<V, C>>()
+                .map(|entry| entry.map(|(key, value)| (key, value.into_inner())))
+        })
+    }
+}
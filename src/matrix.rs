@@ -0,0 +1,220 @@
+//! `with`-target for two-dimensional byte arrays.
+//!
+//! `#[serde(with = "HexMatrix::<C, L>")]` (de)serializes a `[[u8; N]; M]`
+//! (e.g. a lookup table or S-box) of any size under config `C`, in one of
+//! two layouts:
+//!
+//! - `Concat`: a single hex string of all rows in order (row-major), which
+//!   the config treats as one byte array of `N * M` bytes.
+//! - `Rows`: a list of `M` hex strings, each a row of `N` bytes.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::matrix::{Concat, HexMatrix, Rows};
+//! # use serde_hex::{Strict, StrictPfx};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Cipher {
+//!     #[serde(with = "HexMatrix::<Strict, Concat>")]
+//!     sbox: [[u8; 2]; 2],
+//!     #[serde(with = "HexMatrix::<StrictPfx, Rows>")]
+//!     keys: [[u8; 3]; 2],
+//! }
+//!
+//! # fn main() {
+//! let cipher = Cipher {
+//!     sbox: [[0x63, 0x7c], [0x77, 0x7b]],
+//!     keys: [[0x00, 0x01, 0x02], [0xaa, 0xbb, 0xcc]],
+//! };
+//! let json = serde_json::to_string(&cipher).unwrap();
+//! assert_eq!(json, r#"{"sbox":"637c777b","keys":["0x000102","0xaabbcc"]}"#);
+//! assert_eq!(serde_json::from_str::<Cipher>(&json).unwrap(), cipher);
+//! # }
+//! ```
+use config::{HexConf, Strict};
+use io;
+use serde::{de, Deserializer, Serializer};
+use std::marker::PhantomData;
+use types::Error;
+use utils;
+use wrapper::Hex;
+use {SerHex, SerHexList};
+
+/// Layout of a matrix, as selected by the second parameter of `HexMatrix`.
+pub trait MatrixLayout {
+    /// serialize `matrix` in this layout.
+    fn serialize<C, S, const N: usize, const M: usize>(
+        matrix: &[[u8; N]; M],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        C: HexConf,
+        S: Serializer;
+
+    /// deserialize a matrix in this layout.
+    fn deserialize<'de, C, D, const N: usize, const M: usize>(
+        deserializer: D,
+    ) -> Result<[[u8; N]; M], D::Error>
+    where
+        C: HexConf,
+        D: Deserializer<'de>;
+}
+
+/// Layout which writes a matrix as a single hex string, row by row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Concat;
+
+impl MatrixLayout for Concat {
+    fn serialize<C, S, const N: usize, const M: usize>(
+        matrix: &[[u8; N]; M],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        C: HexConf,
+        S: Serializer,
+    {
+        <Flat<N, M> as SerHex<C>>::serialize(&Flat(*matrix), serializer)
+    }
+
+    fn deserialize<'de, C, D, const N: usize, const M: usize>(
+        deserializer: D,
+    ) -> Result<[[u8; N]; M], D::Error>
+    where
+        C: HexConf,
+        D: Deserializer<'de>,
+    {
+        <Flat<N, M> as SerHex<C>>::deserialize(deserializer).map(|flat| flat.0)
+    }
+}
+
+/// Layout which writes a matrix as a list of hex strings, one per row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rows;
+
+impl MatrixLayout for Rows {
+    fn serialize<C, S, const N: usize, const M: usize>(
+        matrix: &[[u8; N]; M],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        C: HexConf,
+        S: Serializer,
+    {
+        let rows = matrix
+            .iter()
+            .map(|row| Hex::<Flat<N, 1>, C>::from(Flat([*row])));
+        serializer.collect_seq(rows)
+    }
+
+    fn deserialize<'de, C, D, const N: usize, const M: usize>(
+        deserializer: D,
+    ) -> Result<[[u8; N]; M], D::Error>
+    where
+        C: HexConf,
+        D: Deserializer<'de>,
+    {
+        let rows = <Flat<N, 1> as SerHexList<C>>::deserialize_array::<D, M>(deserializer)?;
+        Ok(rows.map(|row| row.0[0]))
+    }
+}
+
+/// `with`-target which (de)serializes a `[[u8; N]; M]` under config `C`, in
+/// the layout `L`.
+pub struct HexMatrix<C = Strict, L = Concat>(PhantomData<(C, L)>);
+
+impl<C: HexConf, L: MatrixLayout> HexMatrix<C, L> {
+    /// serialize `matrix` in layout `L`.
+    pub fn serialize<S, const N: usize, const M: usize>(
+        matrix: &[[u8; N]; M],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        L::serialize::<C, S, N, M>(matrix, serializer)
+    }
+
+    /// deserialize a matrix in layout `L`.
+    pub fn deserialize<'de, D, const N: usize, const M: usize>(
+        deserializer: D,
+    ) -> Result<[[u8; N]; M], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        L::deserialize::<C, D, N, M>(deserializer)
+    }
+}
+
+// a matrix (or, with `M = 1`, a single row) encoded as one contiguous
+// byte array.
+struct Flat<const N: usize, const M: usize>([[u8; N]; M]);
+
+impl<C, const N: usize, const M: usize> SerHex<C> for Flat<N, M>
+where
+    C: HexConf,
+{
+    type Error = Error;
+
+    fn into_hex_raw<D>(&self, dst: D) -> Result<(), Self::Error>
+    where
+        D: io::Write,
+    {
+        let src = self.0.as_flattened();
+        match C::width() {
+            Some(width) => utils::writehex_width::<C, _>(src, width, dst),
+            None => utils::writehex_with::<C, _>(src, dst),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        let pfx = if C::withpfx() { 2 } else { 0 };
+        pfx + C::width().unwrap_or(N * M * 2)
+    }
+
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        err.into_de_error(src)
+    }
+
+    fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        let mut flat = Flat([[0u8; N]; M]);
+        utils::fromhex_with::<C>(flat.0.as_flattened_mut(), src.as_ref())?;
+        Ok(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Concat, HexMatrix, Rows};
+    use config::{Compact, StrictCap};
+    use serde::de::value::{BorrowedStrDeserializer, Error, SeqDeserializer};
+
+    #[test]
+    fn layouts() {
+        let hex = BorrowedStrDeserializer::<Error>::new("ABCDEF01");
+        let matrix: [[u8; 2]; 2] = HexMatrix::<StrictCap, Concat>::deserialize(hex).unwrap();
+        assert_eq!(matrix, [[0xab, 0xcd], [0xef, 0x01]]);
+        let hex = BorrowedStrDeserializer::<Error>::new("abc");
+        let matrix: [[u8; 1]; 3] = HexMatrix::<Compact, Concat>::deserialize(hex).unwrap();
+        assert_eq!(matrix, [[0x00], [0x0a], [0xbc]]);
+        let hex = BorrowedStrDeserializer::<Error>::new("abcdef");
+        let rslt = HexMatrix::<StrictCap, Concat>::deserialize::<_, 2, 2>(hex);
+        assert!(rslt.is_err());
+        let rows = vec!["0a", "bc"];
+        let rows = rows.into_iter().map(BorrowedStrDeserializer::<Error>::new);
+        let de: SeqDeserializer<_, Error> = SeqDeserializer::new(rows);
+        let matrix: [[u8; 1]; 2] = HexMatrix::<Compact, Rows>::deserialize(de).unwrap();
+        assert_eq!(matrix, [[0x0a], [0xbc]]);
+        let rows = vec!["0a"];
+        let rows = rows.into_iter().map(BorrowedStrDeserializer::<Error>::new);
+        let de: SeqDeserializer<_, Error> = SeqDeserializer::new(rows);
+        assert!(HexMatrix::<Compact, Rows>::deserialize::<_, 1, 2>(de).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Single hex digit (`u4`) values.
+//!
+//! `Nibble` serializes as exactly one hex character under any config, so
+//! arrays of nibbles (via the blanket strict array impls) encode one digit
+//! per element.  Useful for BCD-style and packed-nibble protocol fields.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::{Nibble, SerHex, StrictPfx};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Frame {
+//!     #[serde(with = "SerHex::<StrictPfx>")]
+//!     kind: Nibble,
+//!     #[serde(with = "SerHex::<StrictPfx>")]
+//!     digits: [Nibble; 4],
+//! }
+//!
+//! # fn main() {
+//! let digits = [Nibble::new(2).unwrap(), Nibble::new(0).unwrap(),
+//!     Nibble::new(2).unwrap(), Nibble::new(6).unwrap()];
+//! let frame = Frame { kind: Nibble::new(0xa).unwrap(), digits };
+//! let json = serde_json::to_string(&frame).unwrap();
+//! assert_eq!(json, r#"{"kind":"0xa","digits":"0x2026"}"#);
+//! assert_eq!(serde_json::from_str::<Frame>(&json).unwrap(), frame);
+//! assert!(serde_json::from_str::<Frame>(r#"{"kind":"0x0a","digits":"0x2026"}"#).is_err());
+//! # }
+//! ```
+use config::HexConf;
+use io;
+use serde::de;
+use types::{Error, ParseHexError};
+use utils::{fromval, fromvalcaps, intoval, prefix, strippfx};
+use SerHex;
+
+/// A four-bit value, encoded as a single hex digit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nibble(u8);
+
+impl Nibble {
+    /// nibble with value `val`, if it is less than 16.
+    pub fn new(val: u8) -> Option<Self> {
+        if val < 0x10 {
+            Some(Nibble(val))
+        } else {
+            None
+        }
+    }
+
+    /// split a byte into its high and low nibbles.
+    pub fn split(byte: u8) -> [Nibble; 2] {
+        [Nibble(byte >> 4), Nibble(byte & 0x0f)]
+    }
+
+    /// join a high and a low nibble into a byte.
+    pub fn join(high: Nibble, low: Nibble) -> u8 {
+        (high.0 << 4) | low.0
+    }
+
+    /// value of the nibble.
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<Nibble> for u8 {
+    fn from(nibble: Nibble) -> u8 {
+        nibble.0
+    }
+}
+
+impl<C> SerHex<C> for Nibble
+where
+    C: HexConf,
+{
+    type Error = Error;
+
+    fn into_hex_raw<D>(&self, mut dst: D) -> Result<(), Self::Error>
+    where
+        D: io::Write,
+    {
+        if <C as HexConf>::withpfx() {
+            dst.write_all(prefix::<C>())?;
+        }
+        let chr = if <C as HexConf>::withcap() {
+            fromvalcaps(self.0)
+        } else {
+            fromval(self.0)
+        };
+        dst.write_all(&[chr])?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> usize {
+        if <C as HexConf>::withpfx() {
+            3
+        } else {
+            1
+        }
+    }
+
+    fn de_error<E>(err: Self::Error, src: &[u8]) -> E
+    where
+        E: de::Error,
+    {
+        err.into_de_error(src)
+    }
+
+    fn from_hex_raw<S>(src: S) -> Result<Self, Self::Error>
+    where
+        S: AsRef<[u8]>,
+    {
+        let src = src.as_ref();
+        let hex = strippfx(src, <C as HexConf>::acceptpfx());
+        let pfx = src.len() - hex.len();
+        if <C as HexConf>::compact() && <C as HexConf>::emptyzero() && hex.is_empty() {
+            return Ok(Nibble(0));
+        }
+        if hex.len() != 1 {
+            return Err(ParseHexError::size(1, hex.len()).into());
+        }
+        let val = intoval(hex[0]).map_err(|e| e.shift(pfx))?;
+        Ok(Nibble(val))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Nibble;
+    use config::{Compact, Strict, StrictCap};
+    use types::{Error, ParseHexError};
+    use SerHex;
+
+    #[test]
+    fn nibbles() {
+        let [high, low] = Nibble::split(0xaf);
+        assert_eq!((high.get(), low.get()), (0x0a, 0x0f));
+        assert_eq!(Nibble::join(high, low), 0xaf);
+        assert!(Nibble::new(0x10).is_none());
+        assert_eq!(<Nibble as SerHex<StrictCap>>::into_hex(&low).unwrap(), "F");
+        let bcd: [Nibble; 3] = SerHex::<Strict>::from_hex("409").unwrap();
+        assert_eq!(bcd, [Nibble(4), Nibble(0), Nibble(9)]);
+        match <Nibble as SerHex<Compact>>::from_hex("0a") {
+            Err(Error::Parsing(ParseHexError::Size {
+                expect: 1,
+                actual: 2,
+            })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match <Nibble as SerHex<Strict>>::from_hex("g") {
+            Err(Error::Parsing(ParseHexError::Char { val: 'g', index: 0 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
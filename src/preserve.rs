@@ -0,0 +1,274 @@
+//! Decoded values which re-serialize in the form they were received in.
+//!
+//! `Hex<T, C>` always re-encodes under config `C`, so a pass-through
+//! service which decodes `0xABCD` hands back `0xabcd`.  `Preserved<T, C>`
+//! keeps the received text next to the decoded value, and emits it again
+//! unchanged.  If the value is replaced, the new encoding follows the
+//! prefix and letter case of the original input (see `HexForm`).
+//!
+//! ```rust
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::preserve::{LetterCase, Preserved};
+//! # use serde_hex::CompactPfx;
+//! # fn main() {
+//! let mut fee: Preserved<u32, CompactPfx> = serde_json::from_str(r#""0x0ABC""#).unwrap();
+//! assert_eq!(*fee, 0xabc);
+//! assert_eq!(fee.form().case, LetterCase::Upper);
+//! assert_eq!(serde_json::to_string(&fee).unwrap(), r#""0x0ABC""#);
+//! fee.set(0xdef).unwrap();
+//! assert_eq!(serde_json::to_string(&fee).unwrap(), r#""0xDEF""#);
+//! # }
+//! ```
+use alloc::string::String;
+use config::{HexConf, Strict};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::{fmt, str};
+use SerHex;
+
+/// Case of the letters (`a-f`) in some hex text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LetterCase {
+    /// all letters are lowercase.
+    Lower,
+    /// all letters are uppercase.
+    Upper,
+    /// both cases are present.
+    Mixed,
+    /// there are no letters, so the case isn't known.
+    Unknown,
+}
+
+/// Surface form of some hex text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct HexForm {
+    /// whether the text began with a `0x` (or `0X`) prefix.
+    pub prefix: bool,
+    /// whether the prefix was `0X`.
+    pub pfxcap: bool,
+    /// case of the letters which followed the prefix.
+    pub case: LetterCase,
+}
+
+impl HexForm {
+    /// detect the form of `text`.
+    pub fn of(text: &str) -> Self {
+        let pfxcap = text.starts_with("0X");
+        let prefix = pfxcap || text.starts_with("0x");
+        let digits = if prefix { &text[2..] } else { text };
+        let upper = digits.bytes().any(|c| c.is_ascii_uppercase());
+        let lower = digits.bytes().any(|c| c.is_ascii_lowercase());
+        let case = match (upper, lower) {
+            (true, false) => LetterCase::Upper,
+            (false, true) => LetterCase::Lower,
+            (true, true) => LetterCase::Mixed,
+            (false, false) => LetterCase::Unknown,
+        };
+        HexForm {
+            prefix,
+            pfxcap,
+            case,
+        }
+    }
+
+    /// rewrite `text` (as encoded under config `C`) into this form.  mixed
+    /// or unknown case leaves the letters as `C` encoded them.
+    pub fn apply<C: HexConf>(&self, text: &str) -> String {
+        let digits = if C::withpfx() && (text.starts_with("0x") || text.starts_with("0X")) {
+            &text[2..]
+        } else {
+            text
+        };
+        let mut rslt = String::with_capacity(digits.len() + 2);
+        if self.prefix {
+            rslt.push_str(if self.pfxcap { "0X" } else { "0x" });
+        }
+        match self.case {
+            LetterCase::Lower => rslt.extend(digits.chars().map(|c| c.to_ascii_lowercase())),
+            LetterCase::Upper => rslt.extend(digits.chars().map(|c| c.to_ascii_uppercase())),
+            LetterCase::Mixed | LetterCase::Unknown => rslt.push_str(digits),
+        }
+        rslt
+    }
+}
+
+/// Value decoded via `SerHex<C>`, which serializes as the exact text it was
+/// decoded from.
+///
+/// Values built with `new` use the form produced by `C`.  Comparison is on
+/// both the value and the text, so `0xab` and `0xAB` are distinct.
+pub struct Preserved<T, C = Strict> {
+    value: T,
+    text: String,
+    conf: PhantomData<C>,
+}
+
+impl<T, C> Preserved<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    /// wrap `value`, encoding it under config `C`.
+    pub fn new(value: T) -> Result<Self, T::Error> {
+        let text = value.into_hex()?;
+        Ok(Preserved {
+            value,
+            text,
+            conf: PhantomData,
+        })
+    }
+
+    /// decode `text` under config `C`, keeping the text as-is.
+    pub fn parse(text: String) -> Result<Self, T::Error> {
+        let value = T::from_hex(&text)?;
+        Ok(Preserved {
+            value,
+            text,
+            conf: PhantomData,
+        })
+    }
+
+    /// replace the value, re-encoding it in the form of the current text.
+    pub fn set(&mut self, value: T) -> Result<(), T::Error> {
+        let text = self.form().apply::<C>(&value.into_hex()?);
+        self.value = value;
+        self.text = text;
+        Ok(())
+    }
+}
+
+impl<T, C> Preserved<T, C> {
+    /// the text which will be serialized.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// form (prefix and letter case) of the text.
+    pub fn form(&self) -> HexForm {
+        HexForm::of(&self.text)
+    }
+
+    /// unwrap the decoded value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, C> Deref for Preserved<T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, C> AsRef<T> for Preserved<T, C> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, C> str::FromStr for Preserved<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    type Err = T::Error;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Preserved::parse(src.into())
+    }
+}
+
+impl<T, C> fmt::Display for Preserved<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.text)
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for Preserved<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Preserved")
+            .field("value", &self.value)
+            .field("text", &self.text)
+            .finish()
+    }
+}
+
+impl<T: Clone, C> Clone for Preserved<T, C> {
+    fn clone(&self) -> Self {
+        Preserved {
+            value: self.value.clone(),
+            text: self.text.clone(),
+            conf: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, C> PartialEq for Preserved<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.text == other.text
+    }
+}
+
+impl<T: Eq, C> Eq for Preserved<T, C> {}
+
+impl<T, C> Serialize for Preserved<T, C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for Preserved<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        match T::from_hex(&text) {
+            Ok(value) => Ok(Preserved {
+                value,
+                text,
+                conf: PhantomData,
+            }),
+            Err(err) => Err(T::de_error(err, text.as_bytes())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HexForm, LetterCase, Preserved};
+    use config::{Strict, StrictPfx};
+
+    #[test]
+    fn forms() {
+        let form = HexForm::of("0xAb");
+        assert!(form.prefix && !form.pfxcap);
+        assert!(HexForm::of("0Xab").pfxcap);
+        assert_eq!(form.case, LetterCase::Mixed);
+        assert_eq!(HexForm::of("1234").case, LetterCase::Unknown);
+        // the prefix is optional when parsing, and its absence is kept.
+        let mut key: Preserved<[u8; 2], StrictPfx> = "ABCD".parse().unwrap();
+        assert_eq!(*key, [0xab, 0xcd]);
+        key.set([0x01, 0xef]).unwrap();
+        assert_eq!(key.as_str(), "01EF");
+        let mut key: Preserved<[u8; 2], Strict> = "0123".parse().unwrap();
+        key.set([0xab, 0xcd]).unwrap();
+        assert_eq!(key.as_str(), "abcd");
+        assert_eq!(
+            Preserved::<u8, StrictPfx>::new(0xab).unwrap().as_str(),
+            "0xab"
+        );
+    }
+}
@@ -0,0 +1,609 @@
+//! Ready-made types for common domain-specific hex formats.
+//!
+//! Each type wraps a byte array, and implements `Display`/`FromStr` and
+//! `Serialize`/`Deserialize` in its domain's usual format:
+//!
+//! - `EthAddress`: 20 bytes, `0x`-prefixed, with the EIP-55 mixed-case
+//!   checksum.  Mixed-case input must carry a valid checksum, and all-lower
+//!   or all-upper input is accepted unchecked.
+//! - `BtcTxid`: 32 bytes, displayed in reverse byte order (as by Bitcoin
+//!   tooling), while the array holds the bytes in hash order.
+//! - `Sha256Fingerprint`: 32 bytes as colon-separated uppercase pairs (as
+//!   in certificate fingerprints).
+//! - `MacAddress`: 6 bytes as colon-separated lowercase pairs.
+//! - `Rgb` and `Rgba`: CSS colors, written as `#rrggbb` and `#rrggbbaa`.
+//!   Parsing also accepts the `#rgb` (and `#rgba`) shorthand, and the `#`
+//!   may be omitted.  `Rgba` accepts colors without alpha as opaque.
+//! - `SerialNumber<N>`: any number of bytes as dash-separated groups of four
+//!   uppercase digits (e.g. `ABCD-1234-EF56-7890`), as in license keys and
+//!   hardware serial numbers.  Dashes are ignored when parsing, wherever
+//!   they appear.
+//!
+//! Parsing accepts either case (subject to the checksum rule above).
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::presets::{BtcTxid, EthAddress};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Transfer {
+//!     to: EthAddress,
+//!     funding: BtcTxid,
+//! }
+//!
+//! # fn main() {
+//! let to: EthAddress = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap();
+//! assert_eq!(to.to_string(), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+//! let mut hash = [0u8; 32];
+//! hash[0] = 0xab;
+//! let transfer = Transfer { to, funding: BtcTxid(hash) };
+//! let json = serde_json::to_string(&transfer).unwrap();
+//! assert!(json.ends_with(r#"00ab"}"#));
+//! assert_eq!(serde_json::from_str::<Transfer>(&json).unwrap(), transfer);
+//! assert!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".parse::<EthAddress>().is_err());
+//! # }
+//! ```
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Write};
+use std::marker::PhantomData;
+use std::str;
+use types::ParseHexError;
+use utils;
+
+/// An Ethereum address, written with its EIP-55 checksum.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EthAddress(pub [u8; 20]);
+
+/// A Bitcoin transaction id, written in reverse byte order.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BtcTxid(pub [u8; 32]);
+
+/// A SHA-256 fingerprint, written as colon-separated uppercase pairs.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sha256Fingerprint(pub [u8; 32]);
+
+/// A MAC address, written as colon-separated lowercase pairs.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+/// A CSS color, written as `#rrggbb`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rgb(pub [u8; 3]);
+
+/// A CSS color with alpha, written as `#rrggbbaa`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rgba(pub [u8; 4]);
+
+/// A serial number, written as dash-separated groups of four uppercase
+/// digits.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SerialNumber<const N: usize>(pub [u8; N]);
+
+impl_newtype_array!(EthAddress, u8, 20);
+impl_newtype_array!(BtcTxid, u8, 32);
+impl_newtype_array!(Sha256Fingerprint, u8, 32);
+impl_newtype_array!(MacAddress, u8, 6);
+impl_newtype_array!(Rgb, u8, 3);
+impl_newtype_array!(Rgba, u8, 4);
+
+impl fmt::Display for EthAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = [0u8; 42];
+        buf[..2].copy_from_slice(b"0x");
+        utils::intohex(&mut buf[2..], &self.0);
+        checksum(&mut buf[2..]);
+        f.pad(str::from_utf8(&buf).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl str::FromStr for EthAddress {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.as_bytes();
+        let hex = utils::strippfx(src, true);
+        if hex.len() == src.len() {
+            return Err(ParseHexError::MissingPrefix);
+        }
+        let mut buf = [0u8; 20];
+        utils::fromhex(&mut buf, hex).map_err(|e| e.shift(2))?;
+        let lower = !hex.iter().any(u8::is_ascii_uppercase);
+        let upper = !hex.iter().any(u8::is_ascii_lowercase);
+        if !lower && !upper {
+            let mut expect = [0u8; 40];
+            utils::intohex(&mut expect, &buf);
+            checksum(&mut expect);
+            if expect[..] != hex[..] {
+                return Err(ParseHexError::Rejected);
+            }
+        }
+        Ok(EthAddress(buf))
+    }
+}
+
+impl fmt::Display for BtcTxid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rev = self.0;
+        rev.reverse();
+        let mut buf = [0u8; 64];
+        utils::intohex(&mut buf, &rev);
+        f.pad(str::from_utf8(&buf).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl str::FromStr for BtcTxid {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut buf = [0u8; 32];
+        utils::fromhex(&mut buf, src.as_bytes())?;
+        buf.reverse();
+        Ok(BtcTxid(buf))
+    }
+}
+
+impl fmt::Display for Sha256Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        pad_grouped(f, &self.0, 1, ':', true)
+    }
+}
+
+impl str::FromStr for Sha256Fingerprint {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(Sha256Fingerprint(grouped(src)?))
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        pad_grouped(f, &self.0, 1, ':', false)
+    }
+}
+
+impl str::FromStr for MacAddress {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(MacAddress(grouped(src)?))
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = *b"#000000";
+        utils::intohex(&mut buf[1..], &self.0);
+        f.pad(str::from_utf8(&buf).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl str::FromStr for Rgb {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let [r, g, b, _] = color(src, false)?;
+        Ok(Rgb([r, g, b]))
+    }
+}
+
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = *b"#00000000";
+        utils::intohex(&mut buf[1..], &self.0);
+        f.pad(str::from_utf8(&buf).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl str::FromStr for Rgba {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        color(src, true).map(Rgba)
+    }
+}
+
+impl<const N: usize> fmt::Display for SerialNumber<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        pad_grouped(f, &self.0, 2, '-', true)
+    }
+}
+
+impl<const N: usize> str::FromStr for SerialNumber<N> {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut buf = [0u8; N];
+        let mut digits = 0;
+        for (index, val) in src.char_indices().filter(|(_, c)| *c != '-') {
+            if !val.is_ascii() {
+                return Err(ParseHexError::Char { val, index });
+            }
+            let nibble = utils::intoval(val as u8).map_err(|e| e.shift(index))?;
+            if digits < N * 2 {
+                buf[digits / 2] |= nibble << (4 * (1 - digits % 2));
+            }
+            digits += 1;
+        }
+        if digits != N * 2 {
+            return Err(ParseHexError::size(N * 2, digits));
+        }
+        Ok(SerialNumber(buf))
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for SerialNumber<N> {
+    fn from(inner: [u8; N]) -> Self {
+        SerialNumber(inner)
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for SerialNumber<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+// `Debug` as the display form, and serde via `Display`/`FromStr`.
+macro_rules! impl_preset {
+    ($name: ident, $expecting: expr) => {
+        impl_preset!([] $name, $name, $expecting);
+    };
+    ([$($gen: tt)*] $name: ident, $type: ty, $expecting: expr) => {
+        impl<$($gen)*> fmt::Debug for $type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self)
+            }
+        }
+
+        impl<$($gen)*> Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de, $($gen)*> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(PresetVisitor::<$type>($expecting, PhantomData))
+            }
+        }
+    };
+}
+
+impl_preset!(EthAddress, "a checksummed ethereum address");
+impl_preset!(BtcTxid, "a bitcoin transaction id");
+impl_preset!(Sha256Fingerprint, "a colon-separated sha-256 fingerprint");
+impl_preset!(MacAddress, "a colon-separated mac address");
+impl_preset!(Rgb, "a css hex color");
+impl_preset!(Rgba, "a css hex color");
+impl_preset!([const N: usize] SerialNumber, SerialNumber<N>, "a dash-separated serial number");
+
+struct PresetVisitor<T>(&'static str, PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for PresetVisitor<T>
+where
+    T: str::FromStr<Err = ParseHexError>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+
+    fn visit_str<E: de::Error>(self, src: &str) -> Result<T, E> {
+        src.parse()
+            .map_err(|e: ParseHexError| e.into_de_error(src.as_bytes()))
+    }
+}
+
+// write `bytes` as groups of `size` bytes separated by `delim`, padded as
+// `f.pad` would pad the whole string (without buffering it, since the size
+// of a `SerialNumber` isn't known up front).
+fn pad_grouped(
+    f: &mut fmt::Formatter,
+    bytes: &[u8],
+    size: usize,
+    delim: char,
+    cap: bool,
+) -> fmt::Result {
+    let groups = bytes.len().div_ceil(size);
+    let len = bytes.len() * 2 + groups.saturating_sub(1);
+    utils::pad_with(f, len, |f| {
+        for (idx, group) in bytes.chunks(size).enumerate() {
+            if idx > 0 {
+                f.write_char(delim)?;
+            }
+            for byte in group {
+                let mut hex = [0u8; 2];
+                if cap {
+                    utils::intohexcaps(&mut hex, &[*byte]);
+                } else {
+                    utils::intohex(&mut hex, &[*byte]);
+                }
+                f.write_str(str::from_utf8(&hex).map_err(|_| fmt::Error)?)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+// parse colon-separated pairs into exactly `N` bytes.  only the final pair
+// may be short (which is reported as `OddLength`).
+fn grouped<const N: usize>(src: &str) -> Result<[u8; N], ParseHexError> {
+    let mut buf = [0u8; N];
+    let (mut count, mut offset) = (0, 0);
+    let mut pairs = src.as_bytes().split(|c| *c == b':').peekable();
+    while let Some(pair) = pairs.next() {
+        let last = pairs.peek().is_none();
+        match pair.len() {
+            2 => {}
+            0 if last && count == 0 => break,
+            1 if last => return Err(ParseHexError::OddLength { len: 1 }),
+            len => return Err(ParseHexError::size(2, len)),
+        }
+        let byte = utils::intobyte(pair[0], pair[1]).map_err(|e| e.shift(offset))?;
+        if let Some(slot) = buf.get_mut(count) {
+            *slot = byte;
+        }
+        count += 1;
+        offset += 3;
+    }
+    if count != N {
+        return Err(ParseHexError::size(N * 2, count * 2));
+    }
+    Ok(buf)
+}
+
+// parse any of the css hex color forms (those with alpha only if `alpha`
+// is set), defaulting to opaque.
+fn color(src: &str, alpha: bool) -> Result<[u8; 4], ParseHexError> {
+    let src = src.as_bytes();
+    let hex = src.strip_prefix(b"#").unwrap_or(src);
+    let pfx = src.len() - hex.len();
+    let mut rgba = [0, 0, 0, 0xff];
+    match hex.len() {
+        3 | 6 => {}
+        4 | 8 if alpha => {}
+        len => return Err(ParseHexError::Length { len }),
+    }
+    if hex.len() < 6 {
+        for (idx, chr) in hex.iter().enumerate() {
+            let val = utils::intoval(*chr).map_err(|e| e.shift(pfx + idx))?;
+            rgba[idx] = val << 4 | val;
+        }
+    } else {
+        for (idx, pair) in hex.chunks(2).enumerate() {
+            rgba[idx] = utils::intobyte(pair[0], pair[1]).map_err(|e| e.shift(pfx + idx * 2))?;
+        }
+    }
+    Ok(rgba)
+}
+
+// apply the EIP-55 checksum to 40 lowercase hex digits, uppercasing each
+// letter whose nibble in the keccak-256 of the digits is at least 8.
+fn checksum(digits: &mut [u8]) {
+    let hash = keccak256(digits);
+    for (idx, digit) in digits.iter_mut().enumerate() {
+        let nibble = (hash[idx / 2] >> (4 * (1 - idx % 2))) & 0x0f;
+        if nibble >= 8 {
+            digit.make_ascii_uppercase();
+        }
+    }
+}
+
+// keccak-256 (the original padding, as used by ethereum, rather than the
+// sha3-256 padding).
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    const RATE: usize = 136;
+    let mut state = [0u64; 25];
+    let mut block = [0u8; RATE];
+    let mut chunks = data.chunks_exact(RATE);
+    for chunk in &mut chunks {
+        absorb(&mut state, chunk);
+    }
+    let rest = chunks.remainder();
+    block[..rest.len()].copy_from_slice(rest);
+    block[rest.len()] ^= 0x01;
+    block[RATE - 1] ^= 0x80;
+    absorb(&mut state, &block);
+    let mut out = [0u8; 32];
+    for (lane, bytes) in state.iter().zip(out.chunks_mut(8)) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+fn absorb(state: &mut [u64; 25], block: &[u8]) {
+    for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(bytes);
+        *lane ^= u64::from_le_bytes(word);
+    }
+    keccak_f(state);
+}
+
+// the keccak-f[1600] permutation.
+fn keccak_f(a: &mut [u64; 25]) {
+    const RC: [u64; 24] = [
+        0x0000_0000_0000_0001,
+        0x0000_0000_0000_8082,
+        0x8000_0000_0000_808a,
+        0x8000_0000_8000_8000,
+        0x0000_0000_0000_808b,
+        0x0000_0000_8000_0001,
+        0x8000_0000_8000_8081,
+        0x8000_0000_0000_8009,
+        0x0000_0000_0000_008a,
+        0x0000_0000_0000_0088,
+        0x0000_0000_8000_8009,
+        0x0000_0000_8000_000a,
+        0x0000_0000_8000_808b,
+        0x8000_0000_0000_008b,
+        0x8000_0000_0000_8089,
+        0x8000_0000_0000_8003,
+        0x8000_0000_0000_8002,
+        0x8000_0000_0000_0080,
+        0x0000_0000_0000_800a,
+        0x8000_0000_8000_000a,
+        0x8000_0000_8000_8081,
+        0x8000_0000_0000_8080,
+        0x0000_0000_8000_0001,
+        0x8000_0000_8000_8008,
+    ];
+    const RHO: [u32; 24] = [
+        1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+    ];
+    const PI: [usize; 24] = [
+        10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+    ];
+    for rc in RC.iter() {
+        // theta
+        let mut c = [0u64; 5];
+        for (x, col) in c.iter_mut().enumerate() {
+            *col = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[5 * y + x] ^= d;
+            }
+        }
+        // rho and pi
+        let mut last = a[1];
+        for (rho, pi) in RHO.iter().zip(PI.iter()) {
+            let next = a[*pi];
+            a[*pi] = last.rotate_left(*rho);
+            last = next;
+        }
+        // chi
+        for y in 0..5 {
+            let mut row = [0u64; 5];
+            row.copy_from_slice(&a[5 * y..5 * y + 5]);
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        // iota
+        a[0] ^= *rc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        keccak256, BtcTxid, EthAddress, MacAddress, Rgb, Rgba, SerialNumber, Sha256Fingerprint,
+    };
+    use types::ParseHexError;
+    use utils;
+
+    #[test]
+    fn presets() {
+        let mut hash = [0u8; 64];
+        utils::intohex(&mut hash, &keccak256(b""));
+        assert_eq!(
+            &hash[..],
+            &b"c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"[..]
+        );
+        // test vectors from EIP-55.
+        for addr in [
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let parsed: EthAddress = addr.parse().unwrap();
+            assert_eq!(parsed.to_string(), addr);
+            let upper = format!("0x{}", addr[2..].to_uppercase());
+            assert_eq!(upper.parse::<EthAddress>().unwrap(), parsed);
+            assert_eq!(addr.to_lowercase().parse::<EthAddress>().unwrap(), parsed);
+        }
+        match "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse::<EthAddress>() {
+            Err(ParseHexError::MissingPrefix) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let mut txid = [0u8; 32];
+        txid[31] = 0xab;
+        assert!(BtcTxid(txid).to_string().starts_with("ab00"));
+        assert_eq!(
+            BtcTxid(txid).to_string().parse::<BtcTxid>().unwrap().0,
+            txid
+        );
+        let print = Sha256Fingerprint([0xab; 32]).to_string();
+        assert_eq!(print.len(), 32 * 3 - 1);
+        assert!(print.starts_with("AB:AB:"));
+        assert_eq!(
+            print.to_lowercase().parse::<Sha256Fingerprint>().unwrap().0,
+            [0xab; 32]
+        );
+        let mac: MacAddress = "00:1A:2b:3c:4d:5e".parse().unwrap();
+        assert_eq!(mac.to_string(), "00:1a:2b:3c:4d:5e");
+        assert_eq!(format!("{:>19}", mac), "  00:1a:2b:3c:4d:5e");
+        assert_eq!(format!("{:*^21}", mac), "**00:1a:2b:3c:4d:5e**");
+        match "00:1a:2g:3c:4d:5e".parse::<MacAddress>() {
+            Err(ParseHexError::Char { val: 'g', index: 7 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "00:1a:2b3:c4:d5:e".parse::<MacAddress>() {
+            Err(ParseHexError::Size {
+                expect: 2,
+                actual: 3,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "00:1a:2b".parse::<MacAddress>() {
+            Err(ParseHexError::Size {
+                expect: 12,
+                actual: 6,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(Rgb([0x12, 0xab, 0xff]).to_string(), "#12abff");
+        assert_eq!("#1aF".parse::<Rgb>().unwrap(), Rgb([0x11, 0xaa, 0xff]));
+        assert_eq!("12ABff".parse::<Rgb>().unwrap(), Rgb([0x12, 0xab, 0xff]));
+        assert_eq!(
+            "#1af8".parse::<Rgba>().unwrap(),
+            Rgba([0x11, 0xaa, 0xff, 0x88])
+        );
+        assert_eq!(
+            "#12abff".parse::<Rgba>().unwrap(),
+            Rgba([0x12, 0xab, 0xff, 0xff])
+        );
+        assert_eq!(Rgba([0, 0, 0, 0x80]).to_string(), "#00000080");
+        match "#12abff80".parse::<Rgb>() {
+            Err(ParseHexError::Length { len: 8 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "#12aGff".parse::<Rgba>() {
+            Err(ParseHexError::Char { val: 'G', index: 4 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn serial_numbers() {
+        let serial = SerialNumber([0xab, 0xcd, 0x12, 0x34, 0xef, 0x56, 0x78]);
+        assert_eq!(serial.to_string(), "ABCD-1234-EF56-78");
+        assert_eq!(format!("{:<18}|", serial), "ABCD-1234-EF56-78 |");
+        assert_eq!(format!("{:?}", serial), "SerialNumber(ABCD-1234-EF56-78)");
+        assert_eq!(
+            "abcd1234-ef-5678".parse::<SerialNumber<7>>().unwrap(),
+            serial
+        );
+        match "ABCD-1234".parse::<SerialNumber<7>>() {
+            Err(ParseHexError::Size {
+                expect: 14,
+                actual: 8,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match "ABCD_1234".parse::<SerialNumber<4>>() {
+            Err(ParseHexError::Char { val: '_', index: 4 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Schema support via [`schemars`](https://crates.io/crates/schemars) and
+//! [`utoipa`](https://crates.io/crates/utoipa).
+//!
+//! `Hex<T, C>` implements `JsonSchema` (and `utoipa::ToSchema`) as a string
+//! whose `pattern` is derived from the config (prefix, case, and exact or
+//! bounded length, including any fixed `width`).  Fields which use `#[serde(with = "SerHex::<C>")]` can get
+//! the same schema by pointing `schema_with` at `hex_schema` (or, for utoipa,
+//! `openapi_schema`):
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate core;
+//! # extern crate schemars;
+//! # extern crate serde_hex;
+//! # use schemars::JsonSchema;
+//! # use serde_hex::{SerHex, StrictPfx};
+//! #[derive(Serialize, Deserialize, JsonSchema)]
+//! struct Tx {
+//!     #[serde(with = "SerHex::<StrictPfx>")]
+//!     #[schemars(schema_with = "serde_hex::schema::hex_schema::<StrictPfx, [u8; 32]>")]
+//!     hash: [u8; 32],
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! The schema describes the representation which is *emitted*, widened only
+//! for the shorter input a config opts into with `emptyzero` or `padodd`.
+//! Decoding is otherwise more lenient (e.g. the prefix is optional, and
+//! either case is accepted), so the pattern is stricter than what is
+//! actually accepted.
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use config::HexConf;
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use std::mem::size_of;
+#[cfg(feature = "utoipa")]
+use utoipa::openapi::schema::{self as openapi, ObjectBuilder, SchemaFormat, Type};
+#[cfg(feature = "utoipa")]
+use utoipa::openapi::RefOr;
+#[cfg(feature = "utoipa")]
+use utoipa::{PartialSchema, ToSchema};
+use wrapper::Hex;
+
+/// Types whose hex encoding has a known width, used to derive the length
+/// constraint of their schema.
+pub trait HexWidth {
+    /// number of bytes encoded, or `None` if the size is not fixed.
+    const BYTES: Option<usize>;
+}
+
+macro_rules! impl_hex_width {
+    ($($type:ty),*) => {
+        $(
+            impl HexWidth for $type {
+                const BYTES: Option<usize> = Some(size_of::<$type>());
+            }
+        )*
+    };
+}
+
+impl_hex_width!(u8, u16, u32, u64);
+
+impl<T: HexWidth, const N: usize> HexWidth for [T; N] {
+    const BYTES: Option<usize> = match T::BYTES {
+        Some(bytes) => Some(bytes * N),
+        None => None,
+    };
+}
+
+impl HexWidth for Vec<u8> {
+    const BYTES: Option<usize> = None;
+}
+
+/// Regex pattern matching hex emitted under config `C`, for a value of
+/// `bytes` bytes (or any number of bytes, if `None`).  A fixed `width` sets
+/// the exact number of digits of fixed-size values, and the pattern also
+/// admits the shorter input accepted under `emptyzero` and `padodd`.
+pub fn hex_pattern<C: HexConf>(bytes: Option<usize>) -> String {
+    let pfx = C::describe().prefix();
+    let digit = if C::withcap() { "[0-9A-F]" } else { "[0-9a-f]" };
+    let min = if C::emptyzero() { 0 } else { 1 };
+    match (C::compact(), bytes, C::width()) {
+        (_, Some(_), Some(width)) => format!("^{}{}{{{}}}$", pfx, digit, width),
+        (false, Some(bytes), None) if C::padodd() && bytes > 0 => {
+            format!("^{}{}{{{},{}}}$", pfx, digit, bytes * 2 - 1, bytes * 2)
+        }
+        (false, Some(bytes), None) => format!("^{}{}{{{}}}$", pfx, digit, bytes * 2),
+        (false, None, _) if C::padodd() => format!("^{}{}*$", pfx, digit),
+        (false, None, _) => format!("^{}(?:{}{{2}})*$", pfx, digit),
+        (true, Some(bytes), None) => format!("^{}{}{{{},{}}}$", pfx, digit, min, bytes * 2),
+        (true, None, _) if min == 0 => format!("^{}{}*$", pfx, digit),
+        (true, None, _) => format!("^{}{}+$", pfx, digit),
+    }
+}
+
+/// Example of hex emitted under config `C`, for a value of `bytes` bytes
+/// (or of four bytes, if `None`).
+pub fn hex_example<C: HexConf>(bytes: Option<usize>) -> String {
+    let digits = if C::withcap() {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    let len = match (bytes, C::width()) {
+        (Some(_), Some(width)) => width,
+        (bytes, _) => bytes.unwrap_or(4) * 2,
+    };
+    let mut example = String::with_capacity(len + 2);
+    example.push_str(C::describe().prefix());
+    // compact representations have no leading zero to skip.
+    let skip = if C::compact() { 1 } else { 0 };
+    example.extend((0..len).map(|i| digits[(i + skip) % 16] as char));
+    example
+}
+
+// name of the schema of `Hex<T, C>`, which has to be unique for each
+// distinct schema (e.g. `HexStrictPfx32` for `Hex<[u8; 32], StrictPfx>`).
+fn schema_name<C: HexConf>(bytes: Option<usize>) -> String {
+    format!(
+        "Hex{}{}{}{}{}{}{}",
+        if C::compact() { "Compact" } else { "Strict" },
+        if C::withcap() { "Cap" } else { "" },
+        if C::withpfx() { "Pfx" } else { "" },
+        bytes.map(|b| format!("{}", b)).unwrap_or_default(),
+        C::width()
+            .map(|w| format!("Width{}", w))
+            .unwrap_or_default(),
+        if C::emptyzero() { "EmptyZero" } else { "" },
+        if C::padodd() { "PadOdd" } else { "" },
+    )
+}
+
+/// String schema for `T` encoded under config `C`.  Intended for use with
+/// `#[schemars(schema_with = "...")]`.
+#[cfg(feature = "schemars")]
+pub fn hex_schema<C: HexConf, T: HexWidth>(_: &mut SchemaGenerator) -> Schema {
+    ::schemars::json_schema!({
+        "type": "string",
+        "pattern": hex_pattern::<C>(T::BYTES),
+    })
+}
+
+#[cfg(feature = "schemars")]
+impl<T, C> JsonSchema for Hex<T, C>
+where
+    C: HexConf,
+    T: HexWidth,
+{
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        schema_name::<C>(T::BYTES).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("serde_hex::{}", schema_name::<C>(T::BYTES)).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        hex_schema::<C, T>(generator)
+    }
+}
+
+/// OpenAPI schema for `T` encoded under config `C`, with the `hex` format,
+/// a pattern, and an example.  Intended for use with
+/// `#[schema(schema_with = ...)]`.
+#[cfg(feature = "utoipa")]
+pub fn openapi_schema<C: HexConf, T: HexWidth>() -> RefOr<openapi::Schema> {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .format(Some(SchemaFormat::Custom("hex".into())))
+        .pattern(Some(hex_pattern::<C>(T::BYTES)))
+        .examples([hex_example::<C>(T::BYTES)])
+        .into()
+}
+
+#[cfg(feature = "utoipa")]
+impl<T, C> PartialSchema for Hex<T, C>
+where
+    C: HexConf,
+    T: HexWidth,
+{
+    fn schema() -> RefOr<openapi::Schema> {
+        openapi_schema::<C, T>()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<T, C> ToSchema for Hex<T, C>
+where
+    C: HexConf,
+    T: HexWidth,
+{
+    fn name() -> Cow<'static, str> {
+        schema_name::<C>(T::BYTES).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hex_example, hex_pattern};
+    use config::{Compact, CompactPfx, HexConf, StrictCap, StrictPfx, Width};
+    #[cfg(feature = "schemars")]
+    use schemars::SchemaGenerator;
+    use wrapper::Hex;
+
+    struct PadOdd;
+    impl HexConf for PadOdd {
+        fn padodd() -> bool {
+            true
+        }
+    }
+
+    struct ZeroCompact;
+    impl HexConf for ZeroCompact {
+        fn compact() -> bool {
+            true
+        }
+        fn emptyzero() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(hex_pattern::<StrictPfx>(Some(4)), "^0x[0-9a-f]{8}$");
+        assert_eq!(hex_pattern::<StrictCap>(None), "^(?:[0-9A-F]{2})*$");
+        assert_eq!(hex_pattern::<Compact>(Some(8)), "^[0-9a-f]{1,16}$");
+        assert_eq!(hex_pattern::<Compact>(None), "^[0-9a-f]+$");
+        assert_eq!(
+            hex_pattern::<Width<6, CompactPfx>>(Some(4)),
+            "^0x[0-9a-f]{6}$"
+        );
+        assert_eq!(hex_pattern::<PadOdd>(Some(2)), "^[0-9a-f]{3,4}$");
+        assert_eq!(hex_pattern::<PadOdd>(None), "^[0-9a-f]*$");
+        assert_eq!(hex_pattern::<ZeroCompact>(Some(2)), "^[0-9a-f]{0,4}$");
+        assert_eq!(hex_example::<Width<6, CompactPfx>>(Some(4)), "0x123456");
+        assert_eq!(hex_example::<StrictPfx>(Some(2)), "0x0123");
+        assert_eq!(hex_example::<StrictCap>(Some(9)), "0123456789ABCDEF01");
+        assert_eq!(hex_example::<Compact>(None), "12345678");
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn wrapper_schema() {
+        let mut generator = SchemaGenerator::default();
+        let schema = generator.subschema_for::<Hex<[u8; 32], StrictPfx>>();
+        let pattern = schema.get("pattern").and_then(|p| p.as_str());
+        assert_eq!(pattern, Some("^0x[0-9a-f]{64}$"));
+        let schema = generator.subschema_for::<Hex<u64, Compact>>();
+        let pattern = schema.get("pattern").and_then(|p| p.as_str());
+        assert_eq!(pattern, Some("^[0-9a-f]{1,16}$"));
+    }
+
+    #[cfg(feature = "utoipa")]
+    #[test]
+    fn openapi_schema() {
+        use utoipa::openapi::schema::{Schema, SchemaFormat};
+        use utoipa::openapi::RefOr;
+        use utoipa::{PartialSchema, ToSchema};
+
+        assert_eq!(Hex::<[u8; 32], StrictPfx>::name(), "HexStrictPfx32");
+        assert_eq!(Hex::<u64, Compact>::name(), "HexCompact8");
+        assert_eq!(Hex::<u64, Width<4, Compact>>::name(), "HexCompact8Width4");
+        match Hex::<[u8; 2], StrictPfx>::schema() {
+            RefOr::T(Schema::Object(obj)) => {
+                assert_eq!(obj.pattern.as_deref(), Some("^0x[0-9a-f]{4}$"));
+                assert!(obj.format == Some(SchemaFormat::Custom("hex".into())));
+                let examples: Vec<_> = obj.examples.iter().map(|e| e.as_str()).collect();
+                assert_eq!(examples, [Some("0x0123")]);
+            }
+            _ => panic!("expected an inline object schema"),
+        }
+    }
+}
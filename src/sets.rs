@@ -0,0 +1,146 @@
+//! `with`-target for sets of hex-encoded values.
+//!
+//! `#[serde(with = "HexSet::<C>")]` serializes a set (e.g. a `HashSet` or
+//! `BTreeSet` of byte arrays) as a list of hex strings under config `C`,
+//! sorted so that the output doesn't depend on the iteration order of the
+//! set.  Deserialization rejects lists which contain the same value twice,
+//! rather than silently dropping the duplicate.  Where the order of the set
+//! is meaningful (as for an `IndexSet`), use the `combinators` adapter
+//! `As::<IndexSet<HexAs<C>>>` instead, which keeps insertion order.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # use serde_hex::sets::HexSet;
+//! # use serde_hex::StrictPfx;
+//! # use std::collections::HashSet;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Inventory {
+//!     #[serde(with = "HexSet::<StrictPfx>")]
+//!     blocks: HashSet<[u8; 2]>,
+//! }
+//!
+//! # fn main() {
+//! let blocks = vec![[0xff, 0x00], [0x00, 0x01]].into_iter().collect();
+//! let inventory = Inventory { blocks };
+//! let json = serde_json::to_string(&inventory).unwrap();
+//! assert_eq!(json, r#"{"blocks":["0x0001","0xff00"]}"#);
+//! assert_eq!(serde_json::from_str::<Inventory>(&json).unwrap(), inventory);
+//! let dup = r#"{"blocks":["0x0001","0x0001"]}"#;
+//! assert!(serde_json::from_str::<Inventory>(dup).is_err());
+//! # }
+//! ```
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use serde::{de, Deserializer, Serializer};
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use wrapper::Hex;
+use {HexRef, SerHex};
+
+/// `with`-target which (de)serializes a set as a sorted list of hex strings
+/// under config `C`.
+///
+/// Serialization accepts any collection which iterates by reference, and
+/// deserialization any container which implements `FromIterator<T>`.
+/// Elements are ordered (and duplicates detected) via `Ord`, which for byte
+/// arrays matches the order of their strict encodings.
+pub struct HexSet<C = Strict>(PhantomData<C>);
+
+impl<C: HexConf> HexSet<C> {
+    /// serialize `set` as a sorted list of hex strings.
+    pub fn serialize<'a, L, T, S>(set: &'a L, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ?Sized,
+        &'a L: IntoIterator<Item = &'a T>,
+        T: 'a + Ord + SerHex<C>,
+        S: Serializer,
+    {
+        let mut items: Vec<&T> = set.into_iter().collect();
+        items.sort();
+        let items = items.into_iter().map(|item| HexRef {
+            src: item,
+            conf: PhantomData::<C>,
+        });
+        serializer.collect_seq(items)
+    }
+
+    /// deserialize a list of unique hex strings.
+    pub fn deserialize<'de, L, T, D>(deserializer: D) -> Result<L, D::Error>
+    where
+        L: FromIterator<T>,
+        T: Ord + SerHex<C>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SetVisitor::<C, T, L>(PhantomData))
+    }
+}
+
+// collects a sequence of unique hex strings into any `FromIterator` container.
+struct SetVisitor<C, T, L>(PhantomData<(C, T, L)>);
+
+impl<'de, C, T, L> de::Visitor<'de> for SetVisitor<C, T, L>
+where
+    C: HexConf,
+    T: Ord + SerHex<C>,
+    L: FromIterator<T>,
+{
+    type Value = L;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of unique hex strings")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<L, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        // elements are tagged with their position, so that a duplicate
+        // can be reported at the index of its second occurrence.  the
+        // size hint comes from the input, so it is not trusted too far.
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(item) = seq.next_element::<Hex<T, C>>()? {
+            items.push((item.into_inner(), items.len()));
+        }
+        items.sort();
+        if let Some(pair) = items.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            let index = pair[1].1;
+            return Err(de::Error::custom(DuplicateAt(index)));
+        }
+        Ok(items.into_iter().map(|(item, _)| item).collect())
+    }
+}
+
+// error raised when a value appears more than once in a set.
+struct DuplicateAt(usize);
+
+impl fmt::Display for DuplicateAt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "duplicate hex value at index {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexSet;
+    use config::Strict;
+    use serde::de::value::{BorrowedStrDeserializer, Error, SeqDeserializer};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn duplicates() {
+        let items = vec!["00ff", "0001", "00ff"];
+        let items = items.into_iter().map(BorrowedStrDeserializer::<Error>::new);
+        let de: SeqDeserializer<_, Error> = SeqDeserializer::new(items);
+        let err = HexSet::<Strict>::deserialize::<BTreeSet<[u8; 2]>, _, _>(de).unwrap_err();
+        assert_eq!(err.to_string(), "duplicate hex value at index 2");
+        let items = vec!["00ff", "0001"];
+        let items = items.into_iter().map(BorrowedStrDeserializer::<Error>::new);
+        let de: SeqDeserializer<_, Error> = SeqDeserializer::new(items);
+        let set: BTreeSet<[u8; 2]> = HexSet::<Strict>::deserialize(de).unwrap();
+        assert_eq!(set.len(), 2);
+    }
+}
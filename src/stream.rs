@@ -0,0 +1,1432 @@
+//! Incremental (sans-IO) hexadecimal encoding/decoding.
+//!
+//! The types in this module consume their input in arbitrarily sized chunks
+//! and never perform any i/o themselves, which makes them suitable for
+//! converting hex arriving over sockets (or any other source) without first
+//! buffering the entire string.  `encode_chunks`/`decode_chunks` drive them
+//! over caller-supplied chunk iterators (e.g. the windows of a memory-mapped
+//! file), and the `io` adapters in this module are built on top of them and
+//! require the `std` feature.  With the `futures-io` feature, the adapters
+//! have `AsyncRead`/`AsyncWrite` counterparts.
+use codec;
+use config::{HexConf, Strict};
+#[cfg(all(feature = "futures-io", feature = "std"))]
+use futures_io::{AsyncRead, AsyncWrite};
+use serde::{de, ser, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+#[cfg(feature = "std")]
+use std::io;
+use std::marker::PhantomData;
+#[cfg(all(feature = "futures-io", feature = "std"))]
+use std::pin::Pin;
+#[cfg(all(feature = "futures-io", feature = "std"))]
+use std::task::{ready, Context, Poll};
+use std::{cmp, fmt, str};
+use types::{Error, ParseHexError};
+#[cfg(feature = "constant-time")]
+use utils::fromhex_ct;
+use utils::{fromval, fromvalcaps, intohex, intohexcaps, intoval, prefix, scrub};
+
+/// tracks whether the optional `0x` prefix has been handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prefix {
+    /// no input seen yet.
+    Start,
+    /// a leading `0` was seen, which may or may not begin a prefix.
+    Zero,
+    /// prefix has been consumed (or ruled out).
+    Done,
+}
+
+/// Incremental hexadecimal decoder.
+///
+/// Hex characters are supplied in chunks via `feed`, which writes the decoded
+/// bytes to a caller-supplied buffer.  Nibble pairs (and the `0x` prefix, if
+/// the config allows one) may be split across chunk boundaries.  Once all
+/// input has been supplied, `finish` must be called to validate that the
+/// input did not end partway through a byte.
+///
+/// Since the total length of the input isn't known up front, compact
+/// (odd-length) inputs cannot be decoded incrementally; the config is only
+/// consulted for prefixing.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::StrictPfx;
+/// # use serde_hex::stream::HexDecoder;
+/// # fn main() {
+/// let mut decoder = HexDecoder::<StrictPfx>::new();
+/// let mut buf = [0u8; 4];
+/// let mut len = 0;
+/// for chunk in ["0", "xde", "adb", "eef"].iter() {
+///     len += decoder.feed(chunk.as_bytes(), &mut buf[len..]).unwrap();
+/// }
+/// decoder.finish().unwrap();
+/// assert_eq!(buf, [0xde, 0xad, 0xbe, 0xef]);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HexDecoder<C = Strict> {
+    pfx: Prefix,
+    pending: Option<u8>,
+    digits: usize,
+    offset: usize,
+    _conf: PhantomData<C>,
+}
+
+impl<C> HexDecoder<C>
+where
+    C: HexConf,
+{
+    /// Create a new decoder.
+    pub fn new() -> Self {
+        let pfx = if <C as HexConf>::acceptpfx() {
+            Prefix::Start
+        } else {
+            Prefix::Done
+        };
+        HexDecoder {
+            pfx,
+            pending: None,
+            digits: 0,
+            offset: 0,
+            _conf: PhantomData,
+        }
+    }
+
+    /// Number of hex digits consumed so far (not including the prefix).
+    pub fn digits(&self) -> usize {
+        self.digits
+    }
+
+    /// Number of input bytes consumed so far (including the prefix).  Errors
+    /// for non-hexadecimal characters report their index in these terms.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Maximum number of bytes that a call to `feed` with `len` bytes of
+    /// input may write.
+    pub fn max_decoded_len(&self, len: usize) -> usize {
+        let carry = match (self.pending, self.pfx) {
+            (Some(_), _) | (_, Prefix::Zero) => 1,
+            _ => 0,
+        };
+        (len + carry) / 2
+    }
+
+    /// Decode a chunk of hexadecimal characters, writing the decoded bytes
+    /// to the front of `dst`.  Returns the number of bytes written.
+    ///
+    /// Returns a `Buffer` error if `dst` is smaller than `max_decoded_len(src.len())`
+    /// (in which case no input is consumed), or if a non-hexadecimal character
+    /// is encountered (in which case the decoder should be discarded).
+    pub fn feed(&mut self, src: &[u8], dst: &mut [u8]) -> Result<usize, ParseHexError> {
+        let expect = self.max_decoded_len(src.len());
+        if dst.len() < expect {
+            let actual = dst.len();
+            return Err(ParseHexError::Buffer { expect, actual });
+        }
+        let mut written = 0;
+        for &c in src.iter() {
+            match self.pfx {
+                Prefix::Start => {
+                    if c == b'0' {
+                        self.pfx = Prefix::Zero;
+                        self.offset += 1;
+                        continue;
+                    }
+                    self.pfx = Prefix::Done;
+                }
+                Prefix::Zero => {
+                    self.pfx = Prefix::Done;
+                    if c == b'x' || c == b'X' {
+                        self.offset += 1;
+                        continue;
+                    }
+                    // the leading zero was a digit after all.
+                    self.pending = Some(0);
+                    self.digits += 1;
+                }
+                Prefix::Done => {}
+            }
+            let val = intoval(c).map_err(|e| e.shift(self.offset))?;
+            self.digits += 1;
+            self.offset += 1;
+            match self.pending.take() {
+                Some(high) => {
+                    dst[written] = high << 4 | val;
+                    written += 1;
+                }
+                None => self.pending = Some(val),
+            }
+        }
+        Ok(written)
+    }
+
+    /// Signal the end of input.  Returns an error if the input ended
+    /// partway through a byte.
+    pub fn finish(&self) -> Result<(), ParseHexError> {
+        if self.pfx == Prefix::Zero {
+            Err(ParseHexError::OddLength { len: 1 })
+        } else if self.pending.is_some() {
+            Err(ParseHexError::OddLength { len: self.digits })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<C> Default for HexDecoder<C>
+where
+    C: HexConf,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incremental hexadecimal encoder.
+///
+/// Bytes are supplied in chunks via `feed`, which writes their hexadecimal
+/// representation to a caller-supplied buffer.  The prefix (if any) is
+/// emitted ahead of the first chunk, and in compact mode leading zeroes are
+/// suppressed across chunk boundaries.  Once all input has been supplied,
+/// `finish` must be called to flush any remaining output (e.g. a compact
+/// representation of an all-zero input).
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::CompactPfx;
+/// # use serde_hex::stream::HexEncoder;
+/// # fn main() {
+/// let mut encoder = HexEncoder::<CompactPfx>::new();
+/// let mut buf = [0u8; 16];
+/// let mut len = 0;
+/// for chunk in [&[0x00, 0x00][..], &[0x0a], &[0xbc, 0xde]].iter() {
+///     len += encoder.feed(chunk, &mut buf[len..]).unwrap();
+/// }
+/// len += encoder.finish(&mut buf[len..]).unwrap();
+/// assert_eq!(&buf[..len], b"0xabcde");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HexEncoder<C = Strict> {
+    pfx: bool,
+    leading: bool,
+    _conf: PhantomData<C>,
+}
+
+impl<C> HexEncoder<C>
+where
+    C: HexConf,
+{
+    /// Create a new encoder.
+    pub fn new() -> Self {
+        HexEncoder {
+            pfx: <C as HexConf>::withpfx(),
+            leading: <C as HexConf>::compact(),
+            _conf: PhantomData,
+        }
+    }
+
+    /// Maximum number of bytes that a call to `feed` with `len` bytes of
+    /// input may write.
+    pub fn max_encoded_len(&self, len: usize) -> usize {
+        let pfx = if self.pfx { 2 } else { 0 };
+        pfx + len * 2
+    }
+
+    // write the prefix if it has not been written yet.
+    fn write_pfx(&mut self, dst: &mut [u8]) -> usize {
+        if self.pfx {
+            self.pfx = false;
+            dst[..2].copy_from_slice(prefix::<C>());
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Encode a chunk of bytes, writing the hexadecimal characters to the
+    /// front of `dst`.  Returns the number of bytes written.
+    ///
+    /// Returns a `Buffer` error if `dst` is smaller than `max_encoded_len(src.len())`,
+    /// in which case no input is consumed.
+    pub fn feed(&mut self, src: &[u8], dst: &mut [u8]) -> Result<usize, ParseHexError> {
+        let expect = self.max_encoded_len(src.len());
+        if dst.len() < expect {
+            let actual = dst.len();
+            return Err(ParseHexError::Buffer { expect, actual });
+        }
+        let mut written = self.write_pfx(dst);
+        let mut body = src;
+        if self.leading {
+            match src.iter().position(|v| *v > 0u8) {
+                Some(idx) => {
+                    self.leading = false;
+                    // if first non-zero byte is less than `0x10`, repr w/ one hex char.
+                    if src[idx] < 0x10 {
+                        dst[written] = if <C as HexConf>::withcap() {
+                            fromvalcaps(src[idx])
+                        } else {
+                            fromval(src[idx])
+                        };
+                        written += 1;
+                        body = &src[(idx + 1)..];
+                    } else {
+                        body = &src[idx..];
+                    }
+                }
+                None => return Ok(written),
+            }
+        }
+        let end = written + body.len() * 2;
+        if <C as HexConf>::withcap() {
+            intohexcaps(&mut dst[written..end], body);
+        } else {
+            intohex(&mut dst[written..end], body);
+        }
+        Ok(end)
+    }
+
+    /// Signal the end of input, writing any remaining output to the front
+    /// of `dst`.  At most three bytes are written (a prefix and a single
+    /// zero).  Returns the number of bytes written.
+    pub fn finish(mut self, dst: &mut [u8]) -> Result<usize, ParseHexError> {
+        let pfx = if self.pfx { 2 } else { 0 };
+        let zero = if self.leading { 1 } else { 0 };
+        if dst.len() < pfx + zero {
+            let expect = pfx + zero;
+            let actual = dst.len();
+            return Err(ParseHexError::Buffer { expect, actual });
+        }
+        let written = self.write_pfx(dst);
+        if self.leading {
+            // only zeroes were encountered, so write a single zero.
+            dst[written] = b'0';
+            Ok(written + 1)
+        } else {
+            Ok(written)
+        }
+    }
+}
+
+impl<C> Default for HexEncoder<C>
+where
+    C: HexConf,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serialize-only adapter which streams the bytes of an iterator out as hex.
+///
+/// The bytes are pulled from the iterator in small chunks while serializing,
+/// encoded by a `HexEncoder`, and handed to the serializer via `collect_str`,
+/// so the input is never collected into a buffer.  Since the iterator is
+/// consumed, an adapter can only be serialized once; later attempts fail.
+/// As for `HexEncoder`, the `width` of the config is not applied.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// # use serde_hex::CompactPfx;
+/// # use serde_hex::stream::HexIterSer;
+/// # fn main() {
+/// let bytes = (0u8..4).map(|b| b * 0x11);
+/// let ser = HexIterSer::<_, CompactPfx>::with_len(bytes, 4);
+/// assert_eq!(ser.encoded_len(), Some(10));
+/// assert_eq!(serde_json::to_string(&ser).unwrap(), r#""0x112233""#);
+/// assert!(serde_json::to_string(&ser).is_err());
+/// # }
+/// ```
+pub struct HexIterSer<I, C = Strict> {
+    iter: Cell<Option<I>>,
+    len: Option<usize>,
+    _conf: PhantomData<C>,
+}
+
+impl<I, C> HexIterSer<I, C>
+where
+    I: Iterator<Item = u8>,
+    C: HexConf,
+{
+    /// Create an adapter over the bytes of `src`.
+    pub fn new<T>(src: T) -> Self
+    where
+        T: IntoIterator<IntoIter = I, Item = u8>,
+    {
+        HexIterSer {
+            iter: Cell::new(Some(src.into_iter())),
+            len: None,
+            _conf: PhantomData,
+        }
+    }
+
+    /// Create an adapter over the bytes of `src`, which is expected to yield
+    /// `len` bytes.  The hint is only used to report `encoded_len`.
+    pub fn with_len<T>(src: T, len: usize) -> Self
+    where
+        T: IntoIterator<IntoIter = I, Item = u8>,
+    {
+        HexIterSer {
+            len: Some(len),
+            ..HexIterSer::new(src)
+        }
+    }
+
+    /// Maximum length of the encoding, if the number of bytes is known
+    /// (from the hint, or else from an exact `size_hint` of the iterator).
+    pub fn encoded_len(&self) -> Option<usize> {
+        let len = match self.len {
+            Some(len) => Some(len),
+            None => {
+                let iter = self.iter.take();
+                let len = match iter {
+                    Some(ref iter) => match iter.size_hint() {
+                        (lower, Some(upper)) if lower == upper => Some(upper),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                self.iter.set(iter);
+                len
+            }
+        };
+        let pfx = if <C as HexConf>::withpfx() { 2 } else { 0 };
+        len.map(|len| pfx + cmp::max(len * 2, 1))
+    }
+}
+
+// one-shot `Display` adapter which drains the iterator of a `HexIterSer`.
+// kept private (and only used by `serialize`), since it can't be displayed
+// a second time.
+struct IterDisplay<'a, I: 'a, C: 'a>(&'a HexIterSer<I, C>);
+
+impl<'a, I, C> fmt::Display for IterDisplay<'a, I, C>
+where
+    I: Iterator<Item = u8>,
+    C: HexConf,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut iter = self.0.iter.take().ok_or(fmt::Error)?;
+        let mut encoder = HexEncoder::<C>::new();
+        let mut chunk = [0u8; 64];
+        let mut buf = [0u8; 64 * 2 + 2];
+        loop {
+            // `chunk` comes first, so that no byte is pulled from the
+            // iterator once the chunk is full.
+            let mut len = 0;
+            for (slot, byte) in chunk.iter_mut().zip(&mut iter) {
+                *slot = byte;
+                len += 1;
+            }
+            let written = encoder
+                .feed(&chunk[..len], &mut buf)
+                .map_err(|_| fmt::Error)?;
+            f.write_str(str::from_utf8(&buf[..written]).map_err(|_| fmt::Error)?)?;
+            if len < chunk.len() {
+                break;
+            }
+        }
+        let written = encoder.finish(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(str::from_utf8(&buf[..written]).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl<I, C> Serialize for HexIterSer<I, C>
+where
+    I: Iterator<Item = u8>,
+    C: HexConf,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // checked up front, since `collect_str` can't report why `fmt` failed.
+        match self.iter.take() {
+            Some(iter) => {
+                self.iter.set(Some(iter));
+                serializer.collect_str(&IterDisplay(self))
+            }
+            None => Err(ser::Error::custom("iterator was already serialized")),
+        }
+    }
+}
+
+/// Encode a sequence of byte chunks (e.g. the windows of a memory-mapped
+/// file) as a single hex string, writing it to `dst`.
+///
+/// The chunks are encoded by one `HexEncoder` (so compact configs suppress
+/// leading zeroes across chunk boundaries) through a small stack buffer, so
+/// memory use doesn't depend on the size of the input or of its chunks.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::CompactPfx;
+/// # use serde_hex::stream::encode_chunks;
+/// # fn main() {
+/// let chunks = vec![vec![0x00, 0x0a], vec![0xbc], vec![]];
+/// let mut hex = Vec::new();
+/// encode_chunks::<CompactPfx, _, _>(&chunks, &mut hex).unwrap();
+/// assert_eq!(hex, b"0xabc");
+/// # }
+/// ```
+pub fn encode_chunks<C, I, D>(chunks: I, mut dst: D) -> Result<(), Error>
+where
+    C: HexConf,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    D: ::io::Write,
+{
+    const CHUNK: usize = 128;
+    let mut encoder = HexEncoder::<C>::new();
+    let mut hex = [0u8; CHUNK * 2 + 2];
+    let encode = || -> Result<(), Error> {
+        for chunk in chunks {
+            for part in chunk.as_ref().chunks(CHUNK) {
+                let len = encoder.feed(part, &mut hex)?;
+                dst.write_all(&hex[..len])?;
+            }
+        }
+        let len = encoder.finish(&mut hex)?;
+        dst.write_all(&hex[..len])?;
+        Ok(())
+    };
+    let rslt = encode();
+    scrub(&mut hex);
+    rslt
+}
+
+/// Decode a hex string supplied as a sequence of chunks (e.g. the windows of
+/// a memory-mapped file), writing the bytes to `dst`.
+///
+/// The chunks are decoded by one `HexDecoder`, so digit pairs and the prefix
+/// may be split across chunk boundaries, and errors report the offset of the
+/// offending character within the whole input.  As for `HexDecoder`, compact
+/// (odd-length) input is rejected.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::StrictPfx;
+/// # use serde_hex::stream::decode_chunks;
+/// # fn main() {
+/// let chunks = ["0", "xde", "adb", "eef"];
+/// let mut bytes = Vec::new();
+/// decode_chunks::<StrictPfx, _, _>(&chunks, &mut bytes).unwrap();
+/// assert_eq!(bytes, [0xde, 0xad, 0xbe, 0xef]);
+/// # }
+/// ```
+pub fn decode_chunks<C, I, D>(chunks: I, mut dst: D) -> Result<(), Error>
+where
+    C: HexConf,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    D: ::io::Write,
+{
+    const CHUNK: usize = 256;
+    let mut decoder = HexDecoder::<C>::new();
+    let mut buf = [0u8; CHUNK / 2 + 1];
+    let decode = || -> Result<(), Error> {
+        for chunk in chunks {
+            for part in chunk.as_ref().chunks(CHUNK) {
+                let len = decoder.feed(part, &mut buf)?;
+                dst.write_all(&buf[..len])?;
+            }
+        }
+        decoder.finish()?;
+        Ok(())
+    };
+    let rslt = decode();
+    scrub(&mut buf);
+    rslt
+}
+
+/// `DeserializeSeed` which decodes a hex string straight into a caller-supplied
+/// sink (any writer, or a `&mut [u8]` buffer), and produces the number of
+/// bytes written.
+///
+/// This is meant for large fields: the decoded bytes are passed to the sink
+/// in small chunks as the string is decoded, so they're never held in memory
+/// alongside the hex text.  The number of digits is checked against the
+/// config up front (as for `codec::decode_vec`, along with any `width`, which
+/// bounds the number of digits), and constant-time configs decode in
+/// constant time.  Bytes decoded before a bad digit may already have been
+/// written to the sink.
+///
+/// ```rust
+/// # extern crate serde;
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// # use serde::de::DeserializeSeed;
+/// # use serde_hex::StrictPfx;
+/// # use serde_hex::stream::HexSeed;
+/// # fn main() {
+/// let mut de = serde_json::Deserializer::from_str(r#""0xdeadbeef""#);
+/// let mut sink = Vec::new();
+/// let len = HexSeed::<_, StrictPfx>::new(&mut sink).deserialize(&mut de).unwrap();
+/// assert_eq!(len, 4);
+/// assert_eq!(sink, [0xde, 0xad, 0xbe, 0xef]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HexSeed<W, C = Strict> {
+    dst: W,
+    _conf: PhantomData<C>,
+}
+
+impl<W, C> HexSeed<W, C>
+where
+    W: ::io::Write,
+    C: HexConf,
+{
+    /// Seed which writes the decoded bytes to `dst`.
+    pub fn new(dst: W) -> Self {
+        HexSeed {
+            dst,
+            _conf: PhantomData,
+        }
+    }
+
+    // decode `src` into the sink, returning the number of bytes written.
+    fn decode(mut self, src: &[u8]) -> Result<usize, Error> {
+        let opts = C::describe();
+        let digits = codec::checked_digits(&opts, src)?;
+        match opts.width {
+            Some(width) if opts.compact && digits.len() > width => {
+                let got = digits.len();
+                return Err(ParseHexError::Range {
+                    min: 1,
+                    max: width,
+                    got,
+                }
+                .into());
+            }
+            Some(width) if !opts.compact && digits.len() != width => {
+                return Err(ParseHexError::size(width, digits.len()).into());
+            }
+            _ => {}
+        }
+        let offset = src.len() - digits.len();
+        let mut buf = [0u8; 129];
+        let rslt = match () {
+            #[cfg(feature = "constant-time")]
+            () if codec::consttime(&opts) => self.decode_ct(&mut buf, digits),
+            () => self.decode_body(&mut buf, digits, offset),
+        };
+        scrub(&mut buf);
+        rslt
+    }
+
+    // body of `decode`, on input with the prefix stripped (and which starts
+    // at `offset`), using `buf` as scratch space.
+    fn decode_body(
+        &mut self,
+        buf: &mut [u8; 129],
+        mut digits: &[u8],
+        mut offset: usize,
+    ) -> Result<usize, Error> {
+        let mut written = 0;
+        if digits.len() % 2 == 1 {
+            // the leading digit stands alone as the low nibble of the first
+            // byte.
+            let val = intoval(digits[0]).map_err(|e| e.shift(offset))?;
+            self.dst.write_all(&[val])?;
+            written += 1;
+            digits = &digits[1..];
+            offset += 1;
+        }
+        let mut decoder = HexDecoder::<Strict>::new();
+        for chunk in digits.chunks(256) {
+            let len = decoder.feed(chunk, buf).map_err(|e| e.shift(offset))?;
+            self.dst.write_all(&buf[..len])?;
+            written += len;
+        }
+        Ok(written)
+    }
+
+    // constant-time body of `decode`.  every chunk is decoded even after an
+    // error (though no more are written), so that the running time depends
+    // only on the length of the input.
+    #[cfg(feature = "constant-time")]
+    fn decode_ct(&mut self, buf: &mut [u8; 129], digits: &[u8]) -> Result<usize, Error> {
+        let mut rslt = Ok(0);
+        for chunk in digits.chunks(256) {
+            let bytes = &mut buf[..chunk.len() / 2];
+            rslt = match (rslt, fromhex_ct(bytes, chunk)) {
+                (Ok(written), Ok(())) => self.write_chunk(bytes).map(|()| written + bytes.len()),
+                (Err(err), _) => Err(err),
+                (_, Err(err)) => Err(err.into()),
+            };
+        }
+        rslt
+    }
+
+    // write decoded bytes to the sink.  the io error is only distinct from
+    // `Error` with std, so convert via `?` rather than `into`.
+    #[cfg(feature = "constant-time")]
+    fn write_chunk(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.dst.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+impl<'de, W, C> de::DeserializeSeed<'de> for HexSeed<W, C>
+where
+    W: ::io::Write,
+    C: HexConf,
+{
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<usize, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, W, C> de::Visitor<'de> for HexSeed<W, C>
+where
+    W: ::io::Write,
+    C: HexConf,
+{
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, src: &str) -> Result<usize, E> {
+        self.visit_bytes(src.as_bytes())
+    }
+
+    fn visit_bytes<E: de::Error>(self, src: &[u8]) -> Result<usize, E> {
+        self.decode(src).map_err(|e| e.into_de_error(src))
+    }
+}
+
+#[cfg(feature = "std")]
+/// Size of the stack buffer used by the `io` adapters.
+const CHUNK: usize = 256;
+
+#[cfg(feature = "std")]
+/// Adapter which accepts raw bytes via `io::Write`, and writes their
+/// hexadecimal representation to an inner writer.
+///
+/// No intermediate heap buffers are used; each write is encoded through a
+/// small stack buffer and passed directly to the inner writer.  Call `finish`
+/// once all bytes have been written to emit any trailing output (such as
+/// the prefix or compact zero of an empty input) and recover the inner writer.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::StrictPfx;
+/// # use serde_hex::stream::HexWriter;
+/// # use std::io::Write;
+/// # fn main() {
+/// let mut writer = HexWriter::<_, StrictPfx>::new(Vec::new());
+/// writer.write_all(&[0xde, 0xad]).unwrap();
+/// writer.write_all(&[0xbe, 0xef]).unwrap();
+/// let hex = writer.finish().unwrap();
+/// assert_eq!(hex, b"0xdeadbeef");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HexWriter<W, C = Strict> {
+    inner: W,
+    encoder: HexEncoder<C>,
+}
+
+#[cfg(feature = "std")]
+impl<W, C> HexWriter<W, C>
+where
+    W: io::Write,
+    C: HexConf,
+{
+    /// Wrap an inner writer.
+    pub fn new(inner: W) -> Self {
+        HexWriter {
+            inner,
+            encoder: HexEncoder::new(),
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Write any trailing output, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut buf = [0u8; 3];
+        let len = self.encoder.finish(&mut buf).map_err(into_io)?;
+        let rslt = self.inner.write_all(&buf[..len]);
+        scrub(&mut buf);
+        rslt?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W, C> io::Write for HexWriter<W, C>
+where
+    W: io::Write,
+    C: HexConf,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut hex = [0u8; CHUNK];
+        let src = &buf[..cmp::min(buf.len(), (CHUNK - 2) / 2)];
+        let len = self.encoder.feed(src, &mut hex).map_err(into_io)?;
+        let rslt = self.inner.write_all(&hex[..len]);
+        scrub(&mut hex[..len]);
+        rslt.map(|()| src.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Adapter which reads hexadecimal text from an inner reader, and yields
+/// the decoded bytes via `io::Read`.
+///
+/// Invalid input is reported as an `io::Error` of kind `InvalidData`, wrapping
+/// a `ParseHexError` whose index is the offset of the offending character
+/// within the stream.
+/// Input which ends partway through a byte is reported the same way once
+/// the inner reader is exhausted.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::StrictPfx;
+/// # use serde_hex::stream::HexReader;
+/// # use std::io::Read;
+/// # fn main() {
+/// let mut reader = HexReader::<_, StrictPfx>::new(&b"0xdeadbeef"[..]);
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf).unwrap();
+/// assert_eq!(buf, [0xde, 0xad, 0xbe, 0xef]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HexReader<R, C = Strict> {
+    inner: R,
+    decoder: HexDecoder<C>,
+}
+
+#[cfg(feature = "std")]
+impl<R, C> HexReader<R, C>
+where
+    R: io::Read,
+    C: HexConf,
+{
+    /// Wrap an inner reader.
+    pub fn new(inner: R) -> Self {
+        HexReader {
+            inner,
+            decoder: HexDecoder::new(),
+        }
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap this adapter, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // body of `read`, using `hex` as scratch space.
+    fn read_with(&mut self, hex: &mut [u8; CHUNK], buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            // never read more hex than will fit into `buf` once decoded.
+            let carry = self.decoder.max_decoded_len(1);
+            let want = cmp::min(CHUNK, buf.len() * 2 - carry);
+            let got = self.inner.read(&mut hex[..want])?;
+            if got == 0 {
+                self.decoder.finish().map_err(into_io)?;
+                return Ok(0);
+            }
+            let len = self.decoder.feed(&hex[..got], buf).map_err(into_io)?;
+            // reads which only cover a prefix or a single nibble don't yield
+            // any bytes, and must not be mistaken for the end of input.
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R, C> io::Read for HexReader<R, C>
+where
+    R: io::Read,
+    C: HexConf,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut hex = [0u8; CHUNK];
+        let rslt = self.read_with(&mut hex, buf);
+        scrub(&mut hex);
+        rslt
+    }
+}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+/// Asynchronous counterpart of `HexWriter`, which accepts raw bytes via
+/// `AsyncWrite` and writes their hexadecimal representation to an inner
+/// writer.
+///
+/// Each write is encoded into a small internal buffer, which is drained to
+/// the inner writer before more input is accepted.  Closing the adapter
+/// writes any trailing output (as `HexWriter::finish` does) before closing
+/// the inner writer; writes after that fail.
+#[derive(Debug)]
+pub struct AsyncHexWriter<W, C = Strict> {
+    inner: W,
+    encoder: Option<HexEncoder<C>>,
+    buf: [u8; CHUNK],
+    pos: usize,
+    len: usize,
+}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+impl<W, C> AsyncHexWriter<W, C>
+where
+    W: AsyncWrite + Unpin,
+    C: HexConf,
+{
+    /// Wrap an inner writer.
+    pub fn new(inner: W) -> Self {
+        AsyncHexWriter {
+            inner,
+            encoder: Some(HexEncoder::new()),
+            buf: [0u8; CHUNK],
+            pos: 0,
+            len: 0,
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap this adapter, returning the inner writer.  Any output which
+    /// hasn't been flushed is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    // write out the contents of the internal buffer.
+    fn poll_drain(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        while self.pos < self.len {
+            let pending = &self.buf[self.pos..self.len];
+            let n = ready!(Pin::new(&mut self.inner).poll_write(cx, pending))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pos += n;
+        }
+        scrub(&mut self.buf[..self.len]);
+        self.pos = 0;
+        self.len = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+impl<W: Unpin, C> Unpin for AsyncHexWriter<W, C> {}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+impl<W, C> AsyncWrite for AsyncHexWriter<W, C>
+where
+    W: AsyncWrite + Unpin,
+    C: HexConf,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        let encoder = match this.encoder {
+            Some(ref mut encoder) => encoder,
+            None => return Poll::Ready(Err(io::Error::other("hex writer is closed"))),
+        };
+        let src = &buf[..cmp::min(buf.len(), (CHUNK - 2) / 2)];
+        this.len = encoder.feed(src, &mut this.buf).map_err(into_io)?;
+        Poll::Ready(Ok(src.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        if let Some(encoder) = this.encoder.take() {
+            this.len = encoder.finish(&mut this.buf).map_err(into_io)?;
+            ready!(this.poll_drain(cx))?;
+        }
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+/// Asynchronous counterpart of `HexReader`, which reads hexadecimal text
+/// from an inner reader and yields the decoded bytes via `AsyncRead`.
+///
+/// Errors are reported as for `HexReader`.
+#[derive(Debug)]
+pub struct AsyncHexReader<R, C = Strict> {
+    inner: R,
+    decoder: HexDecoder<C>,
+}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+impl<R, C> AsyncHexReader<R, C>
+where
+    R: AsyncRead + Unpin,
+    C: HexConf,
+{
+    /// Wrap an inner reader.
+    pub fn new(inner: R) -> Self {
+        AsyncHexReader {
+            inner,
+            decoder: HexDecoder::new(),
+        }
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap this adapter, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // body of `poll_read`, using `hex` as scratch space.
+    fn poll_read_with(
+        &mut self,
+        cx: &mut Context,
+        hex: &mut [u8; CHUNK],
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            // as in `HexReader`, never read more hex than will fit into
+            // `buf` once decoded.
+            let carry = self.decoder.max_decoded_len(1);
+            let want = cmp::min(CHUNK, buf.len() * 2 - carry);
+            let got = ready!(Pin::new(&mut self.inner).poll_read(cx, &mut hex[..want]))?;
+            if got == 0 {
+                self.decoder.finish().map_err(into_io)?;
+                return Poll::Ready(Ok(0));
+            }
+            let len = self.decoder.feed(&hex[..got], buf).map_err(into_io)?;
+            if len > 0 {
+                return Poll::Ready(Ok(len));
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+impl<R: Unpin, C> Unpin for AsyncHexReader<R, C> {}
+
+#[cfg(all(feature = "futures-io", feature = "std"))]
+impl<R, C> AsyncRead for AsyncHexReader<R, C>
+where
+    R: AsyncRead + Unpin,
+    C: HexConf,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let mut hex = [0u8; CHUNK];
+        let rslt = self.get_mut().poll_read_with(cx, &mut hex, buf);
+        scrub(&mut hex);
+        rslt
+    }
+}
+
+#[cfg(feature = "std")]
+// convert a parsing error into an `io::Error` for use by the `io` adapters.
+fn into_io(err: ParseHexError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HexDecoder, HexEncoder};
+    use {Compact, CompactCapPfx, SerHex, Strict, StrictCap, StrictPfx, Width};
+
+    fn decode_chunks<C: ::HexConf>(chunks: &[&str]) -> Result<Vec<u8>, ::ParseHexError> {
+        let mut decoder = HexDecoder::<C>::new();
+        let mut out = Vec::new();
+        for chunk in chunks.iter() {
+            let mut buf = vec![0u8; decoder.max_decoded_len(chunk.len())];
+            let len = decoder.feed(chunk.as_bytes(), &mut buf)?;
+            out.extend_from_slice(&buf[..len]);
+        }
+        decoder.finish()?;
+        Ok(out)
+    }
+
+    #[test]
+    fn split_chunks() {
+        let exp = vec![0x01, 0x23, 0xab, 0xcd];
+        let splits: [&[&str]; 4] = [
+            &["0123abcd"],
+            &["0", "123a", "bcd"],
+            &["012", "", "3ab", "c", "d"],
+            &["0", "1", "2", "3", "a", "b", "c", "d"],
+        ];
+        for chunks in splits.iter() {
+            assert_eq!(decode_chunks::<Strict>(chunks).unwrap(), exp);
+            assert_eq!(decode_chunks::<StrictPfx>(chunks).unwrap(), exp);
+        }
+        assert_eq!(
+            decode_chunks::<StrictPfx>(&["0", "x0", "123abcd"]).unwrap(),
+            exp
+        );
+        assert_eq!(
+            decode_chunks::<StrictPfx>(&["0x"]).unwrap(),
+            Vec::<u8>::new()
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(decode_chunks::<Strict>(&["0x01"]).is_err());
+        assert!(decode_chunks::<Strict>(&["01", "2"]).is_err());
+        assert!(decode_chunks::<StrictPfx>(&["0"]).is_err());
+        assert!(decode_chunks::<StrictPfx>(&["0x", "0"]).is_err());
+        assert!(decode_chunks::<Strict>(&["01", "zz"]).is_err());
+        let mut decoder = HexDecoder::<Strict>::new();
+        assert!(decoder.feed(b"0123", &mut [0u8; 1]).is_err());
+        assert_eq!(decoder.digits(), 0);
+    }
+
+    fn encode_chunks<C: ::HexConf>(chunks: &[&[u8]]) -> String {
+        let mut encoder = HexEncoder::<C>::new();
+        let mut out = Vec::new();
+        for chunk in chunks.iter() {
+            let mut buf = vec![0u8; encoder.max_encoded_len(chunk.len())];
+            let len = encoder.feed(chunk, &mut buf).unwrap();
+            out.extend_from_slice(&buf[..len]);
+        }
+        let mut buf = [0u8; 3];
+        let len = encoder.finish(&mut buf).unwrap();
+        out.extend_from_slice(&buf[..len]);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn encode_split_chunks() {
+        let val = [0x00, 0x00, 0x0a, 0xbc, 0x00, 0xde];
+        let splits: [&[&[u8]]; 4] = [
+            &[&val],
+            &[&val[..1], &val[1..]],
+            &[&val[..2], &[], &val[2..3], &val[3..]],
+            &[&val[..3], &val[3..5], &val[5..]],
+        ];
+        for chunks in splits.iter() {
+            assert_eq!(
+                encode_chunks::<Strict>(chunks),
+                <[u8; 6] as SerHex<Strict>>::into_hex(&val).unwrap()
+            );
+            assert_eq!(
+                encode_chunks::<StrictCap>(chunks),
+                <[u8; 6] as SerHex<StrictCap>>::into_hex(&val).unwrap()
+            );
+            assert_eq!(encode_chunks::<Compact>(chunks), "abc00de");
+            assert_eq!(encode_chunks::<CompactCapPfx>(chunks), "0xABC00DE");
+        }
+        assert_eq!(encode_chunks::<CompactCapPfx>(&[&[0, 0], &[0]]), "0x0");
+        assert_eq!(encode_chunks::<CompactCapPfx>(&[]), "0x0");
+        assert_eq!(encode_chunks::<StrictPfx>(&[]), "0x");
+    }
+
+    #[test]
+    fn iter_ser() {
+        use super::{HexIterSer, IterDisplay};
+        let val: Vec<u8> = (0..=255u8).collect();
+        let ser = HexIterSer::<_, StrictCap>::new(val.iter().cloned());
+        assert_eq!(ser.encoded_len(), Some(512));
+        let hex: String = val.iter().map(|b| format!("{:02X}", b)).collect();
+        assert_eq!(IterDisplay(&ser).to_string(), hex);
+        let ser = HexIterSer::<_, Compact>::new((0..100).map(|_| 0u8));
+        assert_eq!(IterDisplay(&ser).to_string(), "0");
+        let ser = HexIterSer::<_, StrictPfx>::new((0..3u8).filter(|b| b % 2 == 0));
+        assert_eq!(ser.encoded_len(), None);
+        assert_eq!(IterDisplay(&ser).to_string(), "0x0002");
+    }
+
+    #[test]
+    fn chunk_iters() {
+        use types::{Error, ParseHexError};
+        let val: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let chunks: Vec<&[u8]> = val.chunks(333).collect();
+        let mut hex = Vec::new();
+        super::encode_chunks::<StrictPfx, _, _>(&chunks, &mut hex).unwrap();
+        assert_eq!(hex.len(), 2 + val.len() * 2);
+        let windows: Vec<&[u8]> = hex.chunks(301).collect();
+        let mut out = Vec::new();
+        super::decode_chunks::<StrictPfx, _, _>(&windows, &mut out).unwrap();
+        assert_eq!(out, val);
+        let mut hex = Vec::new();
+        super::encode_chunks::<Compact, _, _>(&[[0u8; 300], [1u8; 300]], &mut hex).unwrap();
+        assert_eq!(hex.len(), 599);
+        let mut out = Vec::new();
+        match super::decode_chunks::<Strict, _, _>(&["0011", "22x3"], &mut out) {
+            Err(Error::Parsing(ParseHexError::Char { val: 'x', index: 6 })) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let rslt = super::decode_chunks::<Strict, _, _>(&["001", "12"], &mut out);
+        assert!(rslt.is_err());
+    }
+
+    #[cfg(feature = "futures-io")]
+    #[test]
+    fn async_adapters() {
+        use super::{AsyncHexReader, AsyncHexWriter};
+        use futures_io::{AsyncRead, AsyncWrite};
+        use std::io;
+        use std::pin::Pin;
+        use std::task::{Context, Poll, Waker};
+
+        // writer which accepts one byte per call, and is pending on every
+        // other call.
+        struct Trickle(Vec<u8>, bool);
+
+        impl AsyncWrite for Trickle {
+            fn poll_write(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                self.1 = !self.1;
+                if self.1 {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                self.0.push(buf[0]);
+                Poll::Ready(Ok(1))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let val: Vec<u8> = (0..=255u8).collect();
+        let mut writer = AsyncHexWriter::<_, StrictPfx>::new(Trickle(Vec::new(), false));
+        let mut rest = &val[..];
+        while !rest.is_empty() {
+            if let Poll::Ready(n) = Pin::new(&mut writer).poll_write(&mut cx, rest) {
+                rest = &rest[n.unwrap()..];
+            }
+        }
+        while Pin::new(&mut writer).poll_close(&mut cx).is_pending() {}
+        match Pin::new(&mut writer).poll_write(&mut cx, &[0]) {
+            Poll::Ready(Err(_)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let hex = writer.into_inner().0;
+        assert_eq!(hex.len(), 2 + val.len() * 2);
+        assert!(hex.starts_with(b"0x000102"));
+
+        let mut reader = AsyncHexReader::<_, StrictPfx>::new(&hex[..]);
+        let (mut out, mut buf) = (Vec::new(), [0u8; 7]);
+        loop {
+            match Pin::new(&mut reader).poll_read(&mut cx, &mut buf) {
+                Poll::Ready(Ok(0)) => break,
+                Poll::Ready(Ok(n)) => out.extend_from_slice(&buf[..n]),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+        assert_eq!(out, val);
+        let mut reader = AsyncHexReader::<_, Strict>::new(&b"abc"[..]);
+        match Pin::new(&mut reader).poll_read(&mut cx, &mut buf) {
+            Poll::Ready(Ok(1)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let rslt = Pin::new(&mut reader).poll_read(&mut cx, &mut buf);
+        assert_eq!(
+            rslt.map_err(|e| e.kind()),
+            Poll::Ready(Err(io::ErrorKind::InvalidData))
+        );
+    }
+
+    #[test]
+    fn seed() {
+        use super::HexSeed;
+        use serde::de::value::{BorrowedStrDeserializer, Error};
+        use serde::de::DeserializeSeed;
+        let hex = format!("0x{}", "ab".repeat(1000));
+        let mut sink = Vec::new();
+        let de = BorrowedStrDeserializer::<Error>::new(&hex);
+        let len = HexSeed::<_, StrictPfx>::new(&mut sink)
+            .deserialize(de)
+            .unwrap();
+        assert_eq!(len, 1000);
+        assert!(sink.iter().all(|b| *b == 0xab));
+        let mut buf = [0u8; 2];
+        let de = BorrowedStrDeserializer::<Error>::new("0xabc");
+        let len = HexSeed::<_, CompactCapPfx>::new(&mut buf[..])
+            .deserialize(de)
+            .unwrap();
+        assert_eq!((len, buf), (2, [0x0a, 0xbc]));
+        let de = BorrowedStrDeserializer::<Error>::new("abcdef");
+        assert!(HexSeed::<_, Strict>::new(&mut buf[..])
+            .deserialize(de)
+            .is_err());
+        let de = BorrowedStrDeserializer::<Error>::new("abc");
+        assert!(HexSeed::<_, Strict>::new(Vec::new())
+            .deserialize(de)
+            .is_err());
+        let de = BorrowedStrDeserializer::<Error>::new("0x0g");
+        let err = HexSeed::<_, StrictPfx>::new(Vec::new())
+            .deserialize(de)
+            .unwrap_err();
+        assert!(err.to_string().ends_with("at index 3"));
+        // the number of digits is checked as for `codec::decode_vec`.
+        let de = BorrowedStrDeserializer::<Error>::new("");
+        assert!(HexSeed::<_, Compact>::new(Vec::new())
+            .deserialize(de)
+            .is_err());
+        let de = BorrowedStrDeserializer::<Error>::new("abcd");
+        assert!(HexSeed::<_, Width<6>>::new(Vec::new())
+            .deserialize(de)
+            .is_err());
+        let de = BorrowedStrDeserializer::<Error>::new("abcd");
+        assert!(HexSeed::<_, Width<3, Compact>>::new(Vec::new())
+            .deserialize(de)
+            .is_err());
+        let mut sink = Vec::new();
+        let de = BorrowedStrDeserializer::<Error>::new("abcdef");
+        let len = HexSeed::<_, Width<6>>::new(&mut sink)
+            .deserialize(de)
+            .unwrap();
+        assert_eq!((len, sink), (3, vec![0xab, 0xcd, 0xef]));
+    }
+
+    #[cfg(feature = "constant-time")]
+    #[test]
+    fn seed_consttime() {
+        use super::HexSeed;
+        use serde::de::value::{BorrowedStrDeserializer, Error};
+        use serde::de::DeserializeSeed;
+        use StrictCtPfx;
+        let hex = format!("0x{}", "ab".repeat(300));
+        let mut sink = Vec::new();
+        let de = BorrowedStrDeserializer::<Error>::new(&hex);
+        let len = HexSeed::<_, StrictCtPfx>::new(&mut sink)
+            .deserialize(de)
+            .unwrap();
+        assert_eq!(len, 300);
+        assert!(sink.iter().all(|b| *b == 0xab));
+        let hex = format!("0x{}g{}", "ab".repeat(200), "a".repeat(199));
+        let de = BorrowedStrDeserializer::<Error>::new(&hex);
+        let mut sink = Vec::new();
+        assert!(HexSeed::<_, StrictCtPfx>::new(&mut sink)
+            .deserialize(de)
+            .is_err());
+        assert_eq!(sink.len(), 128);
+        let de = BorrowedStrDeserializer::<Error>::new("0xabc");
+        assert!(HexSeed::<_, StrictCtPfx>::new(Vec::new())
+            .deserialize(de)
+            .is_err());
+    }
+
+    #[test]
+    fn writer() {
+        use super::HexWriter;
+        use std::io::Write;
+        let val: Vec<u8> = (0..=255u8).chain(0..=255u8).collect();
+        let mut writer = HexWriter::<_, StrictPfx>::new(Vec::new());
+        writer.write_all(&val).unwrap();
+        let hex = writer.finish().unwrap();
+        assert_eq!(hex.len(), 2 + val.len() * 2);
+        assert!(hex.starts_with(b"0x00010203"));
+        assert!(hex.ends_with(b"fdfeff"));
+        let mut writer = HexWriter::<_, Compact>::new(Vec::new());
+        writer.write_all(&[0, 0]).unwrap();
+        assert!(writer.get_ref().is_empty());
+        assert_eq!(writer.finish().unwrap(), b"0");
+    }
+
+    #[test]
+    fn reader() {
+        use super::HexReader;
+        use std::io::Read;
+        let val: Vec<u8> = (0..=255u8).chain(0..=255u8).collect();
+        let hex = format!(
+            "0x{}",
+            <[u8; 32] as SerHex<Strict>>::into_hex(&[0xab; 32]).unwrap()
+        );
+        let mut reader = HexReader::<_, StrictPfx>::new(hex.as_bytes());
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, vec![0xab; 32]);
+        let hex: Vec<u8> = val
+            .iter()
+            .flat_map(|b| format!("{:02x}", b).into_bytes())
+            .collect();
+        // single-byte reads exercise nibbles split across calls.
+        let mut reader = HexReader::<_, Strict>::new(&hex[..]);
+        let mut buf = Vec::new();
+        let mut byte = [0u8; 1];
+        while reader.read(&mut byte).unwrap() > 0 {
+            buf.push(byte[0]);
+        }
+        assert_eq!(buf, val);
+        let mut reader = HexReader::<_, StrictPfx>::new(&b"0xabcdzz"[..]);
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("index `6`"));
+        let mut reader = HexReader::<_, Strict>::new(&b"abc"[..]);
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    }
+}
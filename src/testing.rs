@@ -0,0 +1,173 @@
+//! Conformance checks for `SerHex` implementations.
+//!
+//! Crates which implement `SerHex` by hand can use these to check that
+//! their impls follow the same conventions as the ones provided here:
+//! values survive a round-trip (both via `from_hex` and via serde), the
+//! output is in the canonical form for the config, and malformed input is
+//! rejected.  `assert_serhex_roundtrip!` runs all of the checks over a list
+//! of cases.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_hex;
+//! # use serde_hex::{CompactPfx, StrictCap, StrictPfx};
+//! # fn main() {
+//! assert_serhex_roundtrip!([u8; 2], StrictPfx, [0x00, 0x00], [0xab, 0xcd]);
+//! assert_serhex_roundtrip!([u8; 2], StrictCap, [0xab, 0xcd]);
+//! assert_serhex_roundtrip!(u64, CompactPfx, 0, 1, 0xabc, u64::max_value());
+//! # }
+//! ```
+use alloc::string::String;
+use alloc::{format, vec};
+use config::HexConf;
+use serde::de::value::{BorrowedStrDeserializer, Error};
+use std::fmt::Debug;
+use SerHex;
+
+/// Run every check over each case, panicking on the first failure.
+///
+/// Takes the type under test, the config, and one or more values of that
+/// type.  Equivalent to calling `assert_roundtrip` and `assert_rejects` on
+/// each value.
+#[macro_export]
+macro_rules! assert_serhex_roundtrip {
+    ($type: ty, $conf: ty, $($case: expr),+ $(,)*) => {{
+        $(
+            let case: $type = $case;
+            $crate::testing::assert_roundtrip::<$conf, $type>(&case);
+            $crate::testing::assert_rejects::<$conf, $type>(&case);
+        )+
+    }};
+}
+
+/// Assert that `value` encodes to canonical hex under config `C`, that the
+/// encoding decodes back to `value` (via both `from_hex` and `deserialize`),
+/// and that re-encoding the result yields the same string.
+pub fn assert_roundtrip<C, T>(value: &T)
+where
+    C: HexConf,
+    T: SerHex<C> + PartialEq + Debug,
+{
+    let hex = value
+        .into_hex()
+        .unwrap_or_else(|e| panic!("failed to encode {:?}: {}", value, e));
+    assert_canonical::<C>(&hex);
+    let decoded = T::from_hex(&hex).unwrap_or_else(|e| panic!("failed to decode {:?}: {}", hex, e));
+    assert_eq!(&decoded, value, "round-trip of {:?} via from_hex", hex);
+    let de = BorrowedStrDeserializer::<Error>::new(&hex);
+    let deserialized = <T as SerHex<C>>::deserialize(de)
+        .unwrap_or_else(|e| panic!("failed to deserialize {:?}: {}", hex, e));
+    assert_eq!(&deserialized, value, "round-trip of {:?} via serde", hex);
+    let again = decoded
+        .into_hex()
+        .unwrap_or_else(|e| panic!("failed to re-encode {:?}: {}", decoded, e));
+    assert_eq!(again, hex, "re-encoding of {:?}", value);
+}
+
+/// Assert that `hex` is in the canonical form for config `C`.
+///
+/// The prefix (`0X` with `C::pfxcap()`, and `0x` otherwise) must be present
+/// exactly when `C::withpfx()`, digits must all be of the case selected by
+/// `C::withcap()`, and encodings under a fixed `C::width()` must have
+/// exactly that many digits.  Otherwise, strict encodings must have an even
+/// number of digits, and compact encodings must have no leading zeroes
+/// (other than a lone `0`).
+pub fn assert_canonical<C: HexConf>(hex: &str) {
+    let pfx = C::describe().prefix();
+    let digits = match (C::withpfx(), hex.starts_with("0x") || hex.starts_with("0X")) {
+        (true, true) if hex.starts_with(pfx) => &hex[2..],
+        (true, _) => panic!("missing `{}` prefix in {:?}", pfx, hex),
+        (false, false) => hex,
+        (false, true) => panic!("unexpected `{}` prefix in {:?}", &hex[..2], hex),
+    };
+    for (idx, chr) in digits.char_indices() {
+        let ok = match chr {
+            '0'..='9' => true,
+            'a'..='f' => !C::withcap(),
+            'A'..='F' => C::withcap(),
+            _ => false,
+        };
+        assert!(
+            ok,
+            "non-canonical digit {:?} at index {} in {:?}",
+            chr, idx, hex
+        );
+    }
+    if let Some(width) = C::width() {
+        let exact = digits.len() == width;
+        assert!(exact, "expected {} digits in {:?}", width, hex);
+    } else if C::compact() {
+        let padded = digits.len() > 1 && digits.starts_with('0');
+        assert!(!padded, "leading zero in compact encoding {:?}", hex);
+    } else {
+        let even = digits.len() % 2 == 0;
+        assert!(even, "odd number of digits in strict encoding {:?}", hex);
+    }
+}
+
+/// Assert that malformed variants of the encoding of `value` are rejected
+/// under config `C`.
+///
+/// The variants are: the last digit replaced with a non-hex character, the
+/// prefix doubled (or, if none is expected, added twice), and for strict
+/// configs without `padodd`, the last digit dropped.
+pub fn assert_rejects<C, T>(value: &T)
+where
+    C: HexConf,
+    T: SerHex<C> + Debug,
+{
+    let hex = value
+        .into_hex()
+        .unwrap_or_else(|e| panic!("failed to encode {:?}: {}", value, e));
+    let pfx = if C::withpfx() { 2 } else { 0 };
+    let doubled = if C::withpfx() {
+        format!("0x{}", hex)
+    } else {
+        format!("0x0x{}", hex)
+    };
+    let mut cases = vec![doubled];
+    if hex.len() > pfx {
+        cases.push(format!("{}g", &hex[..hex.len() - 1]));
+        if !C::compact() && !C::padodd() {
+            cases.push(String::from(&hex[..hex.len() - 1]));
+        }
+    }
+    for case in cases {
+        if let Ok(rslt) = T::from_hex(&case) {
+            panic!("accepted malformed {:?} as {:?}", case, rslt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assert_canonical;
+    use config::{Compact, CompactPfx, Strict, StrictCap, StrictCapPfx, Width};
+
+    #[test]
+    fn canonical() {
+        assert_canonical::<StrictCapPfx>("0xABCD");
+        assert_canonical::<Compact>("0");
+        assert_serhex_roundtrip!(u16, Compact, 0, 0xf, 0xffff);
+        assert_canonical::<Width<6, CompactPfx>>("0x000abc");
+        assert_serhex_roundtrip!(u16, Width<6, CompactPfx>, 0, 0xabc);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 6 digits")]
+    fn wrong_width() {
+        assert_canonical::<Width<6, CompactPfx>>("0xabc");
+    }
+
+    #[test]
+    #[should_panic(expected = "non-canonical digit 'a' at index 0")]
+    fn wrong_case() {
+        assert_canonical::<StrictCap>("abcd");
+    }
+
+    #[test]
+    #[should_panic(expected = "odd number of digits")]
+    fn odd_length() {
+        assert_canonical::<Strict>("abc");
+    }
+}
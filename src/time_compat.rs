@@ -0,0 +1,207 @@
+//! `with`-targets for timestamps carried as hex epoch values.
+//!
+//! Each crate is supported behind a feature of the same name:
+//!
+//! - `chrono`: `DateTime<Utc>`.
+//! - `time`: `OffsetDateTime` (parsed with a UTC offset).
+//!
+//! `HexSecs<C>` writes the whole seconds since the unix epoch (dropping any
+//! fraction), and `HexNanos<C>` the nanoseconds, as an unsigned integer under
+//! config `C` (which is normally one of the compact configs).  Times before
+//! the epoch, and nanosecond values which don't fit a `u64` (after 2554),
+//! can't be serialized.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_hex;
+//! # extern crate serde_json;
+//! # #[cfg(feature = "chrono")]
+//! # extern crate chrono;
+//! # #[cfg(feature = "chrono")]
+//! # fn main() {
+//! # use serde_hex::time_compat::{HexNanos, HexSecs};
+//! # use serde_hex::{Compact, CompactPfx};
+//! use chrono::{DateTime, Utc};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Block {
+//!     #[serde(with = "HexSecs::<CompactPfx>")]
+//!     mined: DateTime<Utc>,
+//!     #[serde(with = "HexNanos::<Compact>")]
+//!     seen: DateTime<Utc>,
+//! }
+//!
+//! let json = r#"{"mined":"0x5f5e1000","seen":"16345785d8a0000"}"#;
+//! let block: Block = serde_json::from_str(json).unwrap();
+//! assert_eq!(block.mined.timestamp(), 1_600_000_000);
+//! assert_eq!(block.seen.timestamp(), 100_000_000);
+//! assert_eq!(serde_json::to_string(&block).unwrap(), json);
+//! # }
+//! # #[cfg(not(feature = "chrono"))]
+//! # fn main() {}
+//! ```
+use config::{Compact, HexConf};
+use serde::{de, ser, Deserializer, Serializer};
+use std::marker::PhantomData;
+use SerHex;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// A point in time which can be carried as an offset from the unix epoch.
+pub trait EpochTime: Sized {
+    /// whole seconds since the epoch, or `None` if before it.
+    fn epoch_secs(&self) -> Option<u64>;
+
+    /// nanoseconds since the epoch, or `None` if before it or too far after
+    /// it to fit.
+    fn epoch_nanos(&self) -> Option<u64>;
+
+    /// the time `nanos` nanoseconds after the epoch, or `None` if out of
+    /// range for this type.
+    fn from_epoch_nanos(nanos: u64) -> Option<Self>;
+
+    /// the time `secs` seconds after the epoch, or `None` if out of range
+    /// for this type.
+    fn from_epoch_secs(secs: u64) -> Option<Self>;
+}
+
+/// `with`-target which (de)serializes a timestamp as its seconds since the
+/// unix epoch, in hex under config `C`.
+pub struct HexSecs<C = Compact>(PhantomData<C>);
+
+impl<C: HexConf> HexSecs<C> {
+    /// serialize the whole seconds since the epoch of `time`.
+    pub fn serialize<T, S>(time: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: EpochTime,
+        S: Serializer,
+    {
+        match time.epoch_secs() {
+            Some(secs) => <u64 as SerHex<C>>::serialize(&secs, serializer),
+            None => Err(ser::Error::custom("timestamp is before the unix epoch")),
+        }
+    }
+
+    /// deserialize a timestamp from its seconds since the epoch.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: EpochTime,
+        D: Deserializer<'de>,
+    {
+        let secs = <u64 as SerHex<C>>::deserialize(deserializer)?;
+        T::from_epoch_secs(secs).ok_or_else(|| de::Error::custom("hex timestamp out of range"))
+    }
+}
+
+/// `with`-target which (de)serializes a timestamp as its nanoseconds since
+/// the unix epoch, in hex under config `C`.
+pub struct HexNanos<C = Compact>(PhantomData<C>);
+
+impl<C: HexConf> HexNanos<C> {
+    /// serialize the nanoseconds since the epoch of `time`.
+    pub fn serialize<T, S>(time: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: EpochTime,
+        S: Serializer,
+    {
+        match time.epoch_nanos() {
+            Some(nanos) => <u64 as SerHex<C>>::serialize(&nanos, serializer),
+            None => Err(ser::Error::custom(
+                "timestamp is not representable in nanoseconds since the unix epoch",
+            )),
+        }
+    }
+
+    /// deserialize a timestamp from its nanoseconds since the epoch.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: EpochTime,
+        D: Deserializer<'de>,
+    {
+        let nanos = <u64 as SerHex<C>>::deserialize(deserializer)?;
+        T::from_epoch_nanos(nanos).ok_or_else(|| de::Error::custom("hex timestamp out of range"))
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use super::{EpochTime, NANOS_PER_SEC};
+    use chrono::{DateTime, Utc};
+    use std::convert::TryFrom;
+
+    impl EpochTime for DateTime<Utc> {
+        fn epoch_secs(&self) -> Option<u64> {
+            u64::try_from(self.timestamp()).ok()
+        }
+
+        fn epoch_nanos(&self) -> Option<u64> {
+            let secs = self.epoch_secs()?;
+            let nanos = u64::from(self.timestamp_subsec_nanos());
+            secs.checked_mul(NANOS_PER_SEC)?.checked_add(nanos)
+        }
+
+        fn from_epoch_nanos(nanos: u64) -> Option<Self> {
+            let secs = i64::try_from(nanos / NANOS_PER_SEC).ok()?;
+            DateTime::from_timestamp(secs, (nanos % NANOS_PER_SEC) as u32)
+        }
+
+        fn from_epoch_secs(secs: u64) -> Option<Self> {
+            DateTime::from_timestamp(i64::try_from(secs).ok()?, 0)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use super::EpochTime;
+    use std::convert::TryFrom;
+    use time::OffsetDateTime;
+
+    impl EpochTime for OffsetDateTime {
+        fn epoch_secs(&self) -> Option<u64> {
+            u64::try_from(self.unix_timestamp()).ok()
+        }
+
+        fn epoch_nanos(&self) -> Option<u64> {
+            u64::try_from(self.unix_timestamp_nanos()).ok()
+        }
+
+        fn from_epoch_nanos(nanos: u64) -> Option<Self> {
+            OffsetDateTime::from_unix_timestamp_nanos(i128::from(nanos)).ok()
+        }
+
+        fn from_epoch_secs(secs: u64) -> Option<Self> {
+            OffsetDateTime::from_unix_timestamp(i64::try_from(secs).ok()?).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EpochTime;
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_epoch() {
+        use chrono::{DateTime, Utc};
+        let time = <DateTime<Utc>>::from_epoch_nanos(1_600_000_000_123_456_789).unwrap();
+        assert_eq!(time.epoch_secs(), Some(1_600_000_000));
+        assert_eq!(time.epoch_nanos(), Some(1_600_000_000_123_456_789));
+        let early = DateTime::from_timestamp(-1, 0).unwrap();
+        assert_eq!(early.epoch_secs(), None);
+        assert!(<DateTime<Utc>>::from_epoch_secs(u64::MAX).is_none());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_epoch() {
+        use time::OffsetDateTime;
+        let time = OffsetDateTime::from_epoch_nanos(1_600_000_000_123_456_789).unwrap();
+        assert_eq!(time.epoch_secs(), Some(1_600_000_000));
+        assert_eq!(time.epoch_nanos(), Some(1_600_000_000_123_456_789));
+        let early = OffsetDateTime::from_unix_timestamp(-1).unwrap();
+        assert_eq!(early.epoch_nanos(), None);
+        assert!(OffsetDateTime::from_epoch_secs(u64::MAX).is_none());
+    }
+}
@@ -1,12 +1,26 @@
 //! Miscellaneous type used by this crate.
-use std::{error, fmt, io, result};
+//!
+//! Neither error depends on `std`.  The top-level `Error` only carries
+//! `io::Error` values when the `std` feature is enabled; otherwise write
+//! failures (raised by the stand-in `io::Write` trait) are reported as the
+//! opaque `Error::Write`.
+use serde::de;
+#[cfg(feature = "std")]
+use std::io;
+use std::{error, fmt, result, str};
 
 /// An alias of `std::result::Result` with this crate's
 /// `Error` type inserted by default.
 pub type Result<T> = result::Result<T, Error>;
 
-/// error raised during hexadecimal parsing operations
+/// error raised during hexadecimal parsing operations.
+///
+/// all sizes are measured in hexadecimal characters (not including any
+/// prefix), except for `Buffer`, which is measured in bytes.  `Size` is
+/// raised when an exact size was expected, and `Range` when the size
+/// was bounded (e.g. by the maximum width of a compact value).
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ParseHexError {
     /// hexadecimal buffer was outside allowed range
     Range {
@@ -17,18 +31,172 @@ pub enum ParseHexError {
         /// size the was found
         got: usize,
     },
-    /// hexadecimal buffer was of expected size
+    /// hexadecimal buffer was not of expected size
     Size {
         /// expected size
         expect: usize,
         /// size that was found
         actual: usize,
     },
+    /// caller-supplied output buffer was too small
+    Buffer {
+        /// required size in bytes
+        expect: usize,
+        /// size of the supplied buffer in bytes
+        actual: usize,
+    },
     /// non-hexadecimal character encountered
     Char {
         /// value encountered
         val: char,
+        /// index of the character within the input
+        index: usize,
+    },
+    /// hexadecimal buffer ended partway through a byte
+    OddLength {
+        /// size that was found
+        len: usize,
     },
+    /// hexadecimal buffer length was rejected by a foreign decoder, which
+    /// did not report the length it expected
+    Length {
+        /// size that was found
+        len: usize,
+    },
+    /// hexadecimal buffer was empty
+    EmptyInput,
+    /// required `0x` prefix was not found
+    MissingPrefix,
+    /// `0x` prefix was found where none is allowed
+    UnexpectedPrefix,
+    /// separator between a declared length and its payload was not found
+    MissingSeparator,
+    /// value does not fit in the target type
+    Overflow {
+        /// width of the target type in bits
+        bits: usize,
+        /// the offending value, if it fits in a `u128`
+        value: Option<u128>,
+    },
+    /// invalid hexadecimal encountered during constant-time decoding.
+    /// the offending character and its position are withheld.
+    Invalid,
+    /// hexadecimal was well-formed, but the decoded bytes were rejected by
+    /// the target type (e.g. a point which is not on the curve)
+    Rejected,
+}
+
+impl ParseHexError {
+    /// shift the index of a `Char` error by `by`.  used to translate
+    /// indexes reported by helpers which only see part of the input.
+    pub(crate) fn shift(self, by: usize) -> Self {
+        match self {
+            ParseHexError::Char { val, index } => ParseHexError::Char {
+                val,
+                index: index + by,
+            },
+            other => other,
+        }
+    }
+
+    /// build the error for input of the wrong size.  empty input is
+    /// reported as `EmptyInput` rather than a size of zero.
+    pub(crate) fn size(expect: usize, actual: usize) -> Self {
+        if actual == 0 {
+            ParseHexError::EmptyInput
+        } else {
+            ParseHexError::Size { expect, actual }
+        }
+    }
+
+    /// index of the offending character within the input, if this
+    /// error was caused by a non-hexadecimal character.
+    pub fn index(&self) -> Option<usize> {
+        match *self {
+            ParseHexError::Char { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    /// exact size that was expected, if this error was caused by a
+    /// size mismatch.
+    pub fn expected_len(&self) -> Option<usize> {
+        match *self {
+            ParseHexError::Size { expect, .. } | ParseHexError::Buffer { expect, .. } => {
+                Some(expect)
+            }
+            _ => None,
+        }
+    }
+
+    /// size that was actually found, if this error was caused by
+    /// the size of the input (or output buffer).
+    pub fn actual_len(&self) -> Option<usize> {
+        match *self {
+            ParseHexError::Size { actual, .. } | ParseHexError::Buffer { actual, .. } => {
+                Some(actual)
+            }
+            ParseHexError::Range { got, .. } => Some(got),
+            ParseHexError::OddLength { len } | ParseHexError::Length { len } => Some(len),
+            ParseHexError::EmptyInput => Some(0),
+            _ => None,
+        }
+    }
+
+    /// convert into a serde deserialization error for the input `src`.
+    /// errors are reported via `invalid_length`/`invalid_value` where
+    /// possible, so that the message describes what was expected.
+    pub fn into_de_error<E: de::Error>(self, src: &[u8]) -> E {
+        let unexp = match str::from_utf8(src) {
+            Ok(s) => de::Unexpected::Str(s),
+            Err(_) => de::Unexpected::Bytes(src),
+        };
+        match self {
+            ParseHexError::Range { got, .. } => E::invalid_length(got, &Expecting(&self)),
+            ParseHexError::Size { actual, .. } => E::invalid_length(actual, &Expecting(&self)),
+            ParseHexError::OddLength { len } | ParseHexError::Length { len } => {
+                E::invalid_length(len, &Expecting(&self))
+            }
+            ParseHexError::EmptyInput => E::invalid_length(0, &Expecting(&self)),
+            ParseHexError::Char { val, .. } => {
+                E::invalid_value(de::Unexpected::Char(val), &Expecting(&self))
+            }
+            ParseHexError::MissingPrefix
+            | ParseHexError::UnexpectedPrefix
+            | ParseHexError::MissingSeparator
+            | ParseHexError::Overflow { .. }
+            | ParseHexError::Invalid
+            | ParseHexError::Rejected => E::invalid_value(unexp, &Expecting(&self)),
+            ParseHexError::Buffer { .. } => E::custom(self),
+        }
+    }
+}
+
+// describes the input which would have avoided a given error, for
+// use as the expectation in serde's `invalid_length`/`invalid_value`.
+struct Expecting<'a>(&'a ParseHexError);
+
+impl<'a> de::Expected for Expecting<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            ParseHexError::Range { min, max, .. } => {
+                write!(f, "between {} and {} hex chars", min, max)
+            }
+            ParseHexError::Size { expect, .. } => write!(f, "{} hex chars", expect),
+            ParseHexError::Char { index, .. } => write!(f, "a hex digit at index {}", index),
+            ParseHexError::OddLength { .. } => f.write_str("an even number of hex chars"),
+            ParseHexError::Length { .. } => f.write_str("a hex string of valid length"),
+            ParseHexError::EmptyInput => f.write_str("a non-empty hex string"),
+            ParseHexError::MissingPrefix => f.write_str("a hex string with a `0x` prefix"),
+            ParseHexError::UnexpectedPrefix => f.write_str("a hex string without a `0x` prefix"),
+            ParseHexError::MissingSeparator => f.write_str("a length-prefixed hex string"),
+            ParseHexError::Overflow { bits, .. } => {
+                write!(f, "a hex value of at most {} bits", bits)
+            }
+            ParseHexError::Rejected => f.write_str("a hex encoding of a valid value"),
+            ParseHexError::Buffer { .. } | ParseHexError::Invalid => f.write_str("a hex string"),
+        }
+    }
 }
 
 impl fmt::Display for ParseHexError {
@@ -38,45 +206,96 @@ impl fmt::Display for ParseHexError {
                 ref min,
                 ref max,
                 ref got,
-            } => write!(
-                f,
-                "expected buff size in `{}...{}`, got `{}`",
-                min, max, got
-            ),
+            } => write!(f, "expected `{}...{}` hex chars, got `{}`", min, max, got),
             ParseHexError::Size {
                 ref expect,
                 ref actual,
-            } => write!(f, "expected buff size `{}` got `{}`", expect, actual),
-            ParseHexError::Char { ref val } => write!(f, "non-hex character `{}`", val),
+            } => write!(f, "expected `{}` hex chars, got `{}`", expect, actual),
+            ParseHexError::Buffer {
+                ref expect,
+                ref actual,
+            } => write!(f, "expected buffer of `{}` bytes, got `{}`", expect, actual),
+            ParseHexError::Char { ref val, ref index } => {
+                write!(f, "non-hex character `{}` at index `{}`", val, index)
+            }
+            ParseHexError::OddLength { ref len } => {
+                write!(f, "expected even number of hex chars, got `{}`", len)
+            }
+            ParseHexError::Length { ref len } => write!(f, "invalid length `{}` hex chars", len),
+            ParseHexError::EmptyInput => f.write_str("empty hexadecimal input"),
+            ParseHexError::MissingPrefix => f.write_str("missing `0x` prefix"),
+            ParseHexError::UnexpectedPrefix => f.write_str("unexpected `0x` prefix"),
+            ParseHexError::MissingSeparator => f.write_str("missing length separator"),
+            ParseHexError::Overflow {
+                ref bits,
+                value: Some(ref value),
+            } => write!(f, "value `{:#x}` does not fit in `{}` bits", value, bits),
+            ParseHexError::Overflow { ref bits, .. } => {
+                write!(f, "value does not fit in `{}` bits", bits)
+            }
+            ParseHexError::Invalid => f.write_str("invalid hexadecimal"),
+            ParseHexError::Rejected => f.write_str("decoded value was rejected"),
         }
     }
 }
 
-impl error::Error for ParseHexError {
-    fn description(&self) -> &str {
-        match *self {
-            ParseHexError::Range { .. } => "hexadecimal outside valid range",
-            ParseHexError::Size { .. } => "invalid hexadecimal size",
-            ParseHexError::Char { .. } => "non-hex character",
-        }
-    }
-}
+impl error::Error for ParseHexError {}
 
 /// top-level error kind of this crate
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// error raised during i/o operations
+    #[cfg(feature = "std")]
     IoError(io::Error),
+    /// error raised by a destination which does not report detailed
+    /// errors (e.g. when `std` is unavailable)
+    Write,
+    /// error raised when the value to encode can't be borrowed (e.g. a
+    /// `RefCell` which is mutably borrowed)
+    Borrowed,
     /// error raised during parsing operations
     Parsing(ParseHexError),
 }
 
+impl Error {
+    /// get the underlying parsing error, if any.
+    pub fn parse_error(&self) -> Option<&ParseHexError> {
+        match *self {
+            Error::Parsing(ref err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// get the underlying i/o error, if any.
+    #[cfg(feature = "std")]
+    pub fn io_error(&self) -> Option<&io::Error> {
+        match *self {
+            Error::IoError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// convert into a serde deserialization error for the input `src`.
+    /// see `ParseHexError::into_de_error`.
+    pub fn into_de_error<E: de::Error>(self, src: &[u8]) -> E {
+        match self {
+            Error::Parsing(err) => err.into_de_error(src),
+            other => E::custom(other),
+        }
+    }
+}
+
 // implement `Display` to allow user-facing errors.  Required
-// by the `std::error::Error` trait.
+// by the `std::error::Error` trait.  `Error` is a transparent
+// wrapper, so both the message and the source are forwarded.
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            #[cfg(feature = "std")]
             Error::IoError(ref err) => err.fmt(f),
+            Error::Write => f.write_str("failed to write to destination"),
+            Error::Borrowed => f.write_str("value is already mutably borrowed"),
             Error::Parsing(ref err) => err.fmt(f),
         }
     }
@@ -84,21 +303,17 @@ impl fmt::Display for Error {
 
 // implement the standard error trait for hexadecimal errors.
 impl error::Error for Error {
-    fn description(&self) -> &str {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
-            Error::IoError(ref err) => err.description(),
-            Error::Parsing(ref err) => err.description(),
-        }
-    }
-
-    fn cause(&self) -> Option<&error::Error> {
-        match *self {
-            Error::IoError(ref err) => Some(err),
-            Error::Parsing(ref err) => Some(err),
+            #[cfg(feature = "std")]
+            Error::IoError(ref err) => err.source(),
+            Error::Write | Error::Borrowed => None,
+            Error::Parsing(ref err) => err.source(),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
         Error::IoError(err)
@@ -110,3 +325,52 @@ impl From<ParseHexError> for Error {
         Error::Parsing(err)
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{Error, ParseHexError};
+    use std::error::Error as StdError;
+    use std::io;
+
+    #[test]
+    fn accessors() {
+        let err = Error::from(ParseHexError::Char { val: 'z', index: 3 });
+        assert_eq!(err.to_string(), "non-hex character `z` at index `3`");
+        assert_eq!(err.parse_error().and_then(|e| e.index()), Some(3));
+        assert!(err.io_error().is_none());
+        assert!(err.source().is_none());
+        let err = ParseHexError::Size {
+            expect: 64,
+            actual: 63,
+        };
+        assert_eq!(err.expected_len(), Some(64));
+        assert_eq!(err.actual_len(), Some(63));
+        assert_eq!(err.index(), None);
+        let err = Error::from(io::Error::new(io::ErrorKind::UnexpectedEof, "oops"));
+        assert_eq!(err.to_string(), "oops");
+        assert!(err.io_error().is_some());
+        assert!(err.parse_error().is_none());
+    }
+
+    #[test]
+    fn de_errors() {
+        use serde::de::value::Error as DeError;
+        let err = ParseHexError::Size {
+            expect: 8,
+            actual: 7,
+        };
+        let err: DeError = err.into_de_error(b"0011223");
+        assert_eq!(err.to_string(), "invalid length 7, expected 8 hex chars");
+        let err = Error::from(ParseHexError::Char { val: 'g', index: 2 });
+        let err: DeError = err.into_de_error(b"00g0");
+        assert_eq!(
+            err.to_string(),
+            "invalid value: character `g`, expected a hex digit at index 2"
+        );
+        let err: DeError = ParseHexError::MissingPrefix.into_de_error(b"00");
+        assert_eq!(
+            err.to_string(),
+            "invalid value: string \"00\", expected a hex string with a `0x` prefix"
+        );
+    }
+}
@@ -1,11 +1,20 @@
 //! various helper functions.
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use codec;
+use config::{HexConf, OverflowMode};
+use io;
 use std::borrow::Borrow;
-use std::io;
+use std::mem::MaybeUninit;
+use std::{fmt, iter, str};
 use types::{Error, ParseHexError};
+use {SerHex, SerHexSeq, Strict, StrictCap};
 
 /// convert a byte from a hex string to its numeric value.
 /// use the `tobyte` function to convert a pair of hex characters
-/// to their actual byte representation.
+/// to their actual byte representation.  errors report an index
+/// of zero; callers which know the position of `c` within the
+/// input are responsible for adjusting it.
 #[inline]
 pub fn intoval(c: u8) -> Result<u8, ParseHexError> {
     // ------------------------------------------------------
@@ -23,12 +32,12 @@ pub fn intoval(c: u8) -> Result<u8, ParseHexError> {
     //   MIT/APACHE (at your option)
     // ------------------------------------------------------
     match c {
-        b'A'...b'F' => Ok(c - b'A' + 10),
-        b'a'...b'f' => Ok(c - b'a' + 10),
-        b'0'...b'9' => Ok(c - b'0'),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'0'..=b'9' => Ok(c - b'0'),
         _ => {
             let val = c as char;
-            Err(ParseHexError::Char { val })
+            Err(ParseHexError::Char { val, index: 0 })
         }
     }
 }
@@ -38,8 +47,8 @@ pub fn intoval(c: u8) -> Result<u8, ParseHexError> {
 #[inline]
 pub fn fromval(val: u8) -> u8 {
     match val {
-        0xa...0xf => val - 0xa + b'a',
-        0x0...0x9 => val + b'0',
+        0xa..=0xf => val - 0xa + b'a',
+        0x0..=0x9 => val + b'0',
         _ => panic!("value outside range 0x0...0xf"),
     }
 }
@@ -49,8 +58,8 @@ pub fn fromval(val: u8) -> u8 {
 #[inline]
 pub fn fromvalcaps(val: u8) -> u8 {
     match val {
-        0xA...0xF => val - 0xa + b'A',
-        0x0...0x9 => val + b'0',
+        0xA..=0xF => val - 0xa + b'A',
+        0x0..=0x9 => val + b'0',
         _ => panic!("value outside range 0x0...0xf"),
     }
 }
@@ -59,7 +68,9 @@ pub fn fromvalcaps(val: u8) -> u8 {
 /// underlying byte representation.
 #[inline]
 pub fn intobyte(a: u8, b: u8) -> Result<u8, ParseHexError> {
-    Ok(intoval(a)? << 4 | intoval(b)?)
+    let high = intoval(a)?;
+    let low = intoval(b).map_err(|e| e.shift(1))?;
+    Ok(high << 4 | low)
 }
 
 /// attempt to convert a byte value into a pair of hexadecimal values.
@@ -76,19 +87,82 @@ pub fn frombytecaps(val: u8) -> (u8, u8) {
 
 /// Helper function which takes a mutable slice of expected byte-length and
 /// attempts to parse an immutable slice of bytes as hexadecimal characters.
-/// Returns an error if `src` is not exactly twice the size of `buf`, or if
-/// any non-hexadecimal characters are found.
+/// Returns an error if `src` is not exactly twice the size of `buf` (or
+/// `EmptyInput` if `src` is empty), or if any non-hexadecimal characters are
+/// found.
 pub fn fromhex(buf: &mut [u8], src: &[u8]) -> Result<(), ParseHexError> {
+    fromhex_slots(buf, src)
+}
+
+// destination of a decoded byte, so that the decoding loops below can fill
+// initialized and uninitialized buffers alike.
+pub(crate) trait ByteSlot {
+    fn set(&mut self, val: u8);
+}
+
+impl ByteSlot for u8 {
+    #[inline]
+    fn set(&mut self, val: u8) {
+        *self = val;
+    }
+}
+
+impl ByteSlot for MaybeUninit<u8> {
+    #[inline]
+    fn set(&mut self, val: u8) {
+        self.write(val);
+    }
+}
+
+// body of `fromhex`, generic over the destination.
+fn fromhex_slots<T: ByteSlot>(buf: &mut [T], src: &[u8]) -> Result<(), ParseHexError> {
     let expect = buf.len() * 2;
     let actual = src.len();
     if expect == actual {
-        for (idx, pair) in src.chunks(2).enumerate() {
-            buf[idx] = intobyte(pair[0], pair[1])?;
+        for (idx, (slot, pair)) in buf.iter_mut().zip(src.chunks(2)).enumerate() {
+            slot.set(intobyte(pair[0], pair[1]).map_err(|e| e.shift(idx * 2))?);
         }
         Ok(())
     } else {
-        Err(ParseHexError::Size { expect, actual })
+        Err(ParseHexError::size(expect, actual))
+    }
+}
+
+/// Helper function which decodes a buffer of hexadecimal characters in place,
+/// writing the decoded bytes over the front of `buf`.  Returns the number of
+/// decoded bytes (always half the length of `buf`).  Returns an error if `buf`
+/// is of odd length, or if any non-hexadecimal characters are found, in which
+/// case the contents of `buf` are unspecified.
+pub fn fromhex_inplace(buf: &mut [u8]) -> Result<usize, ParseHexError> {
+    if buf.len() % 2 == 1 {
+        return Err(ParseHexError::OddLength { len: buf.len() });
     }
+    let len = buf.len() / 2;
+    // byte `idx` is only ever written after bytes `idx * 2` and
+    // `idx * 2 + 1` have been read, so no unread input is clobbered.
+    for idx in 0..len {
+        buf[idx] = intobyte(buf[idx * 2], buf[idx * 2 + 1]).map_err(|e| e.shift(idx * 2))?;
+    }
+    Ok(len)
+}
+
+/// Helper function which decodes an owned buffer of hexadecimal characters
+/// without allocating a second buffer.  The decoded bytes are written over
+/// the front of `buf`, which is then truncated to the decoded length.
+#[cfg(feature = "alloc")]
+pub fn fromhex_vec(buf: &mut Vec<u8>) -> Result<(), ParseHexError> {
+    let len = fromhex_inplace(buf)?;
+    buf.truncate(len);
+    Ok(())
+}
+
+/// Helper function which decodes an owned hexadecimal string, reusing its
+/// allocation for the decoded bytes.
+#[cfg(feature = "alloc")]
+pub fn fromhex_string(src: String) -> Result<Vec<u8>, ParseHexError> {
+    let mut buf = src.into_bytes();
+    fromhex_vec(&mut buf)?;
+    Ok(buf)
 }
 
 /// write hex to buffer.
@@ -127,6 +201,698 @@ pub fn intohexcaps(buf: &mut [u8], src: &[u8]) {
     }
 }
 
+/// Strip the leading `0x` (or `0X`) from `src` if `pfx` is set and the
+/// prefix is present.  Otherwise, `src` is returned unchanged.
+pub fn strippfx(src: &[u8], pfx: bool) -> &[u8] {
+    if pfx && (src.starts_with(b"0x") || src.starts_with(b"0X")) {
+        &src[2..]
+    } else {
+        src
+    }
+}
+
+/// The prefix emitted under config `C` (`0x`, or `0X` if `C::pfxcap()`).
+/// Only meaningful for prefixed configs.
+#[inline]
+pub fn prefix<C: HexConf>() -> &'static [u8] {
+    if <C as HexConf>::pfxcap() {
+        b"0X"
+    } else {
+        b"0x"
+    }
+}
+
+/// Non-generic core of the bytearray-style `into_hex_raw` impls.  Writes the
+/// hexadecimal representation of `src` to the front of `buf` according to the
+/// supplied config flags, and returns the number of bytes written.
+///
+/// # panics
+///
+/// panics if `buf` is smaller than `src.len() * 2 + 2`.
+pub fn intohex_conf(buf: &mut [u8], src: &[u8], compact: bool, pfx: bool, cap: bool) -> usize {
+    let mut len = 0;
+    if pfx {
+        buf[..2].copy_from_slice(b"0x");
+        len += 2;
+    }
+    let body = if compact {
+        // find index and value of first non-zero byte.
+        match src.iter().position(|v| *v > 0u8) {
+            Some(idx) => {
+                // if first non-zero byte is less than `0x10`, repr w/ one hex char.
+                if src[idx] < 0x10 {
+                    buf[len] = if cap {
+                        fromvalcaps(src[idx])
+                    } else {
+                        fromval(src[idx])
+                    };
+                    len += 1;
+                    &src[(idx + 1)..]
+                } else {
+                    &src[idx..]
+                }
+            }
+            // if no non-zero byte was found, just write in a zero.
+            None => {
+                buf[len] = b'0';
+                return len + 1;
+            }
+        }
+    } else {
+        src
+    };
+    let end = len + body.len() * 2;
+    if cap {
+        intohexcaps(&mut buf[len..end], body);
+    } else {
+        intohex(&mut buf[len..end], body);
+    }
+    end
+}
+
+/// Non-generic core of the bytearray-style `from_hex_raw` impls.  Parses `src`
+/// into `buf` according to the supplied config flags.  In compact mode, any
+/// leading bytes of `buf` not covered by `src` are set to zero.
+pub fn fromhex_conf(
+    buf: &mut [u8],
+    src: &[u8],
+    compact: bool,
+    pfx: bool,
+) -> Result<(), ParseHexError> {
+    let hex = strippfx(src, pfx);
+    let skip = src.len() - hex.len();
+    fromhex_conf_body(buf, hex, compact).map_err(|e| e.shift(skip))
+}
+
+// body of `fromhex_conf`, operating on input with the prefix stripped.
+// writes every byte of `buf` unless it fails.
+pub(crate) fn fromhex_conf_body<T: ByteSlot>(
+    buf: &mut [T],
+    hex: &[u8],
+    compact: bool,
+) -> Result<(), ParseHexError> {
+    if compact {
+        let min = 1;
+        let max = buf.len() * 2;
+        let got = hex.len();
+        if got == 0 {
+            return Err(ParseHexError::EmptyInput);
+        }
+        if got > max {
+            return Err(ParseHexError::Range { min, max, got });
+        }
+        let body = buf.len() - (got / 2);
+        let head = got % 2;
+        for byte in buf[..(body - head)].iter_mut() {
+            byte.set(0);
+        }
+        if head > 0 {
+            buf[body - head].set(intoval(hex[0])?);
+        }
+        fromhex_slots(&mut buf[body..], &hex[head..]).map_err(|e| e.shift(head))
+    } else {
+        fromhex_slots(buf, hex)
+    }
+}
+
+// length of the `0x` prefix at the start of `src` (if allowed), usable
+// in const contexts.
+const fn const_pfx_len(src: &[u8], pfx: bool) -> usize {
+    if pfx && src.len() >= 2 && src[0] == b'0' && (src[1] == b'x' || src[1] == b'X') {
+        2
+    } else {
+        0
+    }
+}
+
+// const variant of `intoval`, which panics on invalid input.
+const fn const_val(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("non-hex character in hexadecimal literal"),
+    }
+}
+
+/// Number of bytes which `src` decodes to under the supplied config flags.
+/// Used by the `serhex!` macro.
+#[doc(hidden)]
+pub const fn const_decoded_len(src: &str, compact: bool, pfx: bool) -> usize {
+    let src = src.as_bytes();
+    let digits = src.len() - const_pfx_len(src, pfx);
+    if compact {
+        digits / 2 + digits % 2
+    } else {
+        digits / 2
+    }
+}
+
+/// Decode `src` under the supplied config flags, panicking (i.e. failing
+/// compilation, when evaluated in a const context) if it is invalid.
+/// Compact input is left-padded with zeroes.  Used by the `serhex!` macro.
+#[doc(hidden)]
+pub const fn const_decode<const N: usize>(src: &str, compact: bool, pfx: bool) -> [u8; N] {
+    let src = src.as_bytes();
+    let start = const_pfx_len(src, pfx);
+    let digits = src.len() - start;
+    if digits == 0 {
+        panic!("empty hexadecimal literal");
+    }
+    if compact {
+        if digits > N * 2 {
+            panic!("hexadecimal literal is too long");
+        }
+    } else if digits != N * 2 {
+        panic!("hexadecimal literal is the wrong length");
+    }
+    let mut buf = [0u8; N];
+    // fill nibbles from the back, so that compact input is padded.
+    let mut idx = src.len();
+    let mut pos = N * 2;
+    while idx > start {
+        idx -= 1;
+        pos -= 1;
+        let val = const_val(src[idx]);
+        if pos % 2 == 1 {
+            buf[pos / 2] |= val;
+        } else {
+            buf[pos / 2] |= val << 4;
+        }
+    }
+    buf
+}
+
+/// Overwrite `buf` with zeroes if the `zeroize` feature is enabled, and do
+/// nothing otherwise.  Used to clear scratch buffers, which may hold the
+/// encoding of a secret value, before they are dropped.
+#[inline]
+pub fn scrub(buf: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    ::zeroize::Zeroize::zeroize(buf);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = buf;
+}
+
+/// Number of bytes in the hex encoding of `len` bytes under config `C`,
+/// including any prefix.  Exact for strict configs and configs with a fixed
+/// `width`; compact output trims leading zeroes, so for compact configs
+/// this is the maximum.  Use to size buffers for fixed-size values (see
+/// `codec::encode_into`).
+///
+/// Configs have no notion of separators or line wrapping, so neither is
+/// counted.  Output grouped by `group::Grouped` also has a delimiter between
+/// groups (and a prefix on each, if requested), and output wrapped by
+/// `file::HexFile::wrap` has a line break after each line.
+#[inline]
+pub fn encoded_len<C: HexConf>(len: usize) -> usize {
+    codec::encoded_len(&<C as HexConf>::describe(), len)
+}
+
+/// Number of bytes which the hex string `src` decodes to under config `C`,
+/// as by `fromhex_vec_with`: half the digits after any prefix, with an odd
+/// digit counting as a whole byte.  Doesn't check that `src` is valid (see
+/// `validate`), and doesn't skip separators or line breaks, which have to be
+/// removed first.
+#[inline]
+pub fn decoded_len<C: HexConf>(src: &[u8]) -> usize {
+    codec::decoded_len(&<C as HexConf>::describe(), src)
+}
+
+/// Encode `src` into `buf` according to config `C`, returning the number of
+/// bytes written.  This is a thin shim over the non-generic core in `codec`
+/// (which dispatches to `intohex_conf`, or `intohex_ct` for constant-time
+/// configs).  Any `width` is ignored.
+///
+/// # panics
+///
+/// panics if `buf` is smaller than `src.len() * 2 + 2`.
+#[inline]
+pub fn intohex_with<C: HexConf>(buf: &mut [u8], src: &[u8]) -> usize {
+    codec::encode_unchecked(&<C as HexConf>::describe(), buf, src)
+}
+
+/// Decode `src` into `buf` according to config `C`.  This is a thin shim over
+/// `codec::decode_into` (which dispatches to `fromhex_conf`, or `fromhex_ct`
+/// for constant-time configs).
+#[inline]
+pub fn fromhex_with<C: HexConf>(buf: &mut [u8], src: &[u8]) -> Result<(), ParseHexError> {
+    codec::decode_into(&<C as HexConf>::describe(), buf, src)
+}
+
+/// The value of the hex digits `hex`, if they are all valid and the value
+/// fits in a `u128`.  Used to fill in `ParseHexError::Overflow`.
+#[doc(hidden)]
+pub fn hex_value(hex: &[u8]) -> Option<u128> {
+    let lead = hex.iter().take_while(|c| **c == b'0').count();
+    let digits = &hex[lead..];
+    if digits.len() > 32 {
+        return None;
+    }
+    digits.iter().try_fold(0u128, |acc, c| {
+        intoval(*c).ok().map(|val| (acc << 4) | u128::from(val))
+    })
+}
+
+/// The value of the big-endian bytes `src`, if it fits in a `u128`.  Used to
+/// fill in `ParseHexError::Overflow`.
+#[doc(hidden)]
+pub fn be_value(src: &[u8]) -> Option<u128> {
+    let lead = src.iter().take_while(|b| **b == 0).count();
+    let bytes = &src[lead..];
+    if bytes.len() > 16 {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .fold(0u128, |acc, b| (acc << 8) | u128::from(*b)),
+    )
+}
+
+/// Recover from an error raised while parsing `src` into the big-endian
+/// integer `buf` under config `C`.  Input which is too long for the integer
+/// (a `Range` error) is reported as `ParseHexError::Overflow` if the value it
+/// holds needs more than `buf.len()` bytes, rather than merely having extra
+/// leading zeroes.  Overflowing values are then wrapped or saturated into
+/// `buf` according to `C::overflow()`.  If `signed`, input which is merely
+/// sign-extended (extra leading `f` digits before a kept digit with its top
+/// bit set) is in range, and is truncated even when saturating.  Otherwise
+/// saturation is towards the minimum when the leading digit has its top bit
+/// set (as for the bit pattern of a wider negative value), and towards the
+/// maximum otherwise.
+/// Used by `impl_serhex_uint`.
+#[doc(hidden)]
+pub fn int_overflow<C: HexConf>(
+    err: ParseHexError,
+    src: &[u8],
+    signed: bool,
+    buf: &mut [u8],
+) -> Result<(), ParseHexError> {
+    let hex = strippfx(src, <C as HexConf>::acceptpfx());
+    let bits = buf.len() * 8;
+    let err = match err {
+        ParseHexError::Range { .. } if hex.iter().all(|c| intoval(*c).is_ok()) => {
+            let lead = hex.iter().take_while(|c| **c == b'0').count();
+            if (hex.len() - lead) * 4 > bits {
+                let value = hex_value(hex);
+                ParseHexError::Overflow { bits, value }
+            } else {
+                err
+            }
+        }
+        other => other,
+    };
+    if !matches!(err, ParseHexError::Overflow { .. }) {
+        return Err(err);
+    }
+    let digits = &hex[hex.len() - buf.len() * 2..];
+    let top = digits
+        .first()
+        .is_some_and(|c| intoval(*c).is_ok_and(|v| v >= 8));
+    // a signed value written sign-extended (e.g. `ff80` into one byte) has
+    // more digits than fit, but is in range, so saturating would be wrong.
+    let extended = signed
+        && hex[..hex.len() - digits.len()].iter().all(|c| {
+            if top {
+                *c == b'f' || *c == b'F'
+            } else {
+                *c == b'0'
+            }
+        });
+    match <C as HexConf>::overflow() {
+        OverflowMode::Saturate if !extended => {
+            let negative = signed
+                && hex
+                    .first()
+                    .is_some_and(|c| intoval(*c).is_ok_and(|v| v >= 8));
+            let fill = if negative { 0x00 } else { 0xff };
+            buf.iter_mut().for_each(|byte| *byte = fill);
+            if signed {
+                buf[0] = if negative { 0x80 } else { 0x7f };
+            }
+            Ok(())
+        }
+        OverflowMode::Wrap | OverflowMode::Saturate => {
+            fromhex(buf, digits).map_err(|e| e.shift(src.len() - digits.len()))
+        }
+        OverflowMode::Error => Err(err),
+    }
+}
+
+/// Write the hex encoding of the fixed-size `src` to `dst` as exactly
+/// `width` digits (plus any prefix), according to config `C`.  Fails with
+/// `ParseHexError::Overflow` if the value needs more than `width` digits.
+/// Used by the bytearray-style impls for configs with a fixed `width`.
+/// Constant-time configs ignore `width`, as for `writehex_with`.
+#[inline]
+pub fn writehex_width<C, D>(src: &[u8], width: usize, dst: D) -> Result<(), Error>
+where
+    C: HexConf,
+    D: io::Write,
+{
+    codec::write_width(&<C as HexConf>::describe(), src, width, dst)
+}
+
+/// Write the hex encoding of the variable-length `src` to `dst` according to
+/// config `C`.  Encoding is done in fixed-size chunks, so no buffer
+/// proportional to the length of `src` is needed.  Compact configs trim
+/// leading zeroes, as for fixed-size types.
+#[inline]
+pub fn writehex_with<C, D>(src: &[u8], dst: D) -> Result<(), Error>
+where
+    C: HexConf,
+    D: io::Write,
+{
+    codec::write(&<C as HexConf>::describe(), src, dst)
+}
+
+// write the `len` characters written by `body`, padded to the width of `f`
+// with its fill and alignment (left by default), as `f.pad` would pad them.
+// lets `Display` impls honour padding without buffering their output.
+pub(crate) fn pad_with<F>(f: &mut fmt::Formatter, len: usize, body: F) -> fmt::Result
+where
+    F: FnOnce(&mut fmt::Formatter) -> fmt::Result,
+{
+    use std::fmt::Write;
+    let pad = f.width().map_or(0, |width| width.saturating_sub(len));
+    let (pre, post) = match f.align() {
+        Some(fmt::Alignment::Right) => (pad, 0),
+        Some(fmt::Alignment::Center) => (pad / 2, pad - pad / 2),
+        _ => (0, pad),
+    };
+    let fill = f.fill();
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+    body(f)?;
+    for _ in 0..post {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// Decode the variable-length hex string `src` according to config `C`.
+/// Strict configs accept any even number of digits (including none), and
+/// compact configs any non-zero number (or none, if the config treats empty
+/// input as zero), left-padding odd-length input by one nibble.
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn fromhex_vec_with<C: HexConf>(src: &[u8]) -> Result<Vec<u8>, ParseHexError> {
+    codec::decode_vec(&<C as HexConf>::describe(), src)
+}
+
+/// Encode the fixed-size `src` as a new string according to config `C`,
+/// honouring any `width` (as `into_hex` does for byte-array types).  Used by
+/// `impl_newtype_accessors`.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub fn intohex_string_with<C: HexConf>(src: &[u8]) -> Result<String, Error> {
+    let mut dst = String::new();
+    match <C as HexConf>::width() {
+        Some(width) => writehex_width::<C, _>(src, width, io::StringWriter(&mut dst))?,
+        None => writehex_with::<C, _>(src, io::StringWriter(&mut dst))?,
+    }
+    Ok(dst)
+}
+
+/// Encode `src` into the front of the uninitialized `buf` according to config
+/// `C`, returning the written bytes.  A thin shim over `codec::encode_uninit`.
+#[inline]
+pub fn intohex_uninit_with<'a, C: HexConf>(
+    buf: &'a mut [MaybeUninit<u8>],
+    src: &[u8],
+) -> Result<&'a mut [u8], Error> {
+    codec::encode_uninit(&<C as HexConf>::describe(), buf, src)
+}
+
+/// Decode the variable-length hex string `src` into the front of the
+/// uninitialized `buf` according to config `C`, returning the decoded bytes.
+/// A thin shim over `codec::decode_uninit`.
+#[inline]
+pub fn fromhex_uninit_with<'a, C: HexConf>(
+    buf: &'a mut [MaybeUninit<u8>],
+    src: &[u8],
+) -> Result<&'a mut [u8], ParseHexError> {
+    codec::decode_uninit(&<C as HexConf>::describe(), buf, src)
+}
+
+/// Write the hex encoding of `src`, as produced by its `LowerHex`/`UpperHex`
+/// impl, to `dst` according to config `C`.  Strict configs left-pad the
+/// digits to `width` bytes if supplied, and to a whole number of bytes
+/// otherwise.  Used by `impl_serhex_via_fmt`.
+#[doc(hidden)]
+pub fn writehex_fmt<C, T, D>(src: &T, width: Option<usize>, dst: D) -> Result<(), Error>
+where
+    C: HexConf,
+    T: ?Sized + fmt::LowerHex + fmt::UpperHex,
+    D: io::Write,
+{
+    let cap = <C as HexConf>::withcap();
+    let mut count = FmtCount(0);
+    fmt_hex(&mut count, src, cap).map_err(|_| Error::Write)?;
+    let digits = count.0;
+    let pad = if <C as HexConf>::compact() {
+        0
+    } else {
+        match width {
+            Some(bytes) => (bytes * 2).saturating_sub(digits),
+            None => digits % 2,
+        }
+    };
+    let mut out = FmtIo { dst, err: None };
+    if <C as HexConf>::withpfx() {
+        out.dst.write_all(prefix::<C>())?;
+    }
+    for _ in 0..pad {
+        out.dst.write_all(b"0")?;
+    }
+    match fmt_hex(&mut out, src, cap) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(out.err.unwrap_or(Error::Write)),
+    }
+}
+
+/// Check the hex string `src` against config `C`, and yield its digits
+/// (without any prefix) for parsing by `from_str_radix` or similar.  Strict
+/// configs expect exactly `width` bytes if supplied, and a whole number of
+/// bytes otherwise; compact configs accept up to `width` bytes.  Used by
+/// `impl_serhex_via_fmt`.
+#[doc(hidden)]
+pub fn fromhex_fmt<C: HexConf>(src: &[u8], width: Option<usize>) -> Result<&str, ParseHexError> {
+    let compact = <C as HexConf>::compact();
+    let hex = strippfx(src, <C as HexConf>::acceptpfx());
+    let skip = src.len() - hex.len();
+    let got = hex.len();
+    if got == 0 {
+        return if compact && <C as HexConf>::emptyzero() {
+            Ok("0")
+        } else {
+            Err(ParseHexError::EmptyInput)
+        };
+    }
+    let pad = compact || <C as HexConf>::padodd();
+    match width {
+        Some(bytes) if compact => {
+            if got > bytes * 2 {
+                return Err(ParseHexError::Range {
+                    min: 1,
+                    max: bytes * 2,
+                    got,
+                });
+            }
+        }
+        Some(bytes) => {
+            if got != bytes * 2 && !(pad && got + 1 == bytes * 2) {
+                return Err(ParseHexError::size(bytes * 2, got));
+            }
+        }
+        None => {
+            if got % 2 == 1 && !pad {
+                return Err(ParseHexError::OddLength { len: got });
+            }
+        }
+    }
+    for (idx, chr) in hex.iter().enumerate() {
+        intoval(*chr).map_err(|e| e.shift(skip + idx))?;
+    }
+    str::from_utf8(hex).map_err(|_| ParseHexError::Invalid)
+}
+
+// formats `src` as lower or upper case hex digits.
+fn fmt_hex<W, T>(dst: &mut W, src: &T, cap: bool) -> fmt::Result
+where
+    W: fmt::Write,
+    T: ?Sized + fmt::LowerHex + fmt::UpperHex,
+{
+    if cap {
+        fmt::Write::write_fmt(dst, format_args!("{:X}", src))
+    } else {
+        fmt::Write::write_fmt(dst, format_args!("{:x}", src))
+    }
+}
+
+/// Collect the items returned by `next` (until it yields `Ok(None)`) into
+/// any `FromIterator` container, without an intermediate buffer, stopping
+/// at the first error.
+pub(crate) fn collect_until_err<T, E, C, F>(mut next: F) -> Result<C, E>
+where
+    F: FnMut() -> Result<Option<T>, E>,
+    C: iter::FromIterator<T>,
+{
+    iter::from_fn(|| next().transpose()).collect()
+}
+
+// counts the bytes written to it.
+struct FmtCount(usize);
+
+impl fmt::Write for FmtCount {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+// forwards formatted output to an `io::Write`, stashing the first error
+// (since `fmt::Error` carries no details).
+struct FmtIo<D> {
+    dst: D,
+    err: Option<Error>,
+}
+
+impl<D> fmt::Write for FmtIo<D>
+where
+    D: io::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.dst.write_all(s.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                // without `std`, the writer already fails with our `Error`.
+                #[cfg(feature = "std")]
+                let err = Error::from(err);
+                self.err = Some(err);
+                Err(fmt::Error)
+            }
+        }
+    }
+}
+
+/// Encode the field `src` of a struct implemented via `impl_serhex_struct`,
+/// at the full (strict) width of its type, in the case specified by `C`.
+#[doc(hidden)]
+pub fn intohex_field<C, T, D>(src: &T, dst: D) -> Result<(), Error>
+where
+    C: HexConf,
+    T: SerHex<Strict, Error = Error> + SerHex<StrictCap, Error = Error>,
+    D: io::Write,
+{
+    if <C as HexConf>::withcap() {
+        <T as SerHex<StrictCap>>::into_hex_raw(src, dst)
+    } else {
+        <T as SerHex<Strict>>::into_hex_raw(src, dst)
+    }
+}
+
+/// Strip the prefix (if permitted by `C`) from the hex encoding of a struct
+/// implemented via `impl_serhex_struct`, and check that the remainder is
+/// `width` characters long.  Yields the remainder and the prefix length.
+#[doc(hidden)]
+pub fn fromhex_fields<C: HexConf>(src: &[u8], width: usize) -> Result<(&[u8], usize), Error> {
+    let hex = strippfx(src, <C as HexConf>::acceptpfx());
+    if hex.len() != width {
+        return Err(ParseHexError::size(width, hex.len()).into());
+    }
+    Ok((hex, src.len() - hex.len()))
+}
+
+/// Decode the next field of a struct implemented via `impl_serhex_struct`
+/// from the front of `rest`, advancing `rest` and `offset` past it.  The
+/// indexes of invalid characters are reported relative to the whole input.
+///
+/// # panics
+///
+/// panics if `rest` is shorter than the strict encoding of `T`.
+#[doc(hidden)]
+pub fn fromhex_field<T>(rest: &mut &[u8], offset: &mut usize) -> Result<T, Error>
+where
+    T: SerHexSeq<Strict> + SerHex<Strict, Error = Error>,
+{
+    let width = <T as SerHexSeq<Strict>>::size() * 2;
+    let (head, tail) = rest.split_at(width);
+    let rslt = <T as SerHex<Strict>>::from_hex_raw(head).map_err(|err| match err {
+        Error::Parsing(err) => Error::Parsing(err.shift(*offset)),
+        other => other,
+    });
+    *rest = tail;
+    *offset += width;
+    rslt
+}
+
+/// constant-time variant of `intoval`.  yields the numeric value of `c`,
+/// or a negative value if `c` is not a hexadecimal character, without
+/// branching on (or indexing by) the value of `c`.
+#[cfg(feature = "constant-time")]
+#[inline]
+fn intoval_ct(c: u8) -> i16 {
+    let c = c as i16;
+    let mut val: i16 = -1;
+    // each term is `c - base + 1` if `c` is within the given range, and
+    // zero otherwise.  the range checks are sign-bit masks.
+    val += (((0x2f - c) & (c - 0x3a)) >> 8) & (c - 0x2f);
+    val += (((0x40 - c) & (c - 0x47)) >> 8) & (c - 0x36);
+    val += (((0x60 - c) & (c - 0x67)) >> 8) & (c - 0x56);
+    val
+}
+
+/// constant-time variant of `fromval`/`fromvalcaps`.
+#[cfg(feature = "constant-time")]
+#[inline]
+fn fromval_ct(val: u8, cap: bool) -> u8 {
+    let val = val as i16;
+    let alpha = if cap { 0x41 - 0x3a } else { 0x61 - 0x3a };
+    (val + 0x30 + (((9 - val) >> 8) & alpha)) as u8
+}
+
+/// Constant-time variant of `fromhex`.  The running time depends only on the
+/// length of `src`, and errors do not reveal which character was invalid.
+/// Returns an error if `src` is not exactly twice the size of `buf`, or if
+/// any non-hexadecimal characters are found.
+#[cfg(feature = "constant-time")]
+pub fn fromhex_ct(buf: &mut [u8], src: &[u8]) -> Result<(), ParseHexError> {
+    let expect = buf.len() * 2;
+    let actual = src.len();
+    if expect != actual {
+        return Err(ParseHexError::size(expect, actual));
+    }
+    let mut bad: i16 = 0;
+    for (idx, pair) in src.chunks(2).enumerate() {
+        let high = intoval_ct(pair[0]);
+        let low = intoval_ct(pair[1]);
+        bad |= high | low;
+        buf[idx] = (high << 4 | low) as u8;
+    }
+    if bad < 0 {
+        Err(ParseHexError::Invalid)
+    } else {
+        Ok(())
+    }
+}
+
+/// Constant-time variant of `intohex`/`intohexcaps`.
+///
+/// # panics
+///
+/// panics if `buf` is not exactly twice the size of `src`.
+#[cfg(feature = "constant-time")]
+pub fn intohex_ct(buf: &mut [u8], src: &[u8], cap: bool) {
+    assert!(buf.len() == src.len() * 2, "invalid buffer sizes");
+    for (pair, byte) in buf.chunks_mut(2).zip(src.iter()) {
+        pair[0] = fromval_ct(byte >> 4, cap);
+        pair[1] = fromval_ct(byte & 0x0f, cap);
+    }
+}
+
 /// Helper function which attempts to convert an immutable set of bytes into
 /// hexadecimal characters and write them to some destination.
 pub fn writehex<S, B, D>(src: S, mut dst: D) -> Result<(), Error>
@@ -157,6 +923,76 @@ where
     Ok(())
 }
 
+/// Write `src` to a `defmt` formatter as contiguous lowercase hex.  Each byte
+/// is sent as a single value, so no `core::fmt` machinery is involved.
+#[cfg(feature = "defmt")]
+pub fn defmt_hex(src: &[u8], fmt: ::defmt::Formatter) {
+    for byte in src.iter() {
+        ::defmt::write!(fmt, "{=u8:02x}", *byte);
+    }
+}
+
+/// Lazily convert a sequence of bytes into lowercase hexadecimal characters.
+pub fn encode_iter<S, B>(src: S) -> impl Iterator<Item = char>
+where
+    S: IntoIterator<Item = B>,
+    B: Borrow<u8>,
+{
+    src.into_iter().flat_map(|byte| {
+        let (a, b) = frombyte(*byte.borrow());
+        iter::once(a as char).chain(iter::once(b as char))
+    })
+}
+
+/// Lazily convert a sequence of bytes into uppercase hexadecimal characters.
+pub fn encode_iter_caps<S, B>(src: S) -> impl Iterator<Item = char>
+where
+    S: IntoIterator<Item = B>,
+    B: Borrow<u8>,
+{
+    src.into_iter().flat_map(|byte| {
+        let (a, b) = frombytecaps(*byte.borrow());
+        iter::once(a as char).chain(iter::once(b as char))
+    })
+}
+
+/// Lazily convert a sequence of hexadecimal characters into bytes.  Yields
+/// an error (and then stops) if a non-hexadecimal character is encountered,
+/// or if the sequence ends partway through a byte.
+pub fn decode_iter<S>(src: S) -> impl Iterator<Item = Result<u8, ParseHexError>>
+where
+    S: IntoIterator<Item = char>,
+{
+    let mut chars = src.into_iter();
+    let mut count = 0;
+    let mut done = false;
+    iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let a = chars.next()?;
+        let rslt = match chars.next() {
+            Some(b) => charval(a)
+                .and_then(|a| Ok(a << 4 | charval(b).map_err(|e| e.shift(1))?))
+                .map_err(|e| e.shift(count)),
+            None => Err(ParseHexError::OddLength { len: count + 1 }),
+        };
+        count += 2;
+        done = rslt.is_err();
+        Some(rslt)
+    })
+}
+
+/// convert a hexadecimal `char` to its numeric value.
+#[inline]
+fn charval(c: char) -> Result<u8, ParseHexError> {
+    if c.is_ascii() {
+        intoval(c as u8)
+    } else {
+        Err(ParseHexError::Char { val: c, index: 0 })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -198,4 +1034,165 @@ mod tests {
             assert_eq!(src, AsRef::<[u8]>::as_ref(&rslt));
         }
     }
+
+    #[test]
+    fn hex_conf() {
+        use types::ParseHexError;
+        use utils::{fromhex_conf, intohex_conf};
+        let cases: [(&[u8], bool, bool, bool, &str); 6] = [
+            (&[0x00, 0x0a, 0xff], false, false, false, "000aff"),
+            (&[0x00, 0x0a, 0xff], true, false, false, "aff"),
+            (&[0x00, 0x0a, 0xff], true, true, true, "0xAFF"),
+            (&[0x00, 0xaa, 0xff], true, true, false, "0xaaff"),
+            (&[0x00, 0x00, 0x00], true, false, false, "0"),
+            (&[0x00, 0x00, 0x00], false, true, true, "0x000000"),
+        ];
+        for &(src, compact, pfx, cap, exp) in cases.iter() {
+            let mut hex = [0u8; 8];
+            let len = intohex_conf(&mut hex, src, compact, pfx, cap);
+            assert_eq!(&hex[..len], exp.as_bytes());
+            let mut buf = [0xffu8; 3];
+            fromhex_conf(&mut buf, &hex[..len], compact, pfx).unwrap();
+            assert_eq!(&buf, src);
+        }
+        let mut buf = [0u8; 2];
+        match fromhex_conf(&mut buf, b"0x", true, true) {
+            Err(ParseHexError::EmptyInput) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(fromhex_conf(&mut buf, b"12345", true, false).is_err());
+        assert!(fromhex_conf(&mut buf, b"123", false, false).is_err());
+    }
+
+    #[test]
+    fn hex_lengths() {
+        use config::{Compact, Strict, StrictPfx, Width};
+        use utils::{decoded_len, encoded_len, writehex_width};
+        assert_eq!(encoded_len::<Strict>(4), 8);
+        assert_eq!(encoded_len::<StrictPfx>(4), 10);
+        assert_eq!(encoded_len::<Compact>(0), 0);
+        assert_eq!(encoded_len::<Width<6, StrictPfx>>(2), 8);
+        let mut out = Vec::new();
+        writehex_width::<StrictPfx, _>(&[0xab, 0xcd], 6, &mut out).unwrap();
+        assert_eq!(out.len(), encoded_len::<Width<6, StrictPfx>>(2));
+        assert_eq!(decoded_len::<StrictPfx>(b"0xabcd"), 2);
+        assert_eq!(decoded_len::<Compact>(b"abc"), 2);
+        // the prefix is only stripped for prefixed configs.
+        assert_eq!(decoded_len::<Strict>(b"0xab"), 2);
+        assert_eq!(decoded_len::<Compact>(b""), 0);
+    }
+
+    #[test]
+    fn hex_inplace() {
+        use types::ParseHexError;
+        use utils::{fromhex_string, fromhex_vec};
+        let src = String::from("0123456789abcdefABCDEF");
+        let cap = src.capacity();
+        let buf = fromhex_string(src).unwrap();
+        assert_eq!(
+            buf,
+            vec![0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xab, 0xcd, 0xef]
+        );
+        assert_eq!(buf.capacity(), cap);
+        let mut odd = b"abc".to_vec();
+        match fromhex_vec(&mut odd) {
+            Err(ParseHexError::OddLength { len: 3 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let mut bad = b"abzz".to_vec();
+        assert!(fromhex_vec(&mut bad).is_err());
+        let mut empty = Vec::new();
+        fromhex_vec(&mut empty).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn hex_iters() {
+        use utils::{decode_iter, encode_iter, encode_iter_caps};
+        let bytes = [0x01, 0x23, 0xab, 0xcd];
+        let hex: String = encode_iter(&bytes).collect();
+        assert_eq!(hex, "0123abcd");
+        let hex: String = encode_iter_caps(bytes.iter().cloned()).collect();
+        assert_eq!(hex, "0123ABCD");
+        let rslt: Result<Vec<u8>, _> = decode_iter(hex.chars()).collect();
+        assert_eq!(rslt.unwrap(), bytes);
+        let mut odd = decode_iter("abc".chars());
+        assert_eq!(odd.next().unwrap().unwrap(), 0xab);
+        assert!(odd.next().unwrap().is_err());
+        assert!(odd.next().is_none());
+        let mut bad = decode_iter("zz00".chars());
+        assert!(bad.next().unwrap().is_err());
+        assert!(bad.next().is_none());
+        assert!(decode_iter("éé".chars()).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn char_index() {
+        use types::ParseHexError;
+        use utils::{decode_iter, fromhex, fromhex_conf, fromhex_vec};
+        fn index(err: ParseHexError) -> usize {
+            match err {
+                ParseHexError::Char { index, .. } => index,
+                other => panic!("unexpected error: {:?}", other),
+            }
+        }
+        let mut buf = [0u8; 3];
+        assert_eq!(index(fromhex(&mut buf, b"0011z2").unwrap_err()), 4);
+        assert_eq!(index(fromhex(&mut buf, b"00112z").unwrap_err()), 5);
+        assert_eq!(
+            index(fromhex_conf(&mut buf, b"0x0011z2", false, true).unwrap_err()),
+            6
+        );
+        assert_eq!(
+            index(fromhex_conf(&mut buf, b"0x1z", true, true).unwrap_err()),
+            3
+        );
+        assert_eq!(
+            index(fromhex_conf(&mut buf, b"z11", true, false).unwrap_err()),
+            0
+        );
+        let mut vec = b"0011z2".to_vec();
+        assert_eq!(index(fromhex_vec(&mut vec).unwrap_err()), 4);
+        let err = decode_iter("00112z".chars()).find(|r| r.is_err()).unwrap();
+        assert_eq!(index(err.unwrap_err()), 5);
+    }
+
+    #[cfg(feature = "constant-time")]
+    #[test]
+    fn hex_ct() {
+        use types::ParseHexError;
+        use utils::{fromhex, fromhex_ct, intohex, intohex_ct, intoval, intoval_ct};
+        for c in 0..=255u8 {
+            match intoval(c) {
+                Ok(val) => assert_eq!(intoval_ct(c), val as i16),
+                Err(_) => assert!(intoval_ct(c) < 0),
+            }
+        }
+        let bytes: Vec<u8> = (0..=255u8).collect();
+        let mut hex = vec![0u8; 512];
+        let mut exp = vec![0u8; 512];
+        intohex_ct(&mut hex, &bytes, false);
+        intohex(&mut exp, &bytes);
+        assert_eq!(hex, exp);
+        let mut buf = vec![0u8; 256];
+        fromhex_ct(&mut buf, &hex).unwrap();
+        assert_eq!(buf, bytes);
+        intohex_ct(&mut hex, &bytes, true);
+        fromhex(&mut buf, &hex).unwrap();
+        assert_eq!(buf, bytes);
+        match fromhex_ct(&mut buf[..2], b"00g0") {
+            Err(ParseHexError::Invalid) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(fromhex_ct(&mut buf[..2], b"000").is_err());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn scrub_zeroes() {
+        use utils::scrub;
+        let mut buf = *b"deadbeef";
+        scrub(&mut buf);
+        assert_eq!(buf, [0u8; 8]);
+    }
 }
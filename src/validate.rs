@@ -0,0 +1,136 @@
+//! Validation of hexadecimal input without decoding.
+//!
+//! Useful for services which only forward hex strings, and just need to check
+//! that the input is well-formed for a given config.  Validation never
+//! allocates, and applies the same rules as the decoders in this crate.
+use config::HexConf;
+use types::ParseHexError;
+use utils::{decoded_len, intoval, strippfx};
+
+/// Metadata describing a successfully validated hexadecimal string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidatedInfo {
+    /// number of bytes the input decodes to.
+    pub decoded_len: usize,
+    /// whether the input began with a `0x` prefix.
+    pub has_prefix: bool,
+}
+
+/// Check that `src` is valid hexadecimal under config `C`, without decoding it.
+///
+/// The `0x` prefix is optional for prefixed configs (as when decoding), and
+/// is rejected as a non-hex character otherwise.  Strict configs require a
+/// non-zero, even number of digits (or any non-zero number, if the config
+/// pads odd-length input), while compact configs allow any non-zero number
+/// (or none, if the config treats empty input as zero).  If the config has a
+/// `width`, strict configs require exactly that many digits, and compact
+/// configs at most that many.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{validate, Compact, StrictPfx, Width};
+/// # fn main() {
+/// let info = validate::<StrictPfx>("0xdeadbeef").unwrap();
+/// assert_eq!(info.decoded_len, 4);
+/// assert!(info.has_prefix);
+///
+/// assert_eq!(validate::<Compact>("fff").unwrap().decoded_len, 2);
+/// assert!(validate::<StrictPfx>("0xfff").is_err());
+/// assert!(validate::<Width<6, StrictPfx>>("0xab").is_err());
+/// # }
+/// ```
+pub fn validate<C: HexConf>(src: impl AsRef<[u8]>) -> Result<ValidatedInfo, ParseHexError> {
+    let src = src.as_ref();
+    let hex = strippfx(src, <C as HexConf>::acceptpfx());
+    let pfx = src.len() - hex.len();
+    if hex.is_empty() {
+        if !(<C as HexConf>::compact() && <C as HexConf>::emptyzero()) {
+            return Err(ParseHexError::EmptyInput);
+        }
+    } else if !<C as HexConf>::compact() && !<C as HexConf>::padodd() && hex.len() % 2 == 1 {
+        return Err(ParseHexError::OddLength { len: hex.len() });
+    }
+    for (idx, chr) in hex.iter().enumerate() {
+        intoval(*chr).map_err(|e| e.shift(pfx + idx))?;
+    }
+    match <C as HexConf>::width() {
+        Some(width) if <C as HexConf>::compact() && hex.len() > width => {
+            let got = hex.len();
+            return Err(ParseHexError::Range {
+                min: 1,
+                max: width,
+                got,
+            });
+        }
+        Some(width) if !<C as HexConf>::compact() && hex.len() != width => {
+            return Err(ParseHexError::size(width, hex.len()));
+        }
+        _ => {}
+    }
+    Ok(ValidatedInfo {
+        decoded_len: decoded_len::<C>(src),
+        has_prefix: pfx > 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use config::{Compact, CompactPfx, Strict, StrictPfx, Width};
+    use types::ParseHexError;
+
+    #[test]
+    fn strict() {
+        let info = validate::<Strict>("00ff").unwrap();
+        assert_eq!(info.decoded_len, 2);
+        assert!(!info.has_prefix);
+        let info = validate::<StrictPfx>("00ff").unwrap();
+        assert!(!info.has_prefix);
+        match validate::<StrictPfx>("0x") {
+            Err(ParseHexError::EmptyInput) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match validate::<Strict>("0x00") {
+            Err(ParseHexError::Char { val: 'x', index: 1 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match validate::<StrictPfx>("0x0") {
+            Err(ParseHexError::OddLength { len: 1 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match validate::<StrictPfx>("0x00g0") {
+            Err(ParseHexError::Char { val: 'g', index: 4 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(validate::<Compact>("f").unwrap().decoded_len, 1);
+        let info = validate::<CompactPfx>("0x123").unwrap();
+        assert_eq!(info.decoded_len, 2);
+        assert!(info.has_prefix);
+        match validate::<CompactPfx>("0x") {
+            Err(ParseHexError::EmptyInput) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+    #[test]
+    fn width() {
+        let info = validate::<Width<6, StrictPfx>>("0x00abcd").unwrap();
+        assert_eq!(info.decoded_len, 3);
+        match validate::<Width<6, StrictPfx>>("0xab") {
+            Err(ParseHexError::Size {
+                expect: 6,
+                actual: 2,
+            }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(validate::<Width<6, CompactPfx>>("0xab").is_ok());
+        match validate::<Width<2, Compact>>("abc") {
+            Err(ParseHexError::Range { max: 2, got: 3, .. }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Deserializer over a hex string, in the style of `serde::de::value`.
+//!
+//! `HexStrDeserializer` decodes its input under a given config and hands the
+//! bytes to the visitor, so any type which deserializes from bytes (via
+//! `visit_bytes`/`visit_byte_buf`, or as a sequence or tuple of `u8`, such as
+//! `Vec<u8>` or `[u8; N]`) can be read from hex in one step.  It doesn't
+//! interpret the bytes any further: a type whose wire format is the hex of
+//! some binary encoding has to decode that encoding in its own visitor, as
+//! below.
+//!
+//! ```rust
+//! # extern crate serde;
+//! # extern crate serde_hex;
+//! # use serde::de::{self, Deserialize, Deserializer, Visitor};
+//! # use serde_hex::value::HexStrDeserializer;
+//! # use serde_hex::StrictPfx;
+//! # use std::fmt;
+//! // a little-endian `u32`, deserialized from its binary encoding.
+//! #[derive(Debug, PartialEq)]
+//! struct Height(u32);
+//!
+//! impl<'de> Deserialize<'de> for Height {
+//!     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+//!         struct HeightVisitor;
+//!         impl<'de> Visitor<'de> for HeightVisitor {
+//!             type Value = Height;
+//!             fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!                 f.write_str("4 bytes")
+//!             }
+//!             fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Height, E> {
+//!                 if v.len() != 4 {
+//!                     return Err(E::invalid_length(v.len(), &self));
+//!                 }
+//!                 let mut buf = [0u8; 4];
+//!                 buf.copy_from_slice(v);
+//!                 Ok(Height(u32::from_le_bytes(buf)))
+//!             }
+//!         }
+//!         deserializer.deserialize_bytes(HeightVisitor)
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let de: HexStrDeserializer<StrictPfx> = HexStrDeserializer::new("0x2a000000");
+//! assert_eq!(Height::deserialize(de).unwrap(), Height(42));
+//! # }
+//! ```
+use alloc::vec::Vec;
+use config::{HexConf, Strict};
+use serde::de::value::SeqDeserializer;
+use serde::de::{self, Deserializer, Visitor};
+use std::marker::PhantomData;
+use utils;
+
+/// Deserializer which decodes a hex string under config `C`, and presents
+/// the resulting bytes to the visitor (via `visit_byte_buf`, or as a
+/// sequence of `u8` for sequences, tuples and tuple structs).
+///
+/// Options are always `Some`, and newtype structs are transparent.  Errors
+/// are reported via `E`, which defaults to `serde::de::value::Error`.
+pub struct HexStrDeserializer<'a, C = Strict, E = de::value::Error> {
+    src: &'a str,
+    marker: PhantomData<(C, E)>,
+}
+
+impl<'a, C, E> HexStrDeserializer<'a, C, E>
+where
+    C: HexConf,
+{
+    /// deserializer over the hex string `src`, decoded under config `C`.
+    pub fn new(src: &'a str) -> Self {
+        HexStrDeserializer {
+            src,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, C, E> HexStrDeserializer<'a, C, E>
+where
+    C: HexConf,
+    E: de::Error,
+{
+    // decode the whole input.
+    fn decode(&self) -> Result<Vec<u8>, E> {
+        let src = self.src.as_bytes();
+        utils::fromhex_vec_with::<C>(src).map_err(|e| e.into_de_error(src))
+    }
+}
+
+impl<'a, C, E> Clone for HexStrDeserializer<'a, C, E> {
+    fn clone(&self) -> Self {
+        HexStrDeserializer {
+            src: self.src,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, 'a, C, E> Deserializer<'de> for HexStrDeserializer<'a, C, E>
+where
+    C: HexConf,
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_byte_buf(self.decode()?)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        let mut seq = SeqDeserializer::new(self.decode()?.into_iter());
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HexStrDeserializer;
+    use config::{CompactPfx, StrictPfx};
+    use serde::de::value::Error;
+    use serde::Deserialize;
+
+    #[test]
+    fn decode() {
+        // `String` accepts utf-8 bytes, and `Option` is always `Some`.
+        let de: HexStrDeserializer<CompactPfx> = HexStrDeserializer::new("0x6869");
+        let text: Option<String> = Deserialize::deserialize(de).unwrap();
+        assert_eq!(text.as_deref(), Some("hi"));
+        let de: HexStrDeserializer<StrictPfx> = HexStrDeserializer::new("0x686");
+        let err: Error = String::deserialize(de).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 3, expected an even number of hex chars"
+        );
+    }
+
+    #[test]
+    fn sequences() {
+        let de: HexStrDeserializer<StrictPfx> = HexStrDeserializer::new("0xdeadbeef");
+        let bytes: [u8; 4] = Deserialize::deserialize(de.clone()).unwrap();
+        assert_eq!(bytes, [0xde, 0xad, 0xbe, 0xef]);
+        let bytes: Vec<u8> = Deserialize::deserialize(de.clone()).unwrap();
+        assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+        let tuple: (u8, u8, u8, u8) = Deserialize::deserialize(de.clone()).unwrap();
+        assert_eq!(tuple, (0xde, 0xad, 0xbe, 0xef));
+        let rslt: Result<[u8; 2], Error> = Deserialize::deserialize(de);
+        assert!(rslt.is_err());
+    }
+}
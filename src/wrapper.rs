@@ -0,0 +1,708 @@
+//! Wrapper types which carry their hex config with them.
+//!
+//! `#[serde(with = "SerHex::<C>")]` only applies to fields of a struct.  The
+//! `Hex` wrapper applies a config at the type level instead, so hex encoding
+//! can be used anywhere a type can appear (e.g. `Vec<Hex<[u8; 32]>>`, or
+//! the values of a map).  `HexString` keeps validated hex text exactly as it
+//! was received, for services which need to pass values through unchanged
+//! (`HexCow` is the same type, borrowing from the input when it can).
+//! `Redacted` masks the middle of sensitive values for log output.
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use config::{HexConf, Strict};
+#[cfg(feature = "alloc")]
+use serde::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::{fmt, str};
+#[cfg(feature = "alloc")]
+use types::ParseHexError;
+use utils;
+#[cfg(feature = "alloc")]
+use validate::validate;
+use {HexDisplay, SerHex};
+
+/// Transparent wrapper which (de)serializes `T` as hex via `SerHex<C>`.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// # use serde_hex::{Hex, StrictPfx};
+/// # fn main() {
+/// let hashes: Vec<Hex<[u8; 2], StrictPfx>> = vec![Hex::new([0xab, 0xcd]), Hex::new([0x00, 0x01])];
+/// let json = serde_json::to_string(&hashes).unwrap();
+/// assert_eq!(json, r#"["0xabcd","0x0001"]"#);
+/// assert_eq!(hashes[0].to_string(), "0xabcd");
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct Hex<T, C = Strict>(pub T, PhantomData<C>);
+
+impl<T, C> Hex<T, C> {
+    /// wrap `inner`.
+    pub const fn new(inner: T) -> Self {
+        Hex(inner, PhantomData)
+    }
+
+    /// unwrap the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, C> From<T> for Hex<T, C> {
+    fn from(inner: T) -> Self {
+        Hex::new(inner)
+    }
+}
+
+impl<T, C> Deref for Hex<T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, C> DerefMut for Hex<T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T, C> AsRef<T> for Hex<T, C> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, C> AsMut<T> for Hex<T, C> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T, C> Serialize for Hex<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        <T as SerHex<C>>::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for Hex<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <T as SerHex<C>>::deserialize(deserializer).map(Hex::new)
+    }
+
+    fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <T as SerHex<C>>::deserialize_in_place(deserializer, &mut place.0)
+    }
+}
+
+impl<T, C> fmt::Display for Hex<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display = HexDisplay {
+            src: &self.0,
+            err: None,
+            conf: PhantomData::<C>,
+        };
+        fmt::Display::fmt(&display, f)
+    }
+}
+
+impl<T, C> str::FromStr for Hex<T, C>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    type Err = T::Error;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        T::from_hex_raw(src).map(Hex::new)
+    }
+}
+
+// the remaining impls are written out by hand, so that they don't
+// require the config to implement anything.
+
+impl<T: fmt::Debug, C> fmt::Debug for Hex<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Hex").field(&self.0).finish()
+    }
+}
+
+impl<T: Clone, C> Clone for Hex<T, C> {
+    fn clone(&self) -> Self {
+        Hex::new(self.0.clone())
+    }
+}
+
+impl<T: Copy, C> Copy for Hex<T, C> {}
+
+impl<T: Default, C> Default for Hex<T, C> {
+    fn default() -> Self {
+        Hex::new(T::default())
+    }
+}
+
+impl<T: PartialEq, C> PartialEq for Hex<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq, C> Eq for Hex<T, C> {}
+
+impl<T: PartialOrd, C> PartialOrd for Hex<T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T: Ord, C> Ord for Hex<T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<T: Hash, C> Hash for Hex<T, C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+/// Hex text which has been validated under config `C`, but is only decoded
+/// on demand.  The original representation (case, prefix, leading zeroes) is
+/// preserved, and is what gets serialized, so values pass through unchanged.
+///
+/// The text is held in `S`, which is a `String` by default.  `HexCow` holds
+/// it in a `Cow<str>` instead, borrowing from the input where possible.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # use serde_hex::{HexString, CompactPfx};
+/// # fn main() {
+/// let hex: HexString<CompactPfx> = "0xABc".parse().unwrap();
+/// assert_eq!(hex.as_str(), "0xABc");
+/// assert_eq!(hex.decode().unwrap(), vec![0x0a, 0xbc]);
+/// assert!("0xabg".parse::<HexString<CompactPfx>>().is_err());
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub struct HexString<C = Strict, S = String> {
+    text: S,
+    decoded_len: usize,
+    conf: PhantomData<C>,
+}
+
+/// Validated hex text which borrows from the input when the format allows
+/// it, and owns a copy otherwise.  Otherwise the same as `HexString`.
+///
+/// Deserialization borrows via `visit_borrowed_str`, so no copy of the text
+/// is made for formats such as `serde_json::from_str` (as long as the text
+/// contains no escapes).  Fields need `#[serde(borrow)]` to take advantage.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// # use serde_hex::{HexCow, StrictPfx};
+/// #[derive(Deserialize)]
+/// struct Tx<'a> {
+///     #[serde(borrow)]
+///     hash: HexCow<'a, StrictPfx>,
+/// }
+///
+/// # fn main() {
+/// let json = r#"{"hash":"0xabcd"}"#;
+/// let tx: Tx = serde_json::from_str(json).unwrap();
+/// assert!(tx.hash.is_borrowed());
+/// assert_eq!(tx.hash.decode().unwrap(), vec![0xab, 0xcd]);
+/// assert_eq!(tx.hash.into_owned().as_str(), "0xabcd");
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub type HexCow<'a, C = Strict> = HexString<C, Cow<'a, str>>;
+
+#[cfg(feature = "alloc")]
+impl<C: HexConf, S: AsRef<str>> HexString<C, S> {
+    /// validate `text` under config `C`, borrowing or taking ownership of it
+    /// as `S` does.
+    pub fn new<T: Into<S>>(text: T) -> Result<Self, ParseHexError> {
+        let text = text.into();
+        let info = validate::<C>(text.as_ref())?;
+        Ok(HexString {
+            text,
+            decoded_len: info.decoded_len,
+            conf: PhantomData,
+        })
+    }
+
+    /// decode the hex text into bytes.  only fails if `C` decodes more
+    /// strictly than it validates.
+    pub fn decode(&self) -> Result<Vec<u8>, ParseHexError> {
+        utils::fromhex_vec_with::<C>(self.as_str().as_bytes())
+    }
+
+    /// decode the hex text as some `T` (which may apply stricter rules, e.g.
+    /// on length, than validation did).
+    pub fn decode_as<T: SerHex<C>>(&self) -> Result<T, T::Error> {
+        T::from_hex_raw(self.as_str())
+    }
+
+    /// number of bytes which the hex text decodes to.
+    pub fn decoded_len(&self) -> usize {
+        self.decoded_len
+    }
+
+    // validate deserialized text, reporting failures via `E`.
+    fn validated<E: de::Error>(text: S) -> Result<Self, E> {
+        match validate::<C>(text.as_ref()) {
+            Ok(info) => Ok(HexString {
+                text,
+                decoded_len: info.decoded_len,
+                conf: PhantomData,
+            }),
+            Err(err) => Err(err.into_de_error::<E>(text.as_ref().as_bytes())),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> HexString<C, S> {
+    /// the hex text, exactly as it was received.
+    pub fn as_str(&self) -> &str {
+        self.text.as_ref()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> HexString<C> {
+    /// unwrap the hex text.
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, C> HexCow<'a, C> {
+    /// whether the hex text is borrowed (rather than owned).
+    pub fn is_borrowed(&self) -> bool {
+        match self.text {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        }
+    }
+
+    /// unwrap the hex text.
+    pub fn into_cow(self) -> Cow<'a, str> {
+        self.text
+    }
+
+    /// copy the hex text (if it is borrowed) into a `HexString`.
+    pub fn into_owned(self) -> HexString<C> {
+        HexString {
+            text: self.text.into_owned(),
+            decoded_len: self.decoded_len,
+            conf: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> From<HexString<C>> for HexCow<'static, C> {
+    fn from(hex: HexString<C>) -> Self {
+        HexString {
+            text: Cow::Owned(hex.text),
+            decoded_len: hex.decoded_len,
+            conf: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: HexConf, S: AsRef<str> + From<String>> str::FromStr for HexString<C, S> {
+    type Err = ParseHexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        HexString::new(String::from(src))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C: HexConf, S: AsRef<str> + From<String>> ::std::convert::TryFrom<String> for HexString<C, S> {
+    type Error = ParseHexError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        HexString::new(text)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> AsRef<str> for HexString<C, S> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> fmt::Display for HexString<C, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> fmt::Debug for HexString<C, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("HexString").field(&self.as_str()).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: Clone> Clone for HexString<C, S> {
+    fn clone(&self) -> Self {
+        HexString {
+            text: self.text.clone(),
+            decoded_len: self.decoded_len,
+            conf: PhantomData,
+        }
+    }
+}
+
+// equality is on the text (borrowed or not), so `0xAB` and `0xab` are
+// distinct values.
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> PartialEq for HexString<C, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> Eq for HexString<C, S> {}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> Hash for HexString<C, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C, S: AsRef<str>> Serialize for HexString<C, S> {
+    fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where
+        Z: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, C: HexConf> Deserialize<'de> for HexString<C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        HexString::validated(String::deserialize(deserializer)?)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de: 'a, 'a, C: HexConf> Deserialize<'de> for HexCow<'a, C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(CowVisitor(PhantomData))
+    }
+}
+
+// borrows the hex text when the deserializer hands out borrowed data.
+#[cfg(feature = "alloc")]
+struct CowVisitor<'a, C>(PhantomData<(&'a (), C)>);
+
+#[cfg(feature = "alloc")]
+impl<'de: 'a, 'a, C> de::Visitor<'de> for CowVisitor<'a, C>
+where
+    C: HexConf,
+{
+    type Value = HexCow<'a, C>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        HexString::validated(Cow::Borrowed(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        HexString::validated(Cow::Owned(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        HexString::validated(Cow::Owned(v))
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(v) {
+            Ok(text) => self.visit_borrowed_str(text),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match str::from_utf8(v) {
+            Ok(text) => self.visit_str(text),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+/// Wrapper for sensitive bytes (e.g. key material) which only reveals the
+/// first and last `N` bytes when formatted or serialized, with `…` in place
+/// of the rest (`0xdead…beef`), so values can be told apart in logs without
+/// exposing them.
+///
+/// Only byte types are supported (`T: AsRef<[u8]>`, e.g. arrays, `Vec<u8>`
+/// or byte newtypes); integers and other `SerHex` types aren't.  Output is in
+/// the case and prefix of `C` (compact configs are treated as strict), and
+/// honours the width, fill and alignment of the formatter.  Values of no more
+/// than `2 * N` bytes are masked entirely, and `Debug` is redacted as well.
+///
+/// Serialization writes the masked form, while deserialization reads the
+/// full hex of `T` as `Hex<T, C>` does, so a config can be loaded and then
+/// logged as-is.  Values therefore don't round-trip: the masked output of
+/// `Serialize` is rejected by `Deserialize`.
+///
+/// ```rust
+/// # extern crate serde_hex;
+/// # extern crate serde_json;
+/// # use serde_hex::wrapper::Redacted;
+/// # use serde_hex::{StrictCap, StrictPfx};
+/// # fn main() {
+/// let key: Redacted<[u8; 8], StrictPfx> = serde_json::from_str(r#""0xdeadc0de0000beef""#).unwrap();
+/// assert_eq!(key.to_string(), "0xdead…beef");
+/// assert_eq!(serde_json::to_string(&key).unwrap(), r#""0xdead…beef""#);
+/// assert_eq!(format!("{:?}", key), "Redacted(0xdead…beef)");
+/// let short = Redacted::<_, StrictCap, 1>::new([0xab, 0xcd]);
+/// assert_eq!(short.to_string(), "…");
+/// # }
+/// ```
+pub struct Redacted<T, C = Strict, const N: usize = 2>(pub T, PhantomData<C>);
+
+impl<T, C, const N: usize> Redacted<T, C, N> {
+    /// wrap `inner`.
+    pub const fn new(inner: T) -> Self {
+        Redacted(inner, PhantomData)
+    }
+
+    /// unwrap the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T, C, const N: usize> From<T> for Redacted<T, C, N> {
+    fn from(inner: T) -> Self {
+        Redacted::new(inner)
+    }
+}
+
+impl<T, C, const N: usize> Deref for Redacted<T, C, N> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, C, const N: usize> fmt::Display for Redacted<T, C, N>
+where
+    T: AsRef<[u8]>,
+    C: HexConf,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.0.as_ref();
+        let pfx = C::describe().prefix();
+        let masked = bytes.len() <= N * 2;
+        let len = pfx.len() + if masked { 1 } else { N * 4 + 1 };
+        utils::pad_with(f, len, |f| {
+            f.write_str(pfx)?;
+            if masked {
+                return f.write_str("…");
+            }
+            redacted_hex::<C>(f, &bytes[..N])?;
+            f.write_str("…")?;
+            redacted_hex::<C>(f, &bytes[bytes.len() - N..])
+        })
+    }
+}
+
+// write `bytes` as hex in the case of `C`, through a small (scrubbed) stack
+// buffer.
+fn redacted_hex<C: HexConf>(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    let mut buf = [0u8; 64];
+    let rslt = bytes.chunks(buf.len() / 2).try_for_each(|chunk| {
+        let hex = &mut buf[..chunk.len() * 2];
+        if C::withcap() {
+            utils::intohexcaps(hex, chunk);
+        } else {
+            utils::intohex(hex, chunk);
+        }
+        f.write_str(str::from_utf8(hex).map_err(|_| fmt::Error)?)
+    });
+    utils::scrub(&mut buf);
+    rslt
+}
+
+impl<T, C, const N: usize> fmt::Debug for Redacted<T, C, N>
+where
+    T: AsRef<[u8]>,
+    C: HexConf,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Redacted")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+impl<T, C, const N: usize> Serialize for Redacted<T, C, N>
+where
+    T: AsRef<[u8]>,
+    C: HexConf,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T, C, const N: usize> Deserialize<'de> for Redacted<T, C, N>
+where
+    C: HexConf,
+    T: SerHex<C>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <T as SerHex<C>>::deserialize(deserializer).map(Redacted::new)
+    }
+}
+
+impl<T: Clone, C, const N: usize> Clone for Redacted<T, C, N> {
+    fn clone(&self) -> Self {
+        Redacted::new(self.0.clone())
+    }
+}
+
+impl<T: Copy, C, const N: usize> Copy for Redacted<T, C, N> {}
+
+impl<T: Default, C, const N: usize> Default for Redacted<T, C, N> {
+    fn default() -> Self {
+        Redacted::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hex, HexCow, HexString};
+    use config::{CompactPfx, StrictPfx};
+    use types::ParseHexError;
+
+    #[test]
+    fn hex_string() {
+        let hex: HexString<StrictPfx> = "00Ff".parse().unwrap();
+        assert_eq!(hex.as_str(), "00Ff");
+        assert_eq!(hex.decoded_len(), 2);
+        assert_eq!(hex.decode().unwrap(), vec![0x00, 0xff]);
+        assert_eq!(hex.decode_as::<u16>().unwrap(), 0xff);
+        assert!(hex.decode_as::<u32>().is_err());
+        assert_eq!(hex.to_string(), "00Ff");
+        match "0xabc".parse::<HexString<StrictPfx>>() {
+            Err(ParseHexError::OddLength { len: 3 }) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let compact: HexString<CompactPfx> = "0xabc".parse().unwrap();
+        assert_eq!(compact.decode().unwrap(), vec![0x0a, 0xbc]);
+        assert!(compact != "0xABC".parse().unwrap());
+    }
+
+    #[test]
+    fn conversions() {
+        let hex: Hex<u16, CompactPfx> = "0xabc".parse().unwrap();
+        assert_eq!(*hex, 0xabc);
+        assert_eq!(hex.to_string(), "0xabc");
+        assert_eq!(format!("{:?}", hex), "Hex(2748)");
+        let default: Hex<[u8; 4]> = Hex::default();
+        assert_eq!(default.to_string(), "00000000");
+        assert_eq!(default.into_inner(), [0; 4]);
+    }
+
+    #[test]
+    fn redacted() {
+        use super::Redacted;
+        use config::{CompactCapPfx, PfxCap, Strict};
+        let key = Redacted::<_, CompactCapPfx, 3>::new(vec![0x0a; 7]);
+        assert_eq!(key.to_string(), "0x0A0A0A…0A0A0A");
+        let key = Redacted::<_, PfxCap<StrictPfx>, 3>::new(vec![0x0a; 6]);
+        assert_eq!(key.to_string(), "0X…");
+        let key: Redacted<Vec<u8>, Strict, 0> = vec![0xff].into();
+        assert_eq!(format!("{:?}", key), "Redacted(…)");
+        assert_eq!(key.into_inner(), vec![0xff]);
+        let key = Redacted::<_, StrictPfx, 1>::new([0xab, 0xcd, 0xef]);
+        assert_eq!(format!("{:>12}", key), "     0xab…ef");
+        assert_eq!(format!("{:*^9}", key), "*0xab…ef*");
+    }
+
+    #[test]
+    fn hex_cow() {
+        use serde::de::value::{BorrowedStrDeserializer, Error, StringDeserializer};
+        use serde::Deserialize;
+        let de = BorrowedStrDeserializer::<Error>::new("0xABcd");
+        let hex = HexCow::<StrictPfx>::deserialize(de).unwrap();
+        assert!(hex.is_borrowed());
+        assert_eq!(hex.as_str(), "0xABcd");
+        assert_eq!(hex.decode_as::<u16>().unwrap(), 0xabcd);
+        let de = StringDeserializer::<Error>::new("0xabc".to_string());
+        let hex = HexCow::<CompactPfx>::deserialize(de).unwrap();
+        assert!(!hex.is_borrowed());
+        assert_eq!(hex.decoded_len(), 2);
+        let de = BorrowedStrDeserializer::<Error>::new("0xabc");
+        assert!(HexCow::<StrictPfx>::deserialize(de).is_err());
+        let owned: HexCow<StrictPfx> = "00ff".parse::<HexString<StrictPfx>>().unwrap().into();
+        assert_eq!(owned, HexCow::new("00ff").unwrap());
+    }
+}
@@ -1,10 +1,24 @@
 //! Test of `SerHex` functionality with `serde-json`.
+#[macro_use]
 extern crate serde_hex;
 #[macro_use]
 extern crate serde_derive;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+extern crate serde;
 extern crate serde_json;
 
-use serde_hex::{CompactPfx, SerHex, StrictPfx};
+#[cfg(feature = "indexmap")]
+use indexmap::{IndexMap, IndexSet};
+use serde_hex::combinators::{As, HexAs, HexBytes, Same};
+use serde_hex::map_values::HexValues;
+use serde_hex::wrapper::Redacted;
+use serde_hex::{
+    CollectStr, Compact, CompactPfx, Hex, HexString, PfxCap, SerHex, SerHexList, Strict,
+    StrictCapPfx, StrictPfx, Width,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct Foo {
@@ -35,3 +49,469 @@ fn deserialize() {
     };
     assert_eq!(foo, exp);
 }
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Sig {
+    #[serde(with = "SerHex::<StrictPfx>")]
+    sig: [u8; 64],
+}
+
+#[test]
+fn roundtrip_large() {
+    let sig = Sig { sig: [0xab; 64] };
+    let ser = serde_json::to_string(&sig).unwrap();
+    assert_eq!(ser.len(), r#"{"sig":""}"#.len() + 130);
+    let de = serde_json::from_str::<Sig>(&ser).unwrap();
+    assert_eq!(de, sig);
+}
+
+#[cfg(feature = "constant-time")]
+#[test]
+fn roundtrip_consttime() {
+    use serde_hex::StrictCtPfx;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Secret {
+        #[serde(with = "SerHex::<StrictCtPfx>")]
+        key: [u8; 32],
+        #[serde(with = "SerHex::<StrictCtPfx>")]
+        nonce: u64,
+    }
+
+    let secret = Secret {
+        key: [0x5a; 32],
+        nonce: 0xff,
+    };
+    let ser = serde_json::to_string(&secret).unwrap();
+    assert!(ser.contains(r#""nonce":"0x00000000000000ff""#));
+    assert_eq!(serde_json::from_str::<Secret>(&ser).unwrap(), secret);
+    let bad = ser.replace("5a5a", "5a5z");
+    assert!(serde_json::from_str::<Secret>(&bad).is_err());
+}
+
+#[test]
+fn descriptive_errors() {
+    let err = serde_json::from_str::<Foo>(r#"{"bar":"0xabc","bin":"0x00"}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid length"), "{}", err);
+    let bar = "0x0000000000000000000000000000000000000000000000000000000000000000";
+    let ser = format!(r#"{{"bar":"{}","bin":"0x0z"}}"#, bar);
+    let err = serde_json::from_str::<Foo>(&ser).unwrap_err().to_string();
+    assert!(err.contains("invalid value: character `z`"), "{}", err);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Key([u8; 32]);
+impl_newtype_bytearray!(Key, 32);
+impl_serhex_bytearray!(Key, 32, StrictPfx, serde);
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Keys {
+    primary: Key,
+    backups: Vec<Key>,
+}
+
+#[test]
+fn roundtrip_newtype() {
+    let keys = Keys {
+        primary: Key([0x11; 32]),
+        backups: vec![Key([0x22; 32]), Key([0x33; 32])],
+    };
+    let ser = serde_json::to_string(&keys).unwrap();
+    let hex = format!("0x{}", "22".repeat(32));
+    assert!(ser.contains(&hex), "{}", ser);
+    assert_eq!(serde_json::from_str::<Keys>(&ser).unwrap(), keys);
+    assert!(serde_json::from_str::<Key>(r#""0x1234""#).is_err());
+}
+
+define_hex_array!(struct Checksum(4), config = StrictPfx);
+
+#[test]
+fn deserialize_in_place() {
+    use serde::Deserialize;
+    let mut sum = Checksum([0; 4]);
+    let mut de = serde_json::Deserializer::from_str(r#""0xdeadbeef""#);
+    Deserialize::deserialize_in_place(&mut de, &mut sum).unwrap();
+    assert_eq!(sum, Checksum([0xde, 0xad, 0xbe, 0xef]));
+    let mut hex = Hex::<Checksum, CompactPfx>::new(Checksum([0; 4]));
+    let mut de = serde_json::Deserializer::from_str(r#""0xabc""#);
+    Deserialize::deserialize_in_place(&mut de, &mut hex).unwrap();
+    assert_eq!(*hex, Checksum([0, 0, 0x0a, 0xbc]));
+    let mut de = serde_json::Deserializer::from_str(r#""0x12""#);
+    assert!(Deserialize::deserialize_in_place(&mut de, &mut sum).is_err());
+}
+
+serhex_mod!(key_hex, Key, CompactPfx);
+serhex_mod!(pub(crate) count_hex, u32, StrictPfx);
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    #[serde(with = "key_hex")]
+    key: Key,
+    #[serde(with = "count_hex")]
+    count: u32,
+}
+
+#[test]
+fn generated_modules() {
+    let mut key = [0u8; 32];
+    key[31] = 0xab;
+    let entry = Entry {
+        key: Key(key),
+        count: 3,
+    };
+    let ser = serde_json::to_string(&entry).unwrap();
+    assert_eq!(ser, r#"{"key":"0xab","count":"0x00000003"}"#);
+    assert_eq!(serde_json::from_str::<Entry>(&ser).unwrap(), entry);
+}
+
+#[test]
+fn wrapped_containers() {
+    let mut map: BTreeMap<String, Hex<u32, CompactPfx>> = BTreeMap::new();
+    map.insert("a".into(), Hex::new(0x1f));
+    map.insert("b".into(), 0.into());
+    let json = serde_json::to_string(&map).unwrap();
+    assert_eq!(json, r#"{"a":"0x1f","b":"0x0"}"#);
+    let de: BTreeMap<String, Hex<u32, CompactPfx>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de, map);
+    let nested: Vec<Option<Hex<[u8; 2], StrictCapPfx>>> = vec![Some(Hex::new([0xab, 0x01])), None];
+    let json = serde_json::to_string(&nested).unwrap();
+    assert_eq!(json, r#"["0xAB01",null]"#);
+    assert!(serde_json::from_str::<Vec<Hex<[u8; 2], StrictCapPfx>>>(r#"["0xAB"]"#).is_err());
+}
+
+#[test]
+fn passthrough() {
+    let json = r#"["0xABcd","0x0"]"#;
+    let items: Vec<HexString<CompactPfx>> = serde_json::from_str(json).unwrap();
+    assert_eq!(items[0].decode().unwrap(), vec![0xab, 0xcd]);
+    assert_eq!(serde_json::to_string(&items).unwrap(), json);
+    let err = serde_json::from_str::<HexString<StrictPfx>>(r#""0xabc""#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid length"), "{}", err);
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct KeyRing {
+    #[serde(with = "SerHexList::<StrictPfx>")]
+    keys: Vec<Key>,
+    #[serde(serialize_with = "SerHexList::<CompactPfx>::serialize")]
+    #[serde(deserialize_with = "SerHexList::<CompactPfx>::deserialize_array")]
+    nonces: [u64; 3],
+}
+
+#[derive(Serialize)]
+struct KeyRef<'a> {
+    #[serde(with = "SerHexList::<StrictPfx>")]
+    keys: &'a [Key],
+}
+
+#[test]
+fn hex_lists() {
+    let ring = KeyRing {
+        keys: vec![Key([0x01; 32]), Key([0xfe; 32])],
+        nonces: [0, 1, 0xabc],
+    };
+    let ser = serde_json::to_string(&ring).unwrap();
+    assert!(
+        ser.ends_with(r#""nonces":["0x0","0x1","0xabc"]}"#),
+        "{}",
+        ser
+    );
+    assert_eq!(serde_json::from_str::<KeyRing>(&ser).unwrap(), ring);
+    let borrowed = KeyRef { keys: &ring.keys };
+    assert!(ser.starts_with(&serde_json::to_string(&borrowed).unwrap()[..40]));
+    let short = r#"{"keys":[],"nonces":["0x0","0x1"]}"#;
+    let err = serde_json::from_str::<KeyRing>(short)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("an array of 3 hex strings"), "{}", err);
+    let long = r#"{"keys":[],"nonces":["0x0","0x1","0x2","0x3"]}"#;
+    assert!(serde_json::from_str::<KeyRing>(long).is_err());
+    let bad = r#"{"keys":["0x00"],"nonces":["0x0","0x1","0x2"]}"#;
+    let err = serde_json::from_str::<KeyRing>(bad)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid length"), "{}", err);
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Blobs {
+    #[serde(with = "HexValues::<CompactPfx>")]
+    sizes: HashMap<String, u32>,
+    #[serde(with = "HexValues::<StrictPfx>")]
+    keys: BTreeMap<u8, Key>,
+}
+
+#[test]
+fn hex_map_values() {
+    let mut blobs = Blobs {
+        sizes: HashMap::new(),
+        keys: BTreeMap::new(),
+    };
+    blobs.sizes.insert("max".into(), 0xabc);
+    blobs.keys.insert(7, Key([0x01; 32]));
+    let ser = serde_json::to_string(&blobs).unwrap();
+    assert!(
+        ser.starts_with(r#"{"sizes":{"max":"0xabc"},"keys":{"7":"0x0101"#),
+        "{}",
+        ser
+    );
+    assert_eq!(serde_json::from_str::<Blobs>(&ser).unwrap(), blobs);
+    let bad = r#"{"sizes":{"max":"0xabz"},"keys":{}}"#;
+    let err = serde_json::from_str::<Blobs>(bad).unwrap_err().to_string();
+    assert!(err.contains("a hex digit at index 4"), "{}", err);
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Nested {
+    #[serde(with = "As::<Option<Vec<HexAs<StrictPfx>>>>")]
+    roots: Option<Vec<[u8; 2]>>,
+    #[serde(with = "As::<Vec<Option<HexAs<StrictCapPfx>>>>")]
+    slots: Vec<Option<[u8; 1]>>,
+    #[serde(with = "As::<BTreeMap<Same, Option<HexBytes<CompactPfx>>>>")]
+    code: BTreeMap<String, Option<Vec<u8>>>,
+}
+
+#[test]
+fn nested_combinators() {
+    let mut code = BTreeMap::new();
+    code.insert("a".to_string(), Some(vec![0x00, 0x0f, 0xab]));
+    code.insert("b".to_string(), None);
+    let nested = Nested {
+        roots: None,
+        slots: vec![None, Some([0xab])],
+        code,
+    };
+    let ser = serde_json::to_string(&nested).unwrap();
+    let exp = r#"{"roots":null,"slots":[null,"0xAB"],"code":{"a":"0xfab","b":null}}"#;
+    assert_eq!(ser, exp);
+    let de = serde_json::from_str::<Nested>(exp).unwrap();
+    assert_eq!(de.code["a"], Some(vec![0x0f, 0xab]));
+    assert_eq!(de.slots, nested.slots);
+    let bad = r#"{"roots":["0xab"],"slots":[],"code":{}}"#;
+    let err = serde_json::from_str::<Nested>(bad).unwrap_err().to_string();
+    assert!(err.contains("invalid length"), "{}", err);
+}
+
+// serializes as a byte string, like many third-party digest types.
+#[derive(Debug, PartialEq, Eq)]
+struct Digest(Vec<u8>);
+
+impl serde::Serialize for Digest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Digest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DigestVisitor;
+        impl<'de> serde::de::Visitor<'de> for DigestVisitor {
+            type Value = Digest;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("bytes")
+            }
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Digest, E> {
+                Ok(Digest(v.to_vec()))
+            }
+        }
+        deserializer.deserialize_byte_buf(DigestVisitor)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Record {
+    name: String,
+    digests: Vec<Digest>,
+    tag: Option<Digest>,
+}
+
+#[test]
+fn hexify() {
+    use serde::{Deserialize, Serialize};
+    use serde_hex::hexify::{HexifyDeserializer, HexifySerializer};
+    let record = Record {
+        name: "abc".to_string(),
+        digests: vec![Digest(vec![0x01, 0xff]), Digest(vec![])],
+        tag: Some(Digest(vec![0xab])),
+    };
+    let mut buf = Vec::new();
+    let mut ser = serde_json::Serializer::new(&mut buf);
+    record
+        .serialize(HexifySerializer::<_, StrictPfx>::new(&mut ser))
+        .unwrap();
+    let ser = String::from_utf8(buf).unwrap();
+    let exp = r#"{"name":"abc","digests":["0x01ff","0x"],"tag":"0xab"}"#;
+    assert_eq!(ser, exp);
+    let mut de = serde_json::Deserializer::from_str(exp);
+    let rec = Record::deserialize(HexifyDeserializer::<_, StrictPfx>::new(&mut de)).unwrap();
+    assert_eq!(rec, record);
+    let bad = r#"{"name":"abc","digests":["0x01f"],"tag":null}"#;
+    let mut de = serde_json::Deserializer::from_str(bad);
+    let err = Record::deserialize(HexifyDeserializer::<_, StrictPfx>::new(&mut de)).unwrap_err();
+    assert!(err.to_string().contains("invalid length"), "{}", err);
+}
+
+// emits bytes which aren't valid utf-8, as a badly broken impl might.
+#[derive(Debug, PartialEq, Serialize)]
+struct Broken(#[serde(with = "SerHex::<Strict>")] Garbage);
+
+#[derive(Debug, PartialEq)]
+struct Garbage;
+
+impl SerHex<Strict> for Garbage {
+    type Error = serde_hex::Error;
+
+    fn into_hex_raw<D: std::io::Write>(&self, mut dst: D) -> Result<(), Self::Error> {
+        dst.write_all(&[0xc3, 0x28])?;
+        Ok(())
+    }
+
+    fn from_hex_raw<S: AsRef<[u8]>>(_: S) -> Result<Self, Self::Error> {
+        Ok(Garbage)
+    }
+}
+
+#[test]
+fn panic_free() {
+    assert!(SerHex::<Strict>::into_hex(&Garbage).is_err());
+    assert!(serde_json::to_string(&Broken(Garbage)).is_err());
+    let wide = <u32 as SerHex<Width<2, CompactPfx>>>::into_hex(&0x1ff);
+    assert!(wide.is_err());
+}
+
+#[test]
+fn from_char_iter() {
+    let raw = "0xde:ad:be:ef";
+    let digits = raw.chars().filter(|c| *c != ':');
+    let val = <[u8; 4] as SerHex<StrictPfx>>::from_hex_chars(digits).unwrap();
+    assert_eq!(val, [0xde, 0xad, 0xbe, 0xef]);
+    let num =
+        <u64 as SerHex<CompactPfx>>::from_hex_chars("0XFF".chars().map(|c| c.to_ascii_lowercase()))
+            .unwrap();
+    assert_eq!(num, 0xff);
+    match <u16 as SerHex<Strict>>::from_hex_chars("ab\u{e9}d".chars()) {
+        Err(serde_hex::Error::Parsing(serde_hex::ParseHexError::Char {
+            val: '\u{e9}',
+            index: 2,
+        })) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn prefix_case() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Legacy {
+        #[serde(with = "SerHex::<PfxCap<StrictCapPfx>>")]
+        upper: u32,
+        #[serde(with = "SerHex::<PfxCap<CompactPfx>>")]
+        lower: u16,
+    }
+
+    let legacy = Legacy {
+        upper: 0xdeadbeef,
+        lower: 0xabc,
+    };
+    let json = serde_json::to_string(&legacy).unwrap();
+    assert_eq!(json, r#"{"upper":"0XDEADBEEF","lower":"0Xabc"}"#);
+    assert_eq!(serde_json::from_str::<Legacy>(&json).unwrap(), legacy);
+    // either form of the prefix is accepted, whatever the config emits.
+    let mixed = r#"{"upper":"0xDEADBEEF","lower":"0Xabc"}"#;
+    assert_eq!(serde_json::from_str::<Legacy>(mixed).unwrap(), legacy);
+    let hex: HexString<StrictPfx> = serde_json::from_str(r#""0XABCD""#).unwrap();
+    assert_eq!(hex.decode().unwrap(), vec![0xab, 0xcd]);
+}
+
+#[test]
+fn collect_str_errors() {
+    #[derive(Serialize)]
+    struct Narrow {
+        #[serde(with = "SerHex::<CollectStr<Width<2, Compact>>>")]
+        val: u32,
+    }
+    let err = serde_json::to_string(&Narrow { val: 0x1234 }).unwrap_err();
+    assert!(err.to_string().contains("does not fit"), "{}", err);
+    let err = serde_json::to_value(&Narrow { val: 0x1234 }).unwrap_err();
+    assert!(err.to_string().contains("does not fit"), "{}", err);
+    let ser = serde_json::to_string(&Narrow { val: 0x12 }).unwrap();
+    assert_eq!(ser, r#"{"val":"12"}"#);
+}
+
+#[test]
+fn redacted_is_one_way() {
+    let key =
+        Redacted::<[u8; 8], StrictPfx, 2>::new([0xde, 0xad, 0xc0, 0xde, 0x00, 0x00, 0xbe, 0xef]);
+    let json = serde_json::to_string(&key).unwrap();
+    assert_eq!(json, r#""0xdead…beef""#);
+    assert!(serde_json::from_str::<Redacted<[u8; 8], StrictPfx, 2>>(&json).is_err());
+    let full = r#""0xdeadc0de0000beef""#;
+    let parsed: Redacted<[u8; 8], StrictPfx, 2> = serde_json::from_str(full).unwrap();
+    assert_eq!(parsed.into_inner(), key.into_inner());
+}
+
+#[test]
+fn interior_mutability() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct State {
+        #[serde(with = "SerHex::<StrictPfx>")]
+        nonce: Cell<u32>,
+        #[serde(with = "SerHex::<CompactPfx>")]
+        balance: RefCell<u64>,
+    }
+
+    let state = State {
+        nonce: Cell::new(7),
+        balance: RefCell::new(0x100),
+    };
+    state.nonce.set(8);
+    *state.balance.borrow_mut() += 1;
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, r#"{"nonce":"0x00000008","balance":"0x101"}"#);
+    assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+    let guard = state.balance.borrow_mut();
+    assert!(serde_json::to_string(&state).is_err());
+    match <RefCell<u64> as SerHex<CompactPfx>>::into_hex(&state.balance) {
+        Err(serde_hex::Error::Borrowed) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    drop(guard);
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn insertion_order() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Registers {
+        #[serde(with = "As::<IndexMap<HexAs<StrictPfx>, HexAs<CompactPfx>>>")]
+        values: IndexMap<[u8; 1], u32>,
+        #[serde(with = "As::<IndexSet<HexAs<StrictPfx>>>")]
+        dirty: IndexSet<[u8; 1]>,
+        #[serde(with = "HexValues::<StrictPfx>")]
+        labels: IndexMap<String, [u8; 1]>,
+    }
+
+    let regs = Registers {
+        values: vec![([0x10], 0xff), ([0x02], 0x1), ([0x08], 0x0)]
+            .into_iter()
+            .collect(),
+        dirty: vec![[0x10], [0x02]].into_iter().collect(),
+        labels: vec![("pc".to_string(), [0x10]), ("acc".to_string(), [0x02])]
+            .into_iter()
+            .collect(),
+    };
+    let json = serde_json::to_string(&regs).unwrap();
+    assert_eq!(
+        json,
+        r#"{"values":{"0x10":"0xff","0x02":"0x1","0x08":"0x0"},"dirty":["0x10","0x02"],"labels":{"pc":"0x10","acc":"0x02"}}"#
+    );
+    let parsed: Registers = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, regs);
+    assert!(parsed.values.keys().eq(regs.values.keys()));
+    assert!(parsed.dirty.iter().eq(regs.dirty.iter()));
+    let dup = json.replace(r#"["0x10","0x02"]"#, r#"["0x10","0x10"]"#);
+    assert!(serde_json::from_str::<Registers>(&dup).is_err());
+}
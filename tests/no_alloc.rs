@@ -0,0 +1,102 @@
+//! Test that `SerHex` serialization/deserialization of fixed-size types
+//! never touches the heap.  Allocations made by the current thread are
+//! counted by a wrapper around the system allocator.
+#[macro_use]
+extern crate serde_hex;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use serde_hex::{CompactPfx, SerHex, SerHexOpt, StrictCapPfx, StrictPfx};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// count the allocations made by `f`.
+fn allocs<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCS.with(Cell::get);
+    f();
+    ALLOCS.with(Cell::get) - before
+}
+
+// large enough to be formatted directly into the serializer.
+struct Blob([u8; 300]);
+
+impl From<[u8; 300]> for Blob {
+    fn from(inner: [u8; 300]) -> Self {
+        Blob(inner)
+    }
+}
+
+impl AsRef<[u8]> for Blob {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl_serhex_bytearray!(Blob, 300);
+
+#[derive(Serialize, Deserialize)]
+struct Fixed {
+    #[serde(with = "SerHex::<StrictPfx>")]
+    key: [u8; 32],
+    #[serde(with = "SerHex::<StrictCapPfx>")]
+    sig: [u8; 64],
+    #[serde(with = "SerHex::<CompactPfx>")]
+    num: u64,
+    #[serde(with = "SerHexOpt::<StrictPfx>")]
+    opt: Option<u32>,
+    #[serde(with = "SerHex::<StrictPfx>")]
+    blob: Blob,
+}
+
+#[test]
+fn fixed_size() {
+    let fixed = Fixed {
+        key: [0xab; 32],
+        sig: [0xcd; 64],
+        num: 0x1234,
+        opt: Some(7),
+        blob: Blob([0xef; 300]),
+    };
+    // sanity check that allocations are actually being counted.
+    assert!(allocs(|| drop(vec![0u8; 4])) > 0);
+    let mut buf = [0u8; 1024];
+    let mut len = 0;
+    let count = allocs(|| {
+        let mut dst = &mut buf[..];
+        serde_json::to_writer(&mut dst, &fixed).unwrap();
+        len = 1024 - dst.len();
+    });
+    assert_eq!(count, 0);
+    let mut rslt = None;
+    let count = allocs(|| {
+        rslt = Some(serde_json::from_slice::<Fixed>(&buf[..len]).unwrap());
+    });
+    assert_eq!(count, 0);
+    let rslt = rslt.unwrap();
+    assert_eq!(rslt.key, fixed.key);
+    assert_eq!(&rslt.sig[..], &fixed.sig[..]);
+    assert_eq!(rslt.num, fixed.num);
+    assert_eq!(rslt.opt, fixed.opt);
+    assert_eq!(&rslt.blob.0[..], &fixed.blob.0[..]);
+}